@@ -1,39 +1,65 @@
 use alloc::boxed::Box;
 use alloc::rc::Rc;
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
 
-use hal_core::constant::PAGE_SIZE;
-use hal_core::module::{Export, Function, FunctionSignature, Instruction, Memory, Module, ModuleId, ValueType};
-use hal_wasm::{WasmExportDescriptor, WasmInstruction};
+use hal_core::module::{ConstExpr, Data, DataMode, Element, ElementMode, Export, Function, GlobalDefinition, GlobalType, FunctionSignature, Import, ImportDescriptor, Instruction, MemoryType, Module, ModuleId, TableType, ValueType};
+use hal_wasm::{WasmConstExpr, WasmDataMode, WasmElementMode, WasmExportDescriptor, WasmFeature, WasmFeatures, WasmImportDescriptor, WasmInstruction, WasmParseError};
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
-pub struct Compiler {}
+/// Lowers parsed wasm modules into the modules the processor runs.
+pub struct Compiler {
+    features: WasmFeatures,
+}
 
 impl Default for Compiler {
     fn default() -> Self {
-        Self {}
+        Self::with_features(WasmFeatures::default())
     }
 }
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub enum CompilationError {
-    PlaceHolder
+    /// The module uses a proposal which is not enabled.
+    FeatureDisabled(WasmFeature),
+    /// The module contains an instruction or constant expression the validator rejects.
+    Invalid,
+    /// The module is malformed, e.g. a name is not UTF-8.
+    Malformed(WasmParseError),
 }
 
 impl core::fmt::Display for CompilationError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            CompilationError::PlaceHolder => todo!()
+            CompilationError::FeatureDisabled(feature) => write!(f, "{} support is not enabled", feature),
+            CompilationError::Invalid => write!(f, "invalid module"),
+            CompilationError::Malformed(error) => write!(f, "{}", error),
         }
     }
 }
 
 
+// modules are validated before they are compiled, so only single constant instructions remain
+fn const_expr(expr: &WasmConstExpr) -> Result<ConstExpr, CompilationError> {
+    ConstExpr::try_from(expr.clone()).map_err(|_| CompilationError::Invalid)
+}
+
+// the parser only accepts UTF-8 names, modules built by hand may still contain others
+fn name(bytes: &[u8]) -> Result<String, CompilationError> {
+    core::str::from_utf8(bytes)
+        .map(|name| name.to_string())
+        .map_err(|_| CompilationError::Malformed(WasmParseError::InvalidUtf8String))
+}
+
 impl Compiler {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Creates a compiler which refuses to lower instructions of proposals disabled in `features`.
+    pub fn with_features(features: WasmFeatures) -> Self {
+        Self { features }
     }
 
     pub fn compile(&self, id: ModuleId, wasm: hal_wasm::WasmModule) -> Result<Module, CompilationError> {
@@ -44,39 +70,30 @@ impl Compiler {
 
         let mut exports: Vec<Rc<Export>> = vec![];
         let mut functions: Vec<Rc<Function>> = vec![];
-        let mut memories: Vec<Rc<Memory>> = vec![];
-
-        // if let ref import_section = wasm.imports {
-        //     for import in import_section {
-        //         let module_name = import.module.clone();
-        //         let field = import.name.clone();
-        //         let func_type = match import.desc {
-        //             WasmImportDescriptor::Func(type_idx) => {
-        //                 let ref func_types = wasm.types else {
-        //                     panic!("not found type_section")
-        //                 };
-        //
-        //                 let Some(func_type) = func_types.get(type_idx as usize) else {
-        //                     panic!("not found func types in type_section")
-        //                 };
-        //
-        //                 func_type.clone()
-        //             }
-        //             WasmImportDescriptor::Table(_) => todo!(),
-        //             WasmImportDescriptor::Memory(_) => todo!(),
-        //         };
-        //
-        //         let func = hal_core::module::Function::External(hal_core::module::ExternalFunction {
-        //             module: std::str::from_utf8(module_name).unwrap().to_string(),
-        //             func: std::str::from_utf8(field).unwrap().to_string(),
-        //             func_type: hal_core::module::FuncType {
-        //                 params: func_type.params.to_vec(),
-        //                 results: func_type.returns.to_vec(),
-        //             },
-        //         });
-        //         functions.push(func);
-        //     }
-        // }
+        let mut memories: Vec<MemoryType> = vec![];
+
+        let imports = wasm.imports.iter()
+            .map(|import| Ok(Import::new(
+                name(&import.module)?,
+                name(&import.name)?,
+                match &import.desc {
+                    WasmImportDescriptor::Func(type_idx) => ImportDescriptor::Function(*type_idx),
+                    WasmImportDescriptor::Table(table) => ImportDescriptor::Table {
+                        element_type: ValueType::from(&table.element_type),
+                        min: table.limits.min,
+                        max: table.limits.max,
+                    },
+                    WasmImportDescriptor::Memory(memory) => ImportDescriptor::Memory {
+                        min: memory.limits.min,
+                        max: memory.limits.max,
+                    },
+                    WasmImportDescriptor::Global(global_type) => ImportDescriptor::Global(GlobalType {
+                        value_type: ValueType::from(&global_type.value_type),
+                        mutable: global_type.mutable,
+                    }),
+                },
+            )))
+            .collect::<Result<_, CompilationError>>()?;
 
         if let ref code_section = wasm.codes {
             for (func_body, type_idx) in code_section.iter().zip(func_type_addrs.into_iter()) {
@@ -88,6 +105,11 @@ impl Compiler {
                     panic!("not found func types in type_section")
                 };
 
+                // modules may be assembled without the parser, which already rejects disabled proposals
+                for instruction in func_body.code.iter() {
+                    self.features.check(instruction).map_err(CompilationError::FeatureDisabled)?;
+                }
+
                 let mut locals: Vec<ValueType> = Vec::with_capacity(func_body.locals.len());
                 for local in func_body.locals.iter() {
                     for _ in 0..local.0 {
@@ -104,7 +126,8 @@ impl Compiler {
                             ),
                             locals.into(),
                             func_body.code.iter()
-                                .map(|i| (i.clone()).into()).collect(),
+                                .map(|i| Instruction::try_from(i.clone()).map_err(|_| CompilationError::Invalid))
+                                .collect::<Result<_, _>>()?,
                         ))
                 )
 
@@ -136,14 +159,20 @@ impl Compiler {
 
         if let ref sections = wasm.exports {
             for export in sections {
-                let name = core::str::from_utf8(&*export.name).unwrap().to_string();
+                let name = name(&export.name)?;
                 match export.desc {
                     WasmExportDescriptor::Func(idx) => {
                         exports.push(Rc::new(Export::function(name, idx)))
                     }
-                    WasmExportDescriptor::Table(_) => todo!(),
-                    WasmExportDescriptor::Memory(_) => todo!(),
-                    WasmExportDescriptor::Global(_) => todo!()
+                    WasmExportDescriptor::Table(idx) => {
+                        exports.push(Rc::new(Export::table(name, idx)))
+                    }
+                    WasmExportDescriptor::Memory(idx) => {
+                        exports.push(Rc::new(Export::memory(name, idx)))
+                    }
+                    WasmExportDescriptor::Global(idx) => {
+                        exports.push(Rc::new(Export::global(name, idx)))
+                    }
                 }
             }
         };
@@ -168,40 +197,70 @@ impl Compiler {
 
         if let ref sections = wasm.memories {
             for memory in sections {
-                let min = memory.limits.min * PAGE_SIZE;
-                let memory = Rc::new(Memory {
-                    data: vec![0; min as usize].into(),
-                    max: memory.limits.max,
-                });
-                memories.push(memory);
+                memories.push(MemoryType { min: memory.limits.min, max: memory.limits.max });
             }
         }
-        //
-        // if let ref sections = wasm.data {
-        //     for data in sections {
-        //         let memory = memories
-        //             .get_mut(data.memory_index as usize)
-        //             .unwrap(); // FIXME
-        //         // .ok_or(Error::Placeholder(data.memory_index))?;
-        //
-        //         let offset = data.offset as usize;
-        //         let init = &data.data;
-        //         if offset + init.len() > memory.data.len() {
-        //             panic!("data is too large to fit in memory");
-        //         }
-        //
-        //         memory.data[offset..offset + init.len()].copy_from_slice(init);
-        //     }
-        // }
 
+        let tables = wasm.tables.iter()
+            .map(|table| TableType { element_type: ValueType::from(&table.element_type), min: table.limits.min, max: table.limits.max })
+            .collect();
+
+        let types = wasm.types.iter()
+            .map(|func_type| FunctionSignature::new(
+                func_type.params.iter().map(ValueType::from).collect(),
+                func_type.returns.iter().map(ValueType::from).collect(),
+            ))
+            .collect();
+
+        let globals = wasm.globals.iter()
+            .map(|global| Ok(GlobalDefinition {
+                global_type: GlobalType {
+                    value_type: ValueType::from(&global.global_type.value_type),
+                    mutable: global.global_type.mutable,
+                },
+                init: const_expr(&global.init)?,
+            }))
+            .collect::<Result<_, CompilationError>>()?;
+
+        let data = wasm.data.iter()
+            .map(|data| Ok(Data {
+                mode: match &data.mode {
+                    WasmDataMode::Passive => DataMode::Passive,
+                    WasmDataMode::Active { memory_index, offset } => DataMode::Active {
+                        memory: *memory_index,
+                        offset: const_expr(offset)?,
+                    },
+                },
+                bytes: data.data.clone(),
+            }))
+            .collect::<Result<_, CompilationError>>()?;
+
+        let elements = wasm.elements.iter()
+            .map(|element| Ok(Element {
+                mode: match &element.mode {
+                    WasmElementMode::Passive => ElementMode::Passive,
+                    WasmElementMode::Active { table_index, offset } => ElementMode::Active {
+                        table: *table_index,
+                        offset: const_expr(offset)?,
+                    },
+                    WasmElementMode::Declarative => ElementMode::Declarative,
+                },
+                init: element.init.iter().map(const_expr).collect::<Result<_, _>>()?,
+            }))
+            .collect::<Result<_, CompilationError>>()?;
 
-        Ok(
-            Module::new(
-                id,
-                exports.into(),
-                functions.into(),
-                memories.into(),
-            )
-        )
+        Ok(Module {
+            id,
+            types,
+            imports,
+            functions: functions.into(),
+            exports: exports.into(),
+            tables,
+            memories: memories.into(),
+            globals,
+            elements,
+            data,
+            start: wasm.start_function,
+        })
     }
 }
\ No newline at end of file
@@ -1 +1,6 @@
-pub const PAGE_SIZE: u32 = 65536; // 64KiB
\ No newline at end of file
+/// The size of a memory page in bytes.
+pub const PAGE_SIZE: u32 = 65536; // 64KiB
+/// The number of pages a 32 bit memory can address.
+pub const MAX_PAGES: u32 = 65536; // 4GiB
+/// The number of elements a table can grow to.
+pub const MAX_TABLE_SIZE: u32 = 10_000_000; // implementation limit, the spec allows up to u32::MAX
@@ -45,6 +45,10 @@ impl Leb128 for u32 {
             if *byte == 0x00 && idx > 0 {
                 return Err(InvalidEncoding);
             }
+            // The 5th byte only has room for the upper 4 bits of an u32
+            if shift == 28 && low_bits_of_byte(*byte) > 0x0F {
+                return Err(InvalidEncoding);
+            }
             // Add the lower 7 bits of the byte to the result
             result |= (low_bits_of_byte(*byte) as u32) << shift;
             // If the most significant bit (MSB) is not set, we are done
@@ -68,11 +72,6 @@ impl Leb128 for i32 {
         let mut shift = 0;
 
         for (idx, byte) in bytes.as_ref().iter().clone().enumerate() {
-            // 2nd byte onwards can never be 0x00
-            if *byte == 0x00 && idx > 0 {
-                return Err(InvalidEncoding);
-            }
-
             result |= i32::from(low_bits_of_byte(*byte)) << shift;
             shift += 7;
 
@@ -96,6 +95,35 @@ impl Leb128 for i32 {
     }
 }
 
+impl Leb128 for i64 {
+    fn read_leb128(bytes: impl AsRef<[u8]>) -> Result<(Self, usize)> {
+        let mut result = 0i64;
+        let mut shift = 0;
+
+        for (idx, byte) in bytes.as_ref().iter().clone().enumerate() {
+            result |= i64::from(low_bits_of_byte(*byte)) << shift;
+            shift += 7;
+
+            // If the high-order bit is not set, this is the last byte
+            if byte & CONTINUATION_BIT == 0 {
+                // If this was a signed value and the sign bit is set in the final byte
+                if shift < 64 && (SIGN_BIT & byte) == SIGN_BIT {
+                    result |= !0 << shift;
+                }
+
+                return Ok((result, idx + 1));
+            }
+
+            // If we exceed the maximum shift for a 64-bit integer, return an error
+            if shift >= 64 {
+                return Err(InvalidEncoding);
+            }
+        }
+
+        Err(IncompleteEncoding)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::{format, vec};
@@ -140,6 +168,8 @@ mod tests {
             (vec![0x80, 0x80], IncompleteEncoding), // Missing continuation for multi-byte
             (vec![0x80, 0x80, 0x80, 0x80, 0x80], InvalidEncoding), // More than 5 bytes
             (vec![0xFF, 0x00, 0x00, 0x00, 0x00], InvalidEncoding), // Improper use of continuation bits
+            (vec![0x80, 0x80, 0x80, 0x80, 0x10], InvalidEncoding), // Value exceeds 32 bits
+            (vec![0xFF, 0xFF, 0xFF, 0xFF, 0x7F], InvalidEncoding), // Unused bits set
         ] {
             let result = u32::read_leb128(&given);
             assert_eq!(result, Err(expected))
@@ -155,6 +185,9 @@ mod tests {
             (vec![0x7F], -1, 1),
             (vec![0x00], 0, 1),
             (vec![0x80, 0x01], 128, 2),
+            (vec![0xc0, 0x00], 64, 2),
+            (vec![0xe4, 0x00], 100, 2),
+            (vec![0xff, 0x00], 127, 2),
             (vec![0xc0, 0xc4, 0x07], 123456, 3),
             (vec![0xff, 0xff, 0xff, 0xff, 0x07], i32::MAX, 5),
             (vec![0xc0, 0xbb, 0x78], -123456, 3),
@@ -175,10 +208,41 @@ mod tests {
             (vec![0x80, 0x80], IncompleteEncoding), // Missing continuation for multi-byte sequence
             (vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF], InvalidEncoding), // Too many bytes for a valid i32
             (vec![0x80, 0x80, 0x80, 0x80, 0x80], InvalidEncoding), // More than 5 bytes, which is invalid for i32
-            (vec![0xFF, 0x00, 0x00, 0x00, 0x00], InvalidEncoding), // Improper use of continuation bits
         ] {
             let result = i32::read_leb128(&given);
             assert_eq!(result, Err(expected), "{:#04X?}", given)
         }
     }
+
+    #[test]
+    fn i64_ok() {
+        for (given, expected, expected_consumption) in [
+            (vec![0x00], 0, 1),
+            (vec![0x01], 1, 1),
+            (vec![0x7F], -1, 1),
+            (vec![0x80, 0x01], 128, 2),
+            (vec![0xc0, 0xbb, 0x78], -123456, 3),
+            (vec![0xff, 0xff, 0xff, 0xff, 0x07], i64::from(i32::MAX), 5),
+            (vec![0x80, 0x80, 0x80, 0x80, 0x78], i64::from(i32::MIN), 5),
+            (vec![0x80, 0x80, 0x80, 0x80, 0x08], 1 << 31, 5),
+            (vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00], i64::MAX, 10),
+            (vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f], i64::MIN, 10),
+        ] {
+            let (result, consumed) = i64::read_leb128(&given).expect(format!(" {:#04X?}", given).as_ref());
+            assert_eq!(result, expected, "expected {} but got {} for {:#04X?}", expected, result, given);
+            assert_eq!(consumed, expected_consumption, "expected to consume {} but got {} for {:#04X?}", expected_consumption, consumed, given);
+        }
+    }
+
+    #[test]
+    fn i64_invalid() {
+        for (given, expected) in [
+            (vec![0x80], IncompleteEncoding), // Incomplete sequence with one byte
+            (vec![0x80, 0x80], IncompleteEncoding), // Missing continuation for multi-byte sequence
+            (vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], InvalidEncoding), // Too many bytes for a valid i64
+        ] {
+            let result = i64::read_leb128(&given);
+            assert_eq!(result, Err(expected), "{:#04X?}", given)
+        }
+    }
 }
@@ -0,0 +1,29 @@
+use alloc::boxed::Box;
+
+use crate::module::{ConstExpr, MemoryAddress};
+
+/// Index of a data segment within a module.
+pub type DataAddress = u32;
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+/// A data segment, bytes which are copied into a memory.
+pub struct Data {
+    /// When and where the bytes are copied.
+    pub mode: DataMode,
+    /// The bytes of this segment.
+    pub bytes: Box<[u8]>,
+}
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+/// How a data segment is used.
+pub enum DataMode {
+    /// Only copied into memory by `memory.init`.
+    Passive,
+    /// Copied into memory `memory` at `offset` during instantiation.
+    Active {
+        /// The memory the segment is copied into.
+        memory: MemoryAddress,
+        /// Evaluates to the address the segment starts at.
+        offset: ConstExpr,
+    },
+}
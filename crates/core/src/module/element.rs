@@ -0,0 +1,31 @@
+use alloc::boxed::Box;
+
+use crate::module::{ConstExpr, TableAddress};
+
+/// Index of an element segment within a module.
+pub type ElementAddress = u32;
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+/// An element segment, references which are copied into a table.
+pub struct Element {
+    /// When and where the references are copied.
+    pub mode: ElementMode,
+    /// Expressions producing the references of this segment.
+    pub init: Box<[ConstExpr]>,
+}
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+/// How an element segment is used.
+pub enum ElementMode {
+    /// Only copied into a table by `table.init`.
+    Passive,
+    /// Copied into table `table` at `offset` during instantiation.
+    Active {
+        /// The table the segment is copied into.
+        table: TableAddress,
+        /// Evaluates to the index the segment starts at.
+        offset: ConstExpr,
+    },
+    /// Only declares references for `ref.func`, never copied into a table.
+    Declarative,
+}
@@ -1,6 +1,7 @@
 use alloc::string::String;
 
 use crate::module::function::FunctionAddress;
+use crate::module::{GlobalAddress, MemoryAddress, TableAddress};
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub struct Export {
@@ -9,6 +10,7 @@ pub struct Export {
 }
 
 impl Export {
+    /// Exports the function at `addr` as `name`.
     pub fn function(name: String, addr: FunctionAddress) -> Self {
         Self {
             name,
@@ -16,6 +18,30 @@ impl Export {
         }
     }
 
+    /// Exports the memory at `addr` as `name`.
+    pub fn memory(name: String, addr: MemoryAddress) -> Self {
+        Self {
+            name,
+            data: ExportData::Memory(addr),
+        }
+    }
+
+    /// Exports the table at `addr` as `name`.
+    pub fn table(name: String, addr: TableAddress) -> Self {
+        Self {
+            name,
+            data: ExportData::Table(addr),
+        }
+    }
+
+    /// Exports the global at `addr` as `name`.
+    pub fn global(name: String, addr: GlobalAddress) -> Self {
+        Self {
+            name,
+            data: ExportData::Global(addr),
+        }
+    }
+
     pub fn name(&self) -> &str {
         self.name.as_ref()
     }
@@ -27,5 +53,12 @@ impl Export {
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub enum ExportData {
+    /// An exported function.
     Function(FunctionAddress),
+    /// An exported global.
+    Global(GlobalAddress),
+    /// An exported memory.
+    Memory(MemoryAddress),
+    /// An exported table.
+    Table(TableAddress),
 }
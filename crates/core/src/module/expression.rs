@@ -0,0 +1,15 @@
+use crate::module::{FunctionAddress, Value, ValueType};
+
+/// An expression which can be evaluated without executing any code, e.g. the offset of a data segment.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub enum ConstExpr {
+    /// Evaluates to the value.
+    Const(Value),
+    /// Evaluates to the value of the global at the index.
+    GlobalGet(u32),
+    /// Evaluates to a reference to the function.
+    RefFunc(FunctionAddress),
+    /// Evaluates to the null reference of the reference type.
+    RefNull(ValueType),
+}
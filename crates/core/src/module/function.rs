@@ -1,13 +1,22 @@
 use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::fmt::{Display, Formatter};
 
-use crate::module::{ValueType, ValueTypes};
+use crate::module::{DisplayValueTypes, Memory, Value, ValueType, ValueTypes};
 use crate::module::instruction::Instruction;
+use crate::Trap;
 
 pub type LocalAddress = u32;
 pub type FunctionAddress = u32;
+/// Index of a function signature within the type section of a module.
+pub type TypeAddress = u32;
+
+/// Callback of a host function, it receives the memory of the calling instance (if it has one) and the arguments.
+pub type HostCallback = dyn Fn(Option<&Memory>, &[Value]) -> Result<Box<[Value]>, Trap>;
 
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq)]
 pub struct FunctionSignature {
     params: ValueTypes,
     results: ValueTypes,
@@ -20,28 +29,86 @@ impl FunctionSignature {
             results,
         }
     }
+
+    /// Returns the types of the parameters.
+    pub fn params(&self) -> &ValueTypes { &self.params }
+
+    /// Returns the types of the results.
+    pub fn results(&self) -> &ValueTypes { &self.results }
+}
+
+impl Display for FunctionSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} -> {}", DisplayValueTypes(&self.params), DisplayValueTypes(&self.results))
+    }
 }
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub enum Function {
-    Local(FunctionLocal)
+    /// A function implemented by the host.
+    Host(FunctionHost),
+    /// A function defined by a module.
+    Local(FunctionLocal),
 }
 
 impl Function {
+    /// Creates a host function which runs `callback` when called.
+    pub fn host(
+        signature: FunctionSignature,
+        callback: impl Fn(Option<&Memory>, &[Value]) -> Result<Box<[Value]>, Trap> + 'static,
+    ) -> Self {
+        Function::Host(FunctionHost {
+            signature,
+            callback: Rc::new(callback),
+        })
+    }
+
     pub fn local(signature: FunctionSignature, locals: ValueTypes, instructions: Box<[Instruction]>) -> Self {
         Function::Local(FunctionLocal {
             signature,
             locals,
-            instructions,
+            instructions: instructions.into(),
         })
     }
+
+    /// Returns the signature of the function.
+    pub fn signature(&self) -> &FunctionSignature {
+        match self {
+            Function::Host(host) => host.signature(),
+            Function::Local(local) => local.signature(),
+        }
+    }
+}
+
+/// A function implemented by the host.
+pub struct FunctionHost {
+    signature: FunctionSignature,
+    callback: Rc<HostCallback>,
+}
+
+impl FunctionHost {
+    /// Returns the signature of the function.
+    pub fn signature(&self) -> &FunctionSignature { &self.signature }
+
+    /// Runs the callback with the memory of the calling instance and the arguments.
+    pub fn call(&self, memory: Option<&Memory>, args: &[Value]) -> Result<Box<[Value]>, Trap> {
+        (self.callback)(memory, args)
+    }
+}
+
+#[cfg(any(test, debug_assertions))]
+impl core::fmt::Debug for FunctionHost {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FunctionHost").field("signature", &self.signature).finish_non_exhaustive()
+    }
 }
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub struct FunctionLocal {
     signature: FunctionSignature,
     locals: ValueTypes,
-    instructions: Box<[Instruction]>,
+    // shared with the call frames running the function
+    instructions: Rc<[Instruction]>,
 }
 
 impl FunctionLocal {
@@ -53,10 +120,14 @@ impl FunctionLocal {
         self.signature.params.len()
     }
 
+    /// Returns the signature of the function.
+    pub fn signature(&self) -> &FunctionSignature { &self.signature }
+
     pub fn parameters(&self) -> &ValueTypes { &self.signature.params }
 
     pub fn locals(&self) -> &[ValueType] { self.locals.as_ref() }
 
-    pub fn instructions(&self) -> &Box<[Instruction]> { &self.instructions }
+    /// Returns the body of the function.
+    pub fn instructions(&self) -> &Rc<[Instruction]> { &self.instructions }
 }
 
@@ -0,0 +1,101 @@
+use core::cell::RefCell;
+
+use crate::module::{ConstExpr, Value, ValueType};
+use crate::{Trap, TrapType};
+
+/// Index of a global within a module, counting imported globals first.
+pub type GlobalAddress = u32;
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq)]
+/// The type of a global.
+pub struct GlobalType {
+    /// The type of the value.
+    pub value_type: ValueType,
+    /// Whether `global.set` may change the value.
+    pub mutable: bool,
+}
+
+/// A global as declared by a module, its value is computed on instantiation.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+pub struct GlobalDefinition {
+    /// The type of the global.
+    pub global_type: GlobalType,
+    /// Evaluates to the initial value.
+    pub init: ConstExpr,
+}
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+/// A global of an instance, holding a value of its type.
+pub struct Global {
+    global_type: GlobalType,
+    value: RefCell<Value>,
+}
+
+impl Global {
+    /// Creates a global holding `value`, fails if `value` does not have the type of the global.
+    pub fn new(global_type: GlobalType, value: Value) -> Result<Self, Trap> {
+        expect_type(&global_type, &value)?;
+        Ok(Self {
+            global_type,
+            value: RefCell::new(value),
+        })
+    }
+
+    /// Returns the type of the global.
+    pub fn global_type(&self) -> &GlobalType {
+        &self.global_type
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> Value {
+        self.value.borrow().clone()
+    }
+
+    /// Replaces the value, fails if the global is immutable or `value` has the wrong type.
+    pub fn set(&self, value: Value) -> Result<(), Trap> {
+        if !self.global_type.mutable {
+            return Err(Trap::Type(TrapType::Immutable));
+        }
+        expect_type(&self.global_type, &value)?;
+        *self.value.borrow_mut() = value;
+        Ok(())
+    }
+}
+
+fn expect_type(global_type: &GlobalType, value: &Value) -> Result<(), Trap> {
+    let got = value.value_type();
+    if got != global_type.value_type {
+        return Err(Trap::Type(TrapType::Mismatch(global_type.value_type.clone(), got)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_and_set() {
+        let global = Global::new(GlobalType { value_type: ValueType::I32, mutable: true }, Value::I32(1)).unwrap();
+        assert_eq!(global.get(), Value::I32(1));
+        assert_eq!(global.set(Value::I32(2)), Ok(()));
+        assert_eq!(global.get(), Value::I32(2));
+    }
+
+    #[test]
+    fn immutable() {
+        let global = Global::new(GlobalType { value_type: ValueType::I64, mutable: false }, Value::I64(1)).unwrap();
+        assert_eq!(global.set(Value::I64(2)), Err(Trap::Type(TrapType::Immutable)));
+        assert_eq!(global.get(), Value::I64(1));
+    }
+
+    #[test]
+    fn type_mismatch() {
+        let global_type = GlobalType { value_type: ValueType::F32, mutable: true };
+        assert_eq!(Global::new(global_type.clone(), Value::I32(1)).err(), Some(Trap::Type(TrapType::Mismatch(ValueType::F32, ValueType::I32))));
+
+        let global = Global::new(global_type, Value::F32(1.0)).unwrap();
+        assert_eq!(global.set(Value::F64(2.0)), Err(Trap::Type(TrapType::Mismatch(ValueType::F32, ValueType::F64))));
+    }
+}
@@ -0,0 +1,77 @@
+use alloc::rc::Rc;
+use alloc::string::String;
+
+use crate::module::{Function, Global, GlobalType, Memory, Table, TypeAddress, ValueType};
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+/// An import of a module, identified by the module and name it is imported from.
+pub struct Import {
+    module: String,
+    name: String,
+    descriptor: ImportDescriptor,
+}
+
+impl Import {
+    /// Creates an import of `name` from `module`.
+    pub fn new(module: String, name: String, descriptor: ImportDescriptor) -> Self {
+        Self {
+            module,
+            name,
+            descriptor,
+        }
+    }
+
+    /// Returns the name of the module the import is taken from.
+    pub fn module(&self) -> &str {
+        self.module.as_ref()
+    }
+
+    /// Returns the name of the imported entity.
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// Returns what kind of entity is imported and which type it must have.
+    pub fn descriptor(&self) -> &ImportDescriptor {
+        &self.descriptor
+    }
+}
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+/// The kind and type of an imported entity.
+pub enum ImportDescriptor {
+    /// A function with the signature at the index of the type section.
+    Function(TypeAddress),
+    /// A table holding references of `element_type` with at least `min` and at most `max` elements.
+    Table {
+        /// The type of the elements.
+        element_type: ValueType,
+        /// The minimum number of elements.
+        min: u32,
+        /// The maximum number of elements, if the table is bounded.
+        max: Option<u32>,
+    },
+    /// A memory with at least `min` and at most `max` pages.
+    Memory {
+        /// The minimum number of pages.
+        min: u32,
+        /// The maximum number of pages, if the memory is bounded.
+        max: Option<u32>,
+    },
+    /// A global of the given type.
+    Global(GlobalType),
+}
+
+/// An entity provided from outside of a module to satisfy one of its imports.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone)]
+pub enum Extern {
+    /// A function.
+    Function(Rc<Function>),
+    /// A table.
+    Table(Rc<Table>),
+    /// A memory.
+    Memory(Rc<Memory>),
+    /// A global.
+    Global(Rc<Global>),
+}
@@ -1,6 +1,6 @@
 use crate::module::function::FunctionAddress;
 use crate::module::memory::MemoryOffset;
-use crate::module::MemoryFlags;
+use crate::module::{MemoryFlags, ValueType};
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(Clone, PartialEq)]
@@ -18,7 +18,8 @@ pub enum Instruction {
 
     Block(u32),
     BlockWithFuncType(u32, u32),
-    BlockWithType(u32, u32),
+    /// `block` with a single result of the given type, the second operand is the position of its `end`.
+    BlockWithType(ValueType, u32),
 
     Br(u32),
     BrIf(u32),
@@ -42,10 +43,35 @@ pub enum Instruction {
     ConstI32(i32),
     ConstI64(i64),
 
+    /// `f32.convert_i32_s`
+    ConvertI32SF32,
+    /// `f64.convert_i32_s`
+    ConvertI32SF64,
+    /// `f32.convert_i32_u`
+    ConvertI32UF32,
+    /// `f64.convert_i32_u`
+    ConvertI32UF64,
+    /// `f32.convert_i64_s`
+    ConvertI64SF32,
+    /// `f64.convert_i64_s`
+    ConvertI64SF64,
+    /// `f32.convert_i64_u`
+    ConvertI64UF32,
+    /// `f64.convert_i64_u`
+    ConvertI64UF64,
+
     CtzI32,
     CtzI64,
 
+    /// `data.drop` of the data segment at the operand.
+    DataDrop(u32),
+
     DemoteF64F32,
+
+    /// `f32.div`
+    DivF32,
+    /// `f64.div`
+    DivF64,
     DivSI32,
     DivUI32,
     DivSI64,
@@ -56,6 +82,9 @@ pub enum Instruction {
     Drop64,
     DropRef,
 
+    /// `elem.drop` of the element segment at the operand.
+    ElemDrop(u32),
+
     Else(u32),
     End,
     EndBlockFrame,
@@ -76,16 +105,6 @@ pub enum Instruction {
     Extend8SI32,
     Extend8SI64,
 
-    ExtendI32SF32,
-    ExtendI32SF64,
-    ExtendI32UF32,
-    ExtendI32UF64,
-
-    ExtendI64SF32,
-    ExtendI64SF64,
-    ExtendI64UF32,
-    ExtendI64UF64,
-
     FloorF32,
     FloorF64,
 
@@ -109,6 +128,13 @@ pub enum Instruction {
     GtUI32,
     GtUI64,
 
+    /// `if` without results, the operand is the position of its `else` or `end`.
+    If(u32),
+    /// `if` typed by the function type at the first operand, the second is the position of its `else` or `end`.
+    IfWithFuncType(u32, u32),
+    /// `if` with a single result of the given type, the second operand is the position of its `else` or `end`.
+    IfWithType(ValueType, u32),
+
     LeF32,
     LeF64,
     LeSI32,
@@ -116,6 +142,105 @@ pub enum Instruction {
     LeUI32,
     LeUI64,
 
+    /// `f32.load`
+    LoadF32 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `f64.load`
+    LoadF64 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i32.load`
+    LoadI32 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i64.load`
+    LoadI64 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i32.load16_s`
+    Load16SI32 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i64.load16_s`
+    Load16SI64 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i32.load16_u`
+    Load16UI32 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i64.load16_u`
+    Load16UI64 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i64.load32_s`
+    Load32SI64 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i64.load32_u`
+    Load32UI64 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i32.load8_s`
+    Load8SI32 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i64.load8_s`
+    Load8SI64 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i32.load8_u`
+    Load8UI32 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+    /// `i64.load8_u`
+    Load8UI64 {
+        /// The alignment exponent of the memory argument.
+        flags: MemoryFlags,
+        /// The static offset added to the address operand.
+        offset: MemoryOffset,
+    },
+
     LocalGet128(u32),
     LocalGet32(u32),
     LocalGet64(u32),
@@ -133,7 +258,8 @@ pub enum Instruction {
 
     Loop(u32),
     LoopWithFuncType(u32, u32),
-    LoopWithType(u32, u32),
+    /// `loop` with a single result of the given type, the second operand is the position of its `end`.
+    LoopWithType(ValueType, u32),
 
     LtF32,
     LtF64,
@@ -148,6 +274,16 @@ pub enum Instruction {
     MemoryInit(u32, u32),
     MemorySize(u32),
 
+    /// `f32.max`
+    MaxF32,
+    /// `f64.max`
+    MaxF64,
+
+    /// `f32.min`
+    MinF32,
+    /// `f64.min`
+    MinF64,
+
     MulF32,
     MulF64,
     MulI32,
@@ -163,6 +299,11 @@ pub enum Instruction {
     NegF32,
     NegF64,
 
+    /// `f32.nearest`
+    NearestF32,
+    /// `f64.nearest`
+    NearestF64,
+
     OrI32,
     OrI64,
 
@@ -173,7 +314,8 @@ pub enum Instruction {
 
     RefFunc(u32),
     RefIsNull,
-    RefNull(u32),
+    /// `ref.null` of the given reference type.
+    RefNull(ValueType),
 
     ReinterpretF32I32,
     ReinterpretF64I64,
@@ -234,6 +376,11 @@ pub enum Instruction {
     TableSet(u32),
     TableSize(u32),
 
+    /// `f32.trunc`
+    TruncF32,
+    /// `f64.trunc`
+    TruncF64,
+
     TruncF32SI32,
     TruncF32SI64,
     TruncF32UI32,
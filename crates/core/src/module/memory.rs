@@ -1,11 +1,24 @@
 use alloc::vec::Vec;
 use core::cell::RefCell;
+use core::ops::Range;
 
+use crate::constant::{MAX_PAGES, PAGE_SIZE};
+use crate::Trap;
 
 pub type MemoryOffset = u32;
 pub type MemoryFlags = u32;
 pub type MemoryAddress = u32;
 
+/// The limits of a memory as declared by a module, every instance allocates a memory of its own.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct MemoryType {
+    /// The initial number of pages.
+    pub min: u32,
+    /// The maximum number of pages, if the memory is bounded.
+    pub max: Option<u32>,
+}
+
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub struct Memory {
     pub data: RefCell<Vec<u8>>,
@@ -14,6 +27,235 @@ pub struct Memory {
 
 
 impl Memory {
+    /// Creates a zeroed memory of `min` pages which can grow up to `max` pages,
+    /// or `None` if `min` exceeds the maximum or can not be allocated.
+    pub fn new(min: u32, max: Option<u32>) -> Option<Self> {
+        if min > MAX_PAGES {
+            return None;
+        }
+        let size = min as usize * PAGE_SIZE as usize;
+        let mut data = Vec::new();
+        data.try_reserve_exact(size).ok()?;
+        data.resize(size, 0);
+        Some(Self {
+            data: RefCell::new(data),
+            max,
+        })
+    }
+
+    /// Returns the current size in pages.
+    pub fn pages(&self) -> u32 {
+        (self.data.borrow().len() / PAGE_SIZE as usize) as u32
+    }
+
+    /// Returns the current size in bytes.
+    pub fn size(&self) -> usize {
+        self.data.borrow().len()
+    }
+
+    /// Returns the number of pages this memory can grow to.
+    pub fn max_pages(&self) -> u32 {
+        self.max.map_or(MAX_PAGES, |max| max.min(MAX_PAGES))
+    }
+
+    /// Grows the memory by `delta` pages and returns the previous size in pages,
+    /// or `None` if the result would exceed the maximum or can not be allocated.
+    pub fn grow(&self, delta: u32) -> Option<u32> {
+        let previous = self.pages();
+        let pages = previous.checked_add(delta).filter(|pages| *pages <= self.max_pages())?;
+
+        let mut data = self.data.borrow_mut();
+        let size = pages as usize * PAGE_SIZE as usize;
+        let additional = size - data.len();
+        data.try_reserve_exact(additional).ok()?;
+        data.resize(size, 0);
+        Some(previous)
+    }
+
+    /// Reads `N` bytes starting at the effective address `addr + offset`.
+    pub fn load<const N: usize>(&self, addr: u32, offset: MemoryOffset) -> Result<[u8; N], Trap> {
+        let data = self.data.borrow();
+        let range = effective_range(data.len(), addr, offset, N)?;
 
+        let mut result = [0u8; N];
+        result.copy_from_slice(&data[range]);
+        Ok(result)
+    }
+
+    /// Writes `bytes` starting at the effective address `addr + offset`.
+    pub fn store<const N: usize>(&self, addr: u32, offset: MemoryOffset, bytes: [u8; N]) -> Result<(), Trap> {
+        let mut data = self.data.borrow_mut();
+        let range = effective_range(data.len(), addr, offset, N)?;
+
+        data[range].copy_from_slice(&bytes);
+        Ok(())
+    }
+
+    /// Copies `bytes` into memory starting at `addr`.
+    pub fn write(&self, addr: u32, bytes: &[u8]) -> Result<(), Trap> {
+        let mut data = self.data.borrow_mut();
+        let range = effective_range(data.len(), addr, 0, bytes.len())?;
+
+        data[range].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Reads `len` bytes starting at `addr`.
+    pub fn read(&self, addr: u32, len: u32) -> Result<Vec<u8>, Trap> {
+        let data = self.data.borrow();
+        let range = effective_range(data.len(), addr, 0, len as usize)?;
+        Ok(data[range].to_vec())
+    }
+
+    /// Copies `len` bytes from `src` to `dest`, the regions may overlap.
+    pub fn copy_within(&self, dest: u32, src: u32, len: u32) -> Result<(), Trap> {
+        let mut data = self.data.borrow_mut();
+        let src = effective_range(data.len(), src, 0, len as usize)?;
+        let dest = effective_range(data.len(), dest, 0, len as usize)?;
+
+        data.copy_within(src, dest.start);
+        Ok(())
+    }
+
+    /// Sets `len` bytes starting at `dest` to `value`.
+    pub fn fill(&self, dest: u32, value: u8, len: u32) -> Result<(), Trap> {
+        let mut data = self.data.borrow_mut();
+        let range = effective_range(data.len(), dest, 0, len as usize)?;
+
+        data[range].fill(value);
+        Ok(())
+    }
 }
 
+// The effective address is computed in 64 bits, so `addr + offset + len` can never wrap around
+fn effective_range(memory_len: usize, addr: u32, offset: MemoryOffset, len: usize) -> Result<Range<usize>, Trap> {
+    let start = addr as u64 + offset as u64;
+    let end = start + len as u64;
+    if end > memory_len as u64 {
+        return Err(Trap::MemoryOutOfBounds);
+    }
+    Ok(start as usize..end as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn memory(size: usize) -> Memory {
+        Memory { data: RefCell::new(vec![0; size]), max: None }
+    }
+
+    #[test]
+    fn new() {
+        let memory = Memory::new(2, Some(3)).unwrap();
+        assert_eq!(memory.pages(), 2);
+        assert_eq!(memory.size(), 2 * PAGE_SIZE as usize);
+        assert!(Memory::new(MAX_PAGES + 1, None).is_none());
+    }
+
+    #[test]
+    fn store_and_load() {
+        let memory = memory(16);
+        memory.store(4, 2, 0x1234_5678u32.to_le_bytes()).unwrap();
+        assert_eq!(memory.load::<4>(6, 0), Ok(0x1234_5678u32.to_le_bytes()));
+        assert_eq!(memory.load::<1>(0, 6), Ok([0x78]));
+    }
+
+    #[test]
+    fn last_bytes() {
+        let memory = memory(16);
+        assert_eq!(memory.load::<8>(8, 0), Ok([0; 8]));
+        assert_eq!(memory.store(0, 15, [0xFF]), Ok(()));
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let memory = memory(16);
+        assert_eq!(memory.load::<8>(9, 0), Err(Trap::MemoryOutOfBounds));
+        assert_eq!(memory.load::<1>(0, 16), Err(Trap::MemoryOutOfBounds));
+        assert_eq!(memory.store(15, 0, [0, 0]), Err(Trap::MemoryOutOfBounds));
+    }
+
+    #[test]
+    fn write() {
+        let memory = memory(16);
+        assert_eq!(memory.write(12, &[1, 2, 3, 4]), Ok(()));
+        assert_eq!(memory.load::<4>(12, 0), Ok([1, 2, 3, 4]));
+        assert_eq!(memory.write(16, &[]), Ok(()));
+        assert_eq!(memory.write(13, &[1, 2, 3, 4]), Err(Trap::MemoryOutOfBounds));
+        assert_eq!(memory.write(17, &[]), Err(Trap::MemoryOutOfBounds));
+    }
+
+    #[test]
+    fn copy_within_overlapping() {
+        let memory = memory(8);
+        memory.write(0, &[1, 2, 3, 4]).unwrap();
+
+        assert_eq!(memory.copy_within(2, 0, 4), Ok(()));
+        assert_eq!(memory.read(0, 8), Ok(vec![1, 2, 1, 2, 3, 4, 0, 0]));
+
+        assert_eq!(memory.copy_within(0, 2, 4), Ok(()));
+        assert_eq!(memory.read(0, 8), Ok(vec![1, 2, 3, 4, 3, 4, 0, 0]));
+    }
+
+    #[test]
+    fn copy_within_out_of_bounds() {
+        let memory = memory(8);
+        memory.write(0, &[1, 2, 3, 4]).unwrap();
+
+        assert_eq!(memory.copy_within(5, 0, 4), Err(Trap::MemoryOutOfBounds));
+        assert_eq!(memory.copy_within(0, 5, 4), Err(Trap::MemoryOutOfBounds));
+        assert_eq!(memory.copy_within(8, 8, 0), Ok(()));
+        assert_eq!(memory.copy_within(9, 0, 0), Err(Trap::MemoryOutOfBounds));
+        assert_eq!(memory.read(0, 8), Ok(vec![1, 2, 3, 4, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn fill() {
+        let memory = memory(8);
+        assert_eq!(memory.fill(2, 0xAA, 3), Ok(()));
+        assert_eq!(memory.read(0, 8), Ok(vec![0, 0, 0xAA, 0xAA, 0xAA, 0, 0, 0]));
+        assert_eq!(memory.fill(6, 0xBB, 3), Err(Trap::MemoryOutOfBounds));
+        assert_eq!(memory.fill(8, 0xBB, 0), Ok(()));
+        assert_eq!(memory.read(6, 2), Ok(vec![0, 0]));
+    }
+
+    #[test]
+    fn effective_address_does_not_wrap() {
+        let memory = memory(16);
+        assert_eq!(memory.load::<4>(u32::MAX, 1), Err(Trap::MemoryOutOfBounds));
+        assert_eq!(memory.load::<4>(1, u32::MAX), Err(Trap::MemoryOutOfBounds));
+        assert_eq!(memory.store(u32::MAX, u32::MAX, [0]), Err(Trap::MemoryOutOfBounds));
+    }
+
+    #[test]
+    fn grow() {
+        let memory = Memory::new(1, None).unwrap();
+        assert_eq!(memory.pages(), 1);
+        assert_eq!(memory.grow(0), Some(1));
+        assert_eq!(memory.grow(2), Some(1));
+        assert_eq!(memory.pages(), 3);
+        assert_eq!(memory.size(), 3 * PAGE_SIZE as usize);
+        assert_eq!(memory.load::<1>(3 * PAGE_SIZE - 1, 0), Ok([0]));
+    }
+
+    #[test]
+    fn grow_respects_max() {
+        let memory = Memory::new(0, Some(2)).unwrap();
+        assert_eq!(memory.grow(3), None);
+        assert_eq!(memory.grow(2), Some(0));
+        assert_eq!(memory.grow(1), None);
+        assert_eq!(memory.pages(), 2);
+    }
+
+    #[test]
+    fn grow_beyond_address_space() {
+        let memory = Memory::new(1, None).unwrap();
+        assert_eq!(memory.max_pages(), MAX_PAGES);
+        assert_eq!(memory.grow(MAX_PAGES), None);
+        assert_eq!(memory.grow(u32::MAX), None);
+        assert_eq!(memory.pages(), 1);
+    }
+}
@@ -1,14 +1,25 @@
+pub use crate::module::data::*;
+pub use crate::module::element::*;
 pub use crate::module::export::*;
+pub use crate::module::expression::*;
 pub use crate::module::function::*;
+pub use crate::module::global::*;
+pub use crate::module::import::*;
 pub use crate::module::instruction::*;
 pub use crate::module::memory::*;
 pub use crate::module::module::*;
+pub use crate::module::table::*;
 pub use crate::module::value::*;
 
 mod value;
 mod function;
+mod global;
 mod instruction;
 mod import;
+mod data;
+mod element;
 mod export;
+mod expression;
 mod memory;
 mod module;
+mod table;
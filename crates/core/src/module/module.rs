@@ -1,30 +1,29 @@
 use alloc::boxed::Box;
 use alloc::rc::Rc;
 
-use crate::module::{Export, Function, Memory};
+use crate::module::{Data, Element, Export, Function, FunctionAddress, FunctionSignature, GlobalDefinition, Import, MemoryType, TableType};
 
 pub type ModuleId = u16;
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub struct Module {
     pub id: ModuleId,
+    /// The function signatures of the type section.
+    pub types: Box<[FunctionSignature]>,
+    /// The imports, in the order of the import section.
+    pub imports: Box<[Import]>,
     pub functions: Box<[Rc<Function>]>,
     pub exports: Box<[Rc<Export>]>,
-    pub memories: Box<[Rc<Memory>]>,
-}
-
-impl Module {
-    pub fn new(
-        id: ModuleId,
-        exports: Box<[Rc<Export>]>,
-        functions: Box<[Rc<Function>]>,
-        memories: Box<[Rc<Memory>]>,
-    ) -> Self {
-        Self {
-            id,
-            functions,
-            exports,
-            memories,
-        }
-    }
+    /// The tables defined by the module, excluding imported ones.
+    pub tables: Box<[TableType]>,
+    /// The memories defined by the module, excluding imported ones.
+    pub memories: Box<[MemoryType]>,
+    /// The globals defined by the module, excluding imported ones.
+    pub globals: Box<[GlobalDefinition]>,
+    /// The element segments.
+    pub elements: Box<[Element]>,
+    /// The data segments.
+    pub data: Box<[Data]>,
+    /// The function run on instantiation, if any.
+    pub start: Option<FunctionAddress>,
 }
@@ -0,0 +1,226 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ops::Range;
+
+use crate::constant::MAX_TABLE_SIZE;
+use crate::module::{Value, ValueType};
+use crate::{Trap, TrapType};
+
+/// Index of a table within a module, counting imported tables first.
+pub type TableAddress = u32;
+
+/// A table as declared by a module, every instance allocates a table of its own.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct TableType {
+    /// The type of the elements.
+    pub element_type: ValueType,
+    /// The initial number of elements.
+    pub min: u32,
+    /// The maximum number of elements, if the table is bounded.
+    pub max: Option<u32>,
+}
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+/// A table of an instance, holding references of its element type.
+pub struct Table {
+    element_type: ValueType,
+    elements: RefCell<Vec<Value>>,
+    max: Option<u32>,
+}
+
+impl Table {
+    /// Creates a table of `min` null references which can grow up to `max` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `element_type` is not a reference type.
+    pub fn new(element_type: ValueType, min: u32, max: Option<u32>) -> Self {
+        let null = Value::null(&element_type).expect("table elements must be references");
+        Self {
+            element_type,
+            elements: RefCell::new(vec![null; min as usize]),
+            max,
+        }
+    }
+
+    /// Returns the type of the elements.
+    pub fn element_type(&self) -> &ValueType {
+        &self.element_type
+    }
+
+    /// Returns the current number of elements.
+    pub fn size(&self) -> u32 {
+        self.elements.borrow().len() as u32
+    }
+
+    /// Returns the declared maximum, if any.
+    pub fn max(&self) -> Option<u32> {
+        self.max
+    }
+
+    /// Returns the number of elements this table can grow to.
+    pub fn max_size(&self) -> u32 {
+        self.max.map_or(MAX_TABLE_SIZE, |max| max.min(MAX_TABLE_SIZE))
+    }
+
+    /// Returns the element at `idx`.
+    pub fn get(&self, idx: u32) -> Result<Value, Trap> {
+        self.elements.borrow().get(idx as usize).cloned().ok_or(Trap::TableOutOfBounds)
+    }
+
+    /// Replaces the element at `idx`, fails if `value` does not have the element type.
+    pub fn set(&self, idx: u32, value: Value) -> Result<(), Trap> {
+        self.expect_type(&value)?;
+        let mut elements = self.elements.borrow_mut();
+        let element = elements.get_mut(idx as usize).ok_or(Trap::TableOutOfBounds)?;
+        *element = value;
+        Ok(())
+    }
+
+    /// Grows the table by `delta` elements set to `init` and returns the previous size,
+    /// or `None` if the result would exceed the maximum.
+    pub fn grow(&self, delta: u32, init: Value) -> Result<Option<u32>, Trap> {
+        self.expect_type(&init)?;
+        let previous = self.size();
+        let Some(size) = previous.checked_add(delta).filter(|size| *size <= self.max_size()) else {
+            return Ok(None);
+        };
+
+        self.elements.borrow_mut().resize(size as usize, init);
+        Ok(Some(previous))
+    }
+
+    /// Sets `len` elements starting at `dest` to `value`.
+    pub fn fill(&self, dest: u32, value: Value, len: u32) -> Result<(), Trap> {
+        self.expect_type(&value)?;
+        let mut elements = self.elements.borrow_mut();
+        let range = element_range(elements.len(), dest, len as usize)?;
+
+        elements[range].fill(value);
+        Ok(())
+    }
+
+    /// Copies `values` into the table starting at `dest`.
+    pub fn write(&self, dest: u32, values: &[Value]) -> Result<(), Trap> {
+        for value in values {
+            self.expect_type(value)?;
+        }
+        let mut elements = self.elements.borrow_mut();
+        let range = element_range(elements.len(), dest, values.len())?;
+
+        elements[range].clone_from_slice(values);
+        Ok(())
+    }
+
+    /// Reads `len` elements starting at `src`.
+    pub fn read(&self, src: u32, len: u32) -> Result<Vec<Value>, Trap> {
+        let elements = self.elements.borrow();
+        let range = element_range(elements.len(), src, len as usize)?;
+        Ok(elements[range].to_vec())
+    }
+
+    /// Copies `len` elements from `src` to `dest`, the regions may overlap.
+    pub fn copy_within(&self, dest: u32, src: u32, len: u32) -> Result<(), Trap> {
+        let mut elements = self.elements.borrow_mut();
+        let src = element_range(elements.len(), src, len as usize)?;
+        let dest = element_range(elements.len(), dest, len as usize)?;
+
+        let values = elements[src].to_vec();
+        elements[dest].clone_from_slice(&values);
+        Ok(())
+    }
+
+    fn expect_type(&self, value: &Value) -> Result<(), Trap> {
+        let got = value.value_type();
+        if got != self.element_type {
+            return Err(Trap::Type(TrapType::Mismatch(self.element_type.clone(), got)));
+        }
+        Ok(())
+    }
+}
+
+fn element_range(table_len: usize, start: u32, len: usize) -> Result<Range<usize>, Trap> {
+    let end = start as u64 + len as u64;
+    if end > table_len as u64 {
+        return Err(Trap::TableOutOfBounds);
+    }
+    Ok(start as usize..end as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn func(addr: u32) -> Value {
+        Value::FuncRef(Some(addr))
+    }
+
+    const NULL: Value = Value::FuncRef(None);
+
+    #[test]
+    fn new_table_is_null() {
+        let table = Table::new(ValueType::ExternRef, 2, None);
+        assert_eq!(table.size(), 2);
+        assert_eq!(table.get(0), Ok(Value::ExternRef(None)));
+        assert_eq!(table.get(2), Err(Trap::TableOutOfBounds));
+    }
+
+    #[test]
+    fn set() {
+        let table = Table::new(ValueType::FuncRef, 2, None);
+        assert_eq!(table.set(1, func(7)), Ok(()));
+        assert_eq!(table.get(1), Ok(func(7)));
+        assert_eq!(table.set(2, func(7)), Err(Trap::TableOutOfBounds));
+        assert_eq!(
+            table.set(0, Value::ExternRef(None)),
+            Err(Trap::Type(TrapType::Mismatch(ValueType::FuncRef, ValueType::ExternRef)))
+        );
+    }
+
+    #[test]
+    fn write_and_read() {
+        let table = Table::new(ValueType::FuncRef, 4, None);
+        assert_eq!(table.write(1, &[func(1), NULL, func(3)]), Ok(()));
+        assert_eq!(table.read(0, 4), Ok(vec![NULL, func(1), NULL, func(3)]));
+        assert_eq!(table.write(4, &[]), Ok(()));
+        assert_eq!(table.write(2, &[func(1), func(2), func(3)]), Err(Trap::TableOutOfBounds));
+        assert_eq!(table.write(u32::MAX, &[func(1)]), Err(Trap::TableOutOfBounds));
+        assert_eq!(table.read(3, 2), Err(Trap::TableOutOfBounds));
+        assert_eq!(table.read(0, 4), Ok(vec![NULL, func(1), NULL, func(3)]));
+    }
+
+    #[test]
+    fn grow() {
+        let table = Table::new(ValueType::FuncRef, 1, Some(3));
+        assert_eq!(table.grow(0, NULL), Ok(Some(1)));
+        assert_eq!(table.grow(1, func(5)), Ok(Some(1)));
+        assert_eq!(table.grow(2, NULL), Ok(None));
+        assert_eq!(table.grow(u32::MAX, NULL), Ok(None));
+        assert_eq!(table.read(0, 2), Ok(vec![NULL, func(5)]));
+    }
+
+    #[test]
+    fn fill() {
+        let table = Table::new(ValueType::FuncRef, 4, None);
+        assert_eq!(table.fill(1, func(2), 2), Ok(()));
+        assert_eq!(table.read(0, 4), Ok(vec![NULL, func(2), func(2), NULL]));
+        assert_eq!(table.fill(3, func(2), 2), Err(Trap::TableOutOfBounds));
+        assert_eq!(table.fill(4, func(2), 0), Ok(()));
+    }
+
+    #[test]
+    fn copy_within_overlapping() {
+        let table = Table::new(ValueType::FuncRef, 4, None);
+        table.write(0, &[func(1), func(2)]).unwrap();
+
+        assert_eq!(table.copy_within(1, 0, 2), Ok(()));
+        assert_eq!(table.read(0, 4), Ok(vec![func(1), func(1), func(2), NULL]));
+
+        assert_eq!(table.copy_within(0, 1, 3), Ok(()));
+        assert_eq!(table.read(0, 4), Ok(vec![func(1), func(2), NULL, NULL]));
+
+        assert_eq!(table.copy_within(2, 0, 3), Err(Trap::TableOutOfBounds));
+    }
+}
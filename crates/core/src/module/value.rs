@@ -1,18 +1,37 @@
 use alloc::boxed::Box;
 use core::fmt::{Display, Formatter};
 
+use crate::module::FunctionAddress;
+
+/// Identifies a host object passed into wasm as an `externref`.
+pub type ExternAddress = u32;
+
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(Clone, PartialEq)]
 pub enum ValueType {
+    /// A 32 bit integer.
     I32,
+    /// A 64 bit integer.
     I64,
+    /// A 32 bit float.
+    F32,
+    /// A 64 bit float.
+    F64,
+    /// A nullable function reference.
+    FuncRef,
+    /// A nullable reference to a host object.
+    ExternRef,
 }
 
 impl Display for ValueType {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             ValueType::I32 => write!(f, "i32"),
-            ValueType::I64 => write!(f, "i64")
+            ValueType::I64 => write!(f, "i64"),
+            ValueType::F32 => write!(f, "f32"),
+            ValueType::F64 => write!(f, "f64"),
+            ValueType::FuncRef => write!(f, "funcref"),
+            ValueType::ExternRef => write!(f, "externref"),
         }
     }
 }
@@ -21,25 +40,88 @@ impl ValueType {
     pub fn to_str(&self) -> &'static str {
         match self {
             ValueType::I32 => "i32",
-            ValueType::I64 => "i64"
+            ValueType::I64 => "i64",
+            ValueType::F32 => "f32",
+            ValueType::F64 => "f64",
+            ValueType::FuncRef => "funcref",
+            ValueType::ExternRef => "externref",
         }
     }
+
+    /// Returns whether values of this type are references.
+    pub fn is_reference(&self) -> bool {
+        matches!(self, ValueType::FuncRef | ValueType::ExternRef)
+    }
 }
 
 pub type ValueTypes = Box<[ValueType]>;
 
+// a list of types as written in the text format, e.g. `[i32 f64]`
+pub(crate) struct DisplayValueTypes<'a>(pub(crate) &'a [ValueType]);
+
+impl Display for DisplayValueTypes<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "[")?;
+        for (idx, value_type) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", value_type)?;
+        }
+        write!(f, "]")
+    }
+}
+
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq)]
 pub enum Value {
+    /// A 32 bit integer.
     I32(i32),
+    /// A 64 bit integer.
     I64(i64),
+    /// A 32 bit float.
+    F32(f32),
+    /// A 64 bit float.
+    F64(f64),
+    /// A reference to a function, `None` being the null reference.
+    FuncRef(Option<FunctionAddress>),
+    /// A reference to a host object, `None` being the null reference.
+    ExternRef(Option<ExternAddress>),
+}
+
+impl Value {
+    /// Returns the type of this value.
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Value::I32(_) => ValueType::I32,
+            Value::I64(_) => ValueType::I64,
+            Value::F32(_) => ValueType::F32,
+            Value::F64(_) => ValueType::F64,
+            Value::FuncRef(_) => ValueType::FuncRef,
+            Value::ExternRef(_) => ValueType::ExternRef,
+        }
+    }
+
+    /// Returns the null reference of a reference type, or `None` for a numeric type.
+    pub fn null(value_type: &ValueType) -> Option<Self> {
+        match value_type {
+            ValueType::FuncRef => Some(Value::FuncRef(None)),
+            ValueType::ExternRef => Some(Value::ExternRef(None)),
+            _ => None,
+        }
+    }
 }
 
 impl Display for Value {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             Value::I32(v) => write!(f, "{}", v),
-            Value::I64(v) => write!(f, "{}", v)
+            Value::I64(v) => write!(f, "{}", v),
+            Value::F32(v) => write!(f, "{}", v),
+            Value::F64(v) => write!(f, "{}", v),
+            Value::FuncRef(Some(addr)) => write!(f, "ref.func {}", addr),
+            Value::ExternRef(Some(addr)) => write!(f, "ref.extern {}", addr),
+            Value::FuncRef(None) | Value::ExternRef(None) => write!(f, "ref.null"),
         }
     }
 }
@@ -56,12 +138,26 @@ impl From<i64> for Value {
     }
 }
 
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::F32(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::F64(value)
+    }
+}
+
 impl core::ops::Add for Value {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
             (Value::I32(left), Value::I32(right)) => Value::I32(left.wrapping_add(right)),
             (Value::I64(left), Value::I64(right)) => Value::I64(left.wrapping_add(right)),
+            (Value::F32(left), Value::F32(right)) => Value::F32(left + right),
+            (Value::F64(left), Value::F64(right)) => Value::F64(left + right),
             _ => panic!("type mismatch"),
         }
     }
@@ -73,6 +169,8 @@ impl core::ops::Mul for Value {
         match (self, rhs) {
             (Value::I32(left), Value::I32(right)) => Value::I32(left.wrapping_mul(right)),
             (Value::I64(left), Value::I64(right)) => Value::I64(left.wrapping_mul(right)),
+            (Value::F32(left), Value::F32(right)) => Value::F32(left * right),
+            (Value::F64(left), Value::F64(right)) => Value::F64(left * right),
             _ => panic!("type mismatch"),
         }
     }
@@ -84,6 +182,8 @@ impl core::ops::Sub for Value {
         match (self, rhs) {
             (Value::I32(left), Value::I32(right)) => Value::I32(left.wrapping_sub(right)),
             (Value::I64(left), Value::I64(right)) => Value::I64(left.wrapping_sub(right)),
+            (Value::F32(left), Value::F32(right)) => Value::F32(left - right),
+            (Value::F64(left), Value::F64(right)) => Value::F64(left - right),
             _ => panic!("type mismatch"),
         }
     }
@@ -106,3 +206,21 @@ impl From<Value> for i64 {
         }
     }
 }
+
+impl From<Value> for f32 {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::F32(value) => value,
+            _ => panic!("type mismatch"),
+        }
+    }
+}
+
+impl From<Value> for f64 {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::F64(value) => value,
+            _ => panic!("type mismatch"),
+        }
+    }
+}
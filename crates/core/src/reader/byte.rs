@@ -141,7 +141,7 @@ impl<'a> ByteReader<'a> {
     ///
     /// A `Result` containing the read `f32` value, or a `ParseError` if the read fails.
     pub fn read_f32(&self) -> Result<f32> {
-        todo!()
+        self.read_u32().map(f32::from_bits)
     }
 
     /// Reads a `u32` value encoded in LEB128 format from the current reader position.
@@ -162,7 +162,7 @@ impl<'a> ByteReader<'a> {
     ///
     /// A `Result` containing the read `f64` value, or a `ParseError` if the read fails.
     pub fn read_f64(&self) -> Result<f64> {
-        todo!()
+        self.read_u64().map(f64::from_bits)
     }
 
 
@@ -197,11 +197,10 @@ impl<'a> ByteReader<'a> {
     ///
     /// A `Result` containing the decoded `i64` value, or a `ParseError` if the read fails.
     pub fn read_leb128_i64(&self) -> Result<i64> {
-        // let (result, consumed) = i64::read_leb128(self.peek_range(5)?)?;
-        // let mut pos = self.pos.borrow_mut();
-        // *pos += consumed;
-        // Ok(result)
-        todo!()
+        let (result, consumed) = i64::read_leb128(self.peek_range(10)?)?;
+        let mut pos = self.pos.borrow_mut();
+        *pos += consumed;
+        Ok(result)
     }
 
     /// Reads a 64-bit unsigned integer (`u64`) from the current reader position.
@@ -393,6 +392,23 @@ mod tests {
         assert_eq!(ti.read_u64().unwrap(), 0x100F0E0D0C0B0A09); // Little-endian: 0x090A0B0C0D0E0F10
     }
 
+    #[test]
+    fn read_f32() {
+        let data: &[u8] = &[0x00, 0x00, 0xC0, 0x3F, 0x01, 0x00, 0xA0, 0x7F];
+        let ti = ByteReader::new(data);
+
+        assert_eq!(ti.read_f32().unwrap(), 1.5);
+        assert_eq!(ti.read_f32().unwrap().to_bits(), 0x7FA00001); // NaN payload is kept
+    }
+
+    #[test]
+    fn read_f64() {
+        let data: &[u8] = &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF8, 0xBF];
+        let ti = ByteReader::new(data);
+
+        assert_eq!(ti.read_f64().unwrap(), -1.5);
+    }
+
     #[test]
     fn read_range() {
         let data: &[u8] = &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
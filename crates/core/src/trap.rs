@@ -1,31 +1,70 @@
 use alloc::string::String;
 use core::fmt::{Display, Formatter, write};
 
-use crate::module::{FunctionAddress, MemoryAddress, ValueType};
+use crate::module::{DisplayValueTypes, DataAddress, ElementAddress, FunctionAddress, GlobalAddress, MemoryAddress, TableAddress, TypeAddress, ValueType, ValueTypes, FunctionSignature};
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(PartialEq)]
 pub enum Trap {
     DivisionByZero(TrapDivisionByZero),
 
+    /// The environment of an instance was dropped while the instance was still in use.
+    EnvironmentDropped,
+
+    /// A function handle was called on an instance of another environment.
+    ForeignFunction,
+
+    /// A host function failed with the message.
+    Host(String),
+
+    /// `call_indirect` found a function with a different signature than expected.
+    IndirectCallTypeMismatch,
+
+    /// A NaN was truncated to an integer.
+    InvalidConversionToInteger,
+
+    /// A memory access exceeded the size of the memory.
+    MemoryOutOfBounds,
+
     NotFound(TrapNotFound),
     NotImplemented(TrapNotImplemented),
 
     Overflow(TrapOverflow),
 
+    /// A table access exceeded the size of the table.
+    TableOutOfBounds,
+
     Type(TrapType),
+
+    /// `call_indirect` used an index beyond the size of the table.
+    UndefinedElement,
     Underflow(TrapUnderflow),
+    /// `call_indirect` found a null reference at the table index.
+    UninitializedElement(u32),
+
+    /// `unreachable` was executed.
+    Unreachable,
 }
 
 impl Display for Trap {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             Trap::DivisionByZero(t) => write!(f, "{}", t),
-            Trap::NotFound(_) => todo!(),
+            Trap::EnvironmentDropped => write!(f, "environment dropped"),
+            Trap::ForeignFunction => write!(f, "function of another environment"),
+            Trap::Host(message) => write!(f, "{}", message),
+            Trap::IndirectCallTypeMismatch => write!(f, "indirect call type mismatch"),
+            Trap::InvalidConversionToInteger => write!(f, "invalid conversion to integer"),
+            Trap::MemoryOutOfBounds => write!(f, "out of bounds memory access"),
+            Trap::NotFound(t) => write!(f, "{}", t),
             Trap::NotImplemented(t) => write!(f, "{}", t),
             Trap::Overflow(t) => write!(f, "{}", t),
+            Trap::TableOutOfBounds => write!(f, "out of bounds table access"),
             Trap::Type(t) => write!(f, "{}", t),
+            Trap::UndefinedElement => write!(f, "undefined element"),
             Trap::Underflow(t) => write!(f, "{}", t),
+            Trap::UninitializedElement(idx) => write!(f, "uninitialized element {}", idx),
+            Trap::Unreachable => write!(f, "unreachable"),
 
         }
     }
@@ -48,12 +87,52 @@ impl Display for TrapDivisionByZero {
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(PartialEq)]
 pub enum TrapNotFound {
+    /// No data segment at the index.
+    Data(DataAddress),
+    /// No element segment at the index.
+    Element(ElementAddress),
+    /// No export of the name.
+    Export(String),
     ExportedFunction(String),
+    /// No global exported under the name.
+    ExportedGlobal(String),
+    /// No memory exported under the name.
+    ExportedMemory(String),
+    /// No table exported under the name.
+    ExportedTable(String),
     Function(String),
     FunctionLocal(FunctionAddress),
+    /// No global at the index.
+    Global(GlobalAddress),
     Memory(MemoryAddress),
     Module(String),
     ReturnValue,
+    /// No table at the index.
+    Table(TableAddress),
+    /// No function signature at the index.
+    Type(TypeAddress),
+}
+
+impl Display for TrapNotFound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TrapNotFound::Data(addr) => write!(f, "unknown data segment {}", addr),
+            TrapNotFound::Element(addr) => write!(f, "unknown elem segment {}", addr),
+            TrapNotFound::Export(name) => write!(f, "unknown export {}", name),
+            TrapNotFound::ExportedFunction(name) => write!(f, "unknown exported function {}", name),
+            TrapNotFound::ExportedGlobal(name) => write!(f, "unknown exported global {}", name),
+            TrapNotFound::ExportedMemory(name) => write!(f, "unknown exported memory {}", name),
+            TrapNotFound::ExportedTable(name) => write!(f, "unknown exported table {}", name),
+            TrapNotFound::Function(name) => write!(f, "unknown function {}", name),
+            TrapNotFound::FunctionLocal(addr) => write!(f, "unknown function {}", addr),
+            TrapNotFound::Global(addr) => write!(f, "unknown global {}", addr),
+            TrapNotFound::Memory(addr) => write!(f, "unknown memory {}", addr),
+            TrapNotFound::Module(name) => write!(f, "unknown module {}", name),
+            TrapNotFound::ReturnValue => write!(f, "missing return value"),
+            TrapNotFound::Table(addr) => write!(f, "unknown table {}", addr),
+            TrapNotFound::Type(addr) => write!(f, "unknown type {}", addr),
+        }
+    }
 }
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
@@ -65,7 +144,11 @@ pub enum TrapNotImplemented {
 impl Display for TrapNotImplemented {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
-            TrapNotImplemented::Instruction(i) => write!(f, "instruction not implemented {:?}", i)
+            #[cfg(any(test, debug_assertions))]
+            TrapNotImplemented::Instruction(i) => write!(f, "instruction not implemented {:?}", i),
+            // instructions only implement Debug in debug builds
+            #[cfg(not(any(test, debug_assertions)))]
+            TrapNotImplemented::Instruction(_) => write!(f, "instruction not implemented"),
         }
     }
 }
@@ -82,7 +165,7 @@ impl Display for TrapOverflow {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             TrapOverflow::Integer => write!(f, "integer overflow"),
-            TrapOverflow::Stack => write!(f, "stack overflow"),
+            TrapOverflow::Stack => write!(f, "call stack exhausted"),
         }
     }
 }
@@ -90,13 +173,23 @@ impl Display for TrapOverflow {
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(PartialEq)]
 pub enum TrapType {
-    Mismatch(ValueType, ValueType)
+    /// A function was invoked with arguments of other types than its parameters, the parameters first.
+    ArgumentMismatch(ValueTypes, ValueTypes),
+    /// `global.set` on an immutable global.
+    Immutable,
+    /// A value of another type than expected, expected first.
+    Mismatch(ValueType, ValueType),
+    /// A function was looked up with another signature than it has, the signature of the function first.
+    SignatureMismatch(FunctionSignature, FunctionSignature),
 }
 
 impl Display for TrapType {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
-            TrapType::Mismatch(expected, got) => write!(f, "expected type {:?}, got {:?}", expected, got)
+            TrapType::ArgumentMismatch(expected, got) => write!(f, "expected arguments {}, got {}", DisplayValueTypes(expected), DisplayValueTypes(got)),
+            TrapType::Immutable => write!(f, "global is immutable"),
+            TrapType::Mismatch(expected, got) => write!(f, "expected type {}, got {}", expected, got),
+            TrapType::SignatureMismatch(expected, got) => write!(f, "expected signature {}, got {}", expected, got),
         }
     }
 }
@@ -1,56 +1,194 @@
 use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::any::Any;
 use alloc::rc::Rc;
 use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 
 use hal_compile::Compiler;
-use hal_core::module::{Module, ModuleId, Value};
-use hal_core::Trap;
-use hal_process::{Process, Processor, Store};
-use crate::{EnvironmentError, Instance};
+use hal_core::module::{Extern, ExternAddress, Function, FunctionSignature, Memory, Module, ModuleId, Value};
+use hal_core::{Trap, TrapNotFound};
+use hal_process::{ExternValue, Functions, Process, Processor, Store};
+use hal_wasm::WasmFeatures;
+use crate::{EnvironmentError, HostRef, Instance, InstanceId};
+use crate::linker::Linker;
 
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub struct Environment {
+    pub(crate) features: WasmFeatures,
     pub(crate) compiler: Compiler,
     pub(crate) processor: Rc<Processor>,
     pub(crate) modules: Vec<Module>,
     pub(crate) instances: Vec<Instance>,
+    pub(crate) names: BTreeMap<String, InstanceId>,
+    pub(crate) functions: Rc<Functions>,
+    // stores whose initialization trapped while their functions are still referenced, e.g. from shared tables
+    pub(crate) failed: Vec<Rc<Store>>,
+    // released slots are `None` and never handed out again, wasm code may still hold their handles
+    pub(crate) host_objects: Vec<Option<Rc<dyn Any>>>,
+    pub(crate) linker: Linker,
 }
 
 
 impl Default for Environment {
     fn default() -> Self {
+        Self::with_features(WasmFeatures::default())
+    }
+}
+
+impl Environment {
+    /// Creates an environment which only loads modules using the proposals enabled in `features`.
+    pub fn with_features(features: WasmFeatures) -> Self {
         Self {
-            compiler: Compiler::default(),
+            compiler: Compiler::with_features(features.clone()),
+            features,
             processor: Rc::new(Processor::default()),
             modules: vec![],
             instances: vec![],
+            names: BTreeMap::new(),
+            functions: Rc::new(Functions::default()),
+            failed: vec![],
+            host_objects: vec![],
+            linker: Linker::default(),
         }
     }
-}
 
-impl Environment {
+    /// Returns the proposals modules loaded into this environment may use.
+    pub fn features(&self) -> &WasmFeatures {
+        &self.features
+    }
+
+    /// Invokes the exported function `name` of the most recently created instance.
     pub fn invoke(&mut self, name: impl Into<String>, args: impl AsRef<[Value]>) -> Result<Box<[Value]>, Trap> {
-        // FIXME handle nothing intantiated yet
-        let len = self.instances.len();
-        let instance = self.instances.get_mut(len - 1).unwrap();
+        let name = name.into();
+        let Some(instance) = self.instances.last_mut() else {
+            return Err(Trap::NotFound(TrapNotFound::ExportedFunction(name)));
+        };
         instance.invoke(name, args)
     }
 
     pub fn instantiate(&mut self, id: ModuleId) -> Result<&mut Instance, EnvironmentError> {
         let module = self.modules.get(id as usize).unwrap();
 
-        let process_state = Store::new(&module).unwrap();
+        let imports = module.imports.iter()
+            .map(|import| self.linker.resolve(module, import, &self.functions))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let process_state = Store::new(module, &imports, self.functions.clone())?;
+        if let Err(e) = process_state.initialize(module) {
+            self.fail(process_state);
+            return Err(e.into());
+        }
+
+        let mut process = Process::new(process_state.clone());
+        if let Some(start) = module.start {
+            if let Err(trap) = self.processor.start(&mut process, start) {
+                drop(process);
+                self.fail(process_state);
+                return Err(trap.into());
+            }
+        }
+
         let instance = Instance {
+            id: self.instances.len() as InstanceId,
             processor: Rc::downgrade(&self.processor),
-            process: Process::new(process_state),
+            process,
         };
 
-
         self.instances.push(instance);
+        Ok(self.instances.last_mut().unwrap())
+    }
+
+    // a failed store is kept while one of its functions can be reached from a live instance, a definition
+    // or another kept store, all the others are dropped
+    fn fail(&mut self, store: Rc<Store>) {
+        let mut referenced = self.instances.iter()
+            .flat_map(|instance| instance.process.state().references())
+            .chain(self.linker.references())
+            .collect::<BTreeSet<_>>();
+        let mut candidates = core::mem::take(&mut self.failed);
+        candidates.push(store);
+        loop {
+            let (kept, rest): (Vec<_>, Vec<_>) = candidates.into_iter()
+                .partition(|store| store.defined_functions().any(|addr| referenced.contains(&addr)));
+            candidates = rest;
+            if kept.is_empty() {
+                break;
+            }
+            for store in kept {
+                referenced.extend(store.references());
+                self.failed.push(store);
+            }
+        }
+    }
 
-        Ok(&mut self.instances[0])
+    /// Returns the instance with `id`, as handed out by [`Instance::id`].
+    pub fn instance(&mut self, id: InstanceId) -> Option<&mut Instance> {
+        self.instances.get_mut(id as usize)
     }
-}
\ No newline at end of file
+
+    /// Returns the instance registered under `name`.
+    pub fn named_instance(&mut self, name: &str) -> Option<&mut Instance> {
+        let id = *self.names.get(name)?;
+        self.instance(id)
+    }
+
+    /// Registers an instance under `name`, its exports satisfy the imports from module `name` of
+    /// modules instantiated afterwards.
+    pub fn register(&mut self, name: impl Into<String>, id: InstanceId) -> Result<(), EnvironmentError> {
+        let name = name.into();
+        let instance = self.instances.get(id as usize).ok_or(EnvironmentError::UnknownInstance(id))?;
+
+        for (export, value) in instance.process.extern_values()? {
+            self.linker.define(name.clone(), export, value);
+        }
+        self.names.insert(name, id);
+        Ok(())
+    }
+
+    /// Makes `value` available to modules importing `name` from `module`, replacing a previous definition.
+    pub fn define(&mut self, module: impl Into<String>, name: impl Into<String>, value: Extern) {
+        let value = match value {
+            Extern::Function(function) => ExternValue::Function(self.functions.host(function)),
+            Extern::Table(table) => ExternValue::Table(table),
+            Extern::Memory(memory) => ExternValue::Memory(memory),
+            Extern::Global(global) => ExternValue::Global(global),
+        };
+        self.linker.define(module.into(), name.into(), value);
+    }
+
+    /// Defines a host function, `callback` receives the memory of the calling instance and the arguments.
+    pub fn define_function(
+        &mut self,
+        module: impl Into<String>,
+        name: impl Into<String>,
+        signature: FunctionSignature,
+        callback: impl Fn(Option<&Memory>, &[Value]) -> Result<Box<[Value]>, Trap> + 'static,
+    ) {
+        self.define(module, name, Extern::Function(Rc::new(Function::host(signature, callback))));
+    }
+
+    /// Hands `object` over to the environment and returns a handle which wasm code can hold as an `externref`.
+    pub fn host_ref<T: 'static>(&mut self, object: T) -> HostRef {
+        let addr = self.host_objects.len() as ExternAddress;
+        self.host_objects.push(Some(Rc::new(object)));
+        HostRef(addr)
+    }
+
+    /// Returns the host object behind `handle`, or `None` if it is not a `T` or has been released.
+    pub fn host_object<T: 'static>(&self, handle: HostRef) -> Option<Rc<T>> {
+        let object = self.host_objects.get(handle.0 as usize)?.as_ref()?;
+        Rc::clone(object).downcast().ok()
+    }
+
+    /// Drops the environment's reference to the host object behind `handle` and returns whether
+    /// there was one.
+    ///
+    /// The handle is not handed out again, so copies of it held by the host or by wasm code resolve to
+    /// no object from now on.
+    pub fn release_host_ref(&mut self, handle: HostRef) -> bool {
+        self.host_objects.get_mut(handle.0 as usize).and_then(Option::take).is_some()
+    }
+}
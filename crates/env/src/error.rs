@@ -1,15 +1,40 @@
 use alloc::string::{String, ToString};
+use core::fmt::{Display, Formatter};
 
 use hal_compile::CompilationError;
 use hal_core::Trap;
+use hal_process::StoreError;
+use hal_validate::ValidationError;
 use hal_wasm::WasmParseError;
 use hal_wat::WatParseError;
 
+use crate::InstanceId;
+
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(PartialEq)]
 pub enum EnvironmentError {
+    /// The module could not be loaded.
     LoadError(LoadError),
+    /// A memory of the initial number of pages could not be allocated.
+    MemoryAllocationFailed(u32),
+    /// Instantiation trapped, e.g. while initializing segments or running the start function.
     Trapped(Trap),
+    /// No instance with the id.
+    UnknownInstance(InstanceId),
+    /// An import could not be satisfied.
+    Unlinkable(LinkError),
+}
+
+impl Display for EnvironmentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EnvironmentError::LoadError(error) => write!(f, "{}", error),
+            EnvironmentError::MemoryAllocationFailed(pages) => write!(f, "failed to allocate memory of {} pages", pages),
+            EnvironmentError::Trapped(trap) => write!(f, "trapped: {}", trap),
+            EnvironmentError::UnknownInstance(id) => write!(f, "unknown instance {}", id),
+            EnvironmentError::Unlinkable(error) => write!(f, "{}", error),
+        }
+    }
 }
 
 impl From<LoadError> for EnvironmentError {
@@ -18,30 +43,77 @@ impl From<LoadError> for EnvironmentError {
     }
 }
 
+impl From<LinkError> for EnvironmentError {
+    fn from(value: LinkError) -> Self {
+        EnvironmentError::Unlinkable(value)
+    }
+}
+
 impl From<Trap> for EnvironmentError {
     fn from(value: Trap) -> Self {
         EnvironmentError::Trapped(value)
     }
 }
 
+impl From<StoreError> for EnvironmentError {
+    fn from(value: StoreError) -> Self {
+        match value {
+            StoreError::MemoryAllocationFailed(pages) => EnvironmentError::MemoryAllocationFailed(pages),
+            StoreError::Trapped(trap) => EnvironmentError::Trapped(trap),
+            error => EnvironmentError::LoadError(LoadError::not_found(error.to_string())),
+        }
+    }
+}
+
 impl From<WatParseError> for EnvironmentError {
     fn from(value: WatParseError) -> Self {
         EnvironmentError::LoadError(value.into())
     }
 }
 
+impl From<ValidationError> for EnvironmentError {
+    fn from(value: ValidationError) -> Self {
+        EnvironmentError::LoadError(value.into())
+    }
+}
+
 impl From<WasmParseError> for EnvironmentError{
     fn from(value: WasmParseError) -> Self {
         EnvironmentError::LoadError(value.into())
     }
 }
 
+/// An import which could not be satisfied, identified by its module and name.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(PartialEq)]
+pub enum LinkError {
+    /// The definition has another type than the import, by module and name.
+    IncompatibleImportType(String, String),
+    /// Nothing is defined under the module and name.
+    UnknownImport(String, String),
+}
+
+impl Display for LinkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LinkError::IncompatibleImportType(module, name) => write!(f, "incompatible import type {}.{}", module, name),
+            LinkError::UnknownImport(module, name) => write!(f, "unknown import {}.{}", module, name),
+        }
+    }
+}
+
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(PartialEq)]
 pub enum LoadError {
+    /// The compiler rejected the module.
     CompilationFailed(String),
+    /// Something the module refers to does not exist.
     NotFound(String),
+    /// The module is invalid.
+    ValidationFailed(ValidationError),
+    /// The binary module is malformed.
     WasmParsingFailed(String),
+    /// The text module is malformed.
     WatParsingFailed(String),
 }
 
@@ -53,7 +125,16 @@ impl From<WatParseError> for LoadError {
 
 impl From<CompilationError> for LoadError {
     fn from(value: CompilationError) -> Self {
-        LoadError::CompilationFailed(value.to_string())
+        match value {
+            CompilationError::Malformed(error) => error.into(),
+            error => LoadError::CompilationFailed(error.to_string()),
+        }
+    }
+}
+
+impl From<ValidationError> for LoadError {
+    fn from(value: ValidationError) -> Self {
+        LoadError::ValidationFailed(value)
     }
 }
 
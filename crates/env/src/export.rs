@@ -0,0 +1,50 @@
+use alloc::boxed::Box;
+use alloc::rc::{Rc, Weak};
+
+use hal_core::module::{FunctionAddress, Global, Memory, Table, Value};
+use hal_core::Trap;
+use hal_process::Processor;
+
+use crate::Instance;
+
+/// An export of an instance, as returned by [`Instance::get_export`].
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone)]
+pub enum Exported {
+    /// A function, called through its handle.
+    Function(Func),
+    /// A table.
+    Table(Rc<Table>),
+    /// A memory.
+    Memory(Rc<Memory>),
+    /// A global.
+    Global(Rc<Global>),
+}
+
+/// Handle to a function of an [`Environment`](crate::Environment), its arguments are checked
+/// against the signature on every call, unlike those of a [`TypedFunc`](crate::TypedFunc).
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone)]
+pub struct Func {
+    pub(crate) addr: FunctionAddress,
+    pub(crate) processor: Weak<Processor>,
+}
+
+impl PartialEq for Func {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr == other.addr && Weak::ptr_eq(&self.processor, &other.processor)
+    }
+}
+
+impl Eq for Func {}
+
+impl Func {
+    /// Calls the function on the stack of `instance` with `args`, returning its results.
+    ///
+    /// `instance` has to belong to the environment the function was exported from, otherwise the
+    /// call traps with [`Trap::ForeignFunction`].
+    pub fn call(&self, instance: &mut Instance, args: impl AsRef<[Value]>) -> Result<Box<[Value]>, Trap> {
+        let processor = instance.owned_processor(&self.processor)?;
+        processor.call(&mut instance.process, self.addr, args)
+    }
+}
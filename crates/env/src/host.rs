@@ -0,0 +1,25 @@
+use hal_core::module::{ExternAddress, Value};
+
+/// Handle to a host object owned by an [`Environment`](crate::Environment).
+///
+/// Wasm code sees the handle as an opaque `externref`, it can be stored in tables and globals
+/// and is resolved back to the host object with [`Environment::host_object`](crate::Environment::host_object).
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct HostRef(pub(crate) ExternAddress);
+
+impl HostRef {
+    /// Returns the handle behind an `externref`, or `None` for `ref.null extern` and non-extern values.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::ExternRef(Some(addr)) => Some(Self(*addr)),
+            _ => None,
+        }
+    }
+}
+
+impl From<HostRef> for Value {
+    fn from(value: HostRef) -> Self {
+        Value::ExternRef(Some(value.0))
+    }
+}
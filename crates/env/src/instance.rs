@@ -1,26 +1,110 @@
 use alloc::boxed::Box;
 use alloc::rc::{Rc, Weak};
 use alloc::string::String;
+use core::marker::PhantomData;
 
-use hal_core::module::{Memory, Value};
+use hal_core::module::{FunctionSignature, Global, GlobalAddress, Memory, Table, TableAddress, Value};
 use hal_core::module::MemoryAddress;
-use hal_core::Trap;
-use hal_process::{Process, Processor};
+use hal_core::{Trap, TrapType};
+use hal_process::{ExternValue, MemoryGrowHook, Process, Processor, TypedValues};
+
+use crate::{Exported, Func, TypedFunc};
+
+/// Identifies an instance within its [`Environment`](crate::Environment).
+pub type InstanceId = u32;
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub struct Instance {
+    pub(crate) id: InstanceId,
     pub(crate) processor: Weak<Processor>,
     pub(crate) process: Process,
 }
 
 impl Instance {
+    /// Returns the id the environment hands out for this instance.
+    pub fn id(&self) -> InstanceId {
+        self.id
+    }
+
     pub fn invoke(&mut self, name: impl Into<String>, args: impl AsRef<[Value]>) -> Result<Box<[Value]>, Trap> {
         // FIXME instead of invoking process directly there should be a mailbox
-        let process = &mut self.process;
-        self.processor.upgrade().unwrap().invoke(process, name, args)
+        let processor = self.processor()?;
+        processor.invoke(&mut self.process, name, args)
+    }
+
+    // the processor is owned by the environment, which normally outlives its instances
+    pub(crate) fn processor(&self) -> Result<Rc<Processor>, Trap> {
+        self.processor.upgrade().ok_or(Trap::EnvironmentDropped)
+    }
+
+    // function handles remember the processor of their environment, an instance of another one would
+    // resolve their address in the wrong function space
+    pub(crate) fn owned_processor(&self, owner: &Weak<Processor>) -> Result<Rc<Processor>, Trap> {
+        if !Weak::ptr_eq(&self.processor, owner) {
+            return Err(Trap::ForeignFunction);
+        }
+        self.processor()
+    }
+
+    /// Looks up the exported function `name` and checks that it takes `P` and returns `R`.
+    pub fn typed_func<P: TypedValues, R: TypedValues>(&self, name: impl Into<String>) -> Result<TypedFunc<P, R>, Trap> {
+        let (addr, function) = self.process.exported_function(name)?;
+
+        // like for arguments, the function decides what is expected and the caller's types are what it got
+        let requested = FunctionSignature::new(P::value_types(), R::value_types());
+        if *function.signature() != requested {
+            return Err(Trap::Type(TrapType::SignatureMismatch(function.signature().clone(), requested)));
+        }
+        Ok(TypedFunc { addr, processor: self.processor.clone(), signature: PhantomData })
+    }
+
+    /// Returns the table at `idx`, counting imported tables first.
+    pub fn table(&self, idx: TableAddress) -> Result<Rc<Table>, Trap> {
+        self.process.table(idx)
     }
 
     pub fn memory(&self, idx: MemoryAddress) -> Result<Rc<Memory>, Trap> {
         self.process.memory(idx)
     }
+
+    /// Returns the global at `idx`, counting imported globals first.
+    pub fn global(&self, idx: GlobalAddress) -> Result<Rc<Global>, Trap> {
+        self.process.global(idx)
+    }
+
+    /// Returns the export `name`, whatever kind of extern it is.
+    pub fn get_export(&self, name: impl Into<String>) -> Result<Exported, Trap> {
+        Ok(match self.process.get_export(name)? {
+            ExternValue::Function(addr) => Exported::Function(Func { addr, processor: self.processor.clone() }),
+            ExternValue::Table(table) => Exported::Table(table),
+            ExternValue::Memory(memory) => Exported::Memory(memory),
+            ExternValue::Global(global) => Exported::Global(global),
+        })
+    }
+
+    /// Returns the global exported as `name`.
+    pub fn exported_global(&self, name: impl Into<String>) -> Result<Rc<Global>, Trap> {
+        self.process.exported_global(name)
+    }
+
+    /// Returns the memory exported as `name`.
+    pub fn exported_memory(&self, name: impl Into<String>) -> Result<Rc<Memory>, Trap> {
+        self.process.exported_memory(name)
+    }
+
+    /// Returns the table exported as `name`.
+    pub fn exported_table(&self, name: impl Into<String>) -> Result<Rc<Table>, Trap> {
+        self.process.exported_table(name)
+    }
+
+    /// Asks `hook` before `memory.grow` grows a memory of this instance.
+    pub fn set_memory_grow_hook(&mut self, hook: impl MemoryGrowHook + 'static) {
+        self.process.set_memory_grow_hook(hook)
+    }
+
+    /// Limits the number of nested wasm calls of invocations, a call beyond it traps with
+    /// "call stack exhausted".
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.process.set_max_call_depth(depth)
+    }
 }
\ No newline at end of file
@@ -6,15 +6,22 @@ extern crate alloc;
 extern crate core;
 
 pub use env::Environment;
-pub use error::{EnvironmentError, LoadError};
-pub use instance::Instance;
+pub use error::{EnvironmentError, LinkError, LoadError};
+pub use export::{Exported, Func};
+pub use host::HostRef;
+pub use instance::{Instance, InstanceId};
 pub use load::{LoadWasm, LoadWat};
 pub use source::{wasm_source, wat_source};
 pub use spawn::{SpawnWasm, SpawnWat};
+pub use typed::TypedFunc;
 
 mod env;
 mod source;
 mod error;
+mod export;
+mod host;
+mod linker;
 mod load;
 mod spawn;
 mod instance;
+mod typed;
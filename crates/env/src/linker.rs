@@ -0,0 +1,78 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use hal_core::module::{FunctionAddress, Import, ImportDescriptor, Module, Value};
+use hal_process::{ExternValue, Functions};
+
+use crate::LinkError;
+
+/// Definitions available to imports, keyed by (module, name).
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Default)]
+pub(crate) struct Linker {
+    definitions: BTreeMap<(String, String), ExternValue>,
+}
+
+impl Linker {
+    pub(crate) fn define(&mut self, module: String, name: String, value: ExternValue) {
+        self.definitions.insert((module, name), value);
+    }
+
+    // the functions the definitions refer to, directly or from a table or global
+    pub(crate) fn references(&self) -> Vec<FunctionAddress> {
+        let mut references = Vec::new();
+        for value in self.definitions.values() {
+            match value {
+                ExternValue::Function(addr) => references.push(*addr),
+                ExternValue::Table(table) => references.extend(table.read(0, table.size()).unwrap_or_default().into_iter().filter_map(function_reference)),
+                ExternValue::Global(global) => references.extend(function_reference(global.get())),
+                ExternValue::Memory(_) => {}
+            }
+        }
+        references
+    }
+
+    pub(crate) fn resolve(&self, module: &Module, import: &Import, functions: &Functions) -> Result<ExternValue, LinkError> {
+        let key = (String::from(import.module()), String::from(import.name()));
+        let Some(value) = self.definitions.get(&key) else {
+            return Err(LinkError::UnknownImport(key.0, key.1));
+        };
+
+        let compatible = match (import.descriptor(), value) {
+            (ImportDescriptor::Function(type_addr), ExternValue::Function(addr)) => {
+                let function = functions.function(*addr).map_err(|_| LinkError::UnknownImport(key.0.clone(), key.1.clone()))?;
+                module.types.get(*type_addr as usize) == Some(function.signature())
+            }
+            (ImportDescriptor::Table { element_type, min, max }, ExternValue::Table(table)) => {
+                table.element_type() == element_type && limits_match(table.size(), table.max(), *min, *max)
+            }
+            (ImportDescriptor::Memory { min, max }, ExternValue::Memory(memory)) => {
+                limits_match(memory.pages(), memory.max, *min, *max)
+            }
+            (ImportDescriptor::Global(global_type), ExternValue::Global(global)) => global.global_type() == global_type,
+            _ => false,
+        };
+
+        if !compatible {
+            return Err(LinkError::IncompatibleImportType(key.0, key.1));
+        }
+        Ok(value.clone())
+    }
+}
+
+// the provided limits must lie within the imported ones, an unbounded import accepts any maximum
+fn limits_match(size: u32, max: Option<u32>, expected_min: u32, expected_max: Option<u32>) -> bool {
+    size >= expected_min && match (max, expected_max) {
+        (_, None) => true,
+        (Some(max), Some(expected_max)) => max <= expected_max,
+        (None, Some(_)) => false,
+    }
+}
+
+fn function_reference(value: Value) -> Option<FunctionAddress> {
+    match value {
+        Value::FuncRef(addr) => addr,
+        _ => None,
+    }
+}
@@ -1,7 +1,7 @@
-use alloc::string::ToString;
 use core::fmt::{Display, Formatter};
 
 use hal_core::module::ModuleId;
+use hal_validate::Validator;
 use hal_wasm::WasmParser;
 use hal_wat::WatParser;
 
@@ -17,13 +17,20 @@ pub trait LoadWat<SOURCE> {
 
 impl Display for LoadError {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        todo!()
+        match self {
+            LoadError::CompilationFailed(reason) => write!(f, "compilation failed: {}", reason),
+            LoadError::NotFound(reason) => write!(f, "not found: {}", reason),
+            LoadError::ValidationFailed(error) => write!(f, "validation failed: {}", error),
+            LoadError::WasmParsingFailed(reason) => write!(f, "failed to parse wasm: {}", reason),
+            LoadError::WatParsingFailed(reason) => write!(f, "failed to parse wat: {}", reason),
+        }
     }
 }
 
 impl<T: AsRef<[u8]>> LoadWasm<wasm_source::Bytes<T>> for Environment {
     fn load(&mut self, source: wasm_source::Bytes<T>) -> Result<ModuleId, LoadError> {
-        let wasm = WasmParser::parse(source.as_ref())?;
+        let wasm = WasmParser::parse_with_features(source.as_ref(), &self.features)?;
+        Validator::validate_with_features(&wasm, &self.features)?;
         let module_id = self.modules.len() as ModuleId;
         let module = self.compiler.compile(module_id, wasm)?;
 
@@ -48,6 +55,9 @@ impl<T: AsRef<str>> LoadWasm<wat_source::String<T>> for Environment {
 mod tests {
     mod wat {
         mod string {
+            use hal_validate::ValidationErrorKind;
+            use hal_wasm::{WasmFeature, WasmFeatures};
+
             use crate::{Environment, LoadError, LoadWasm, wat_source};
 
             #[test]
@@ -64,6 +74,27 @@ mod tests {
                 let result = ti.load(wat_source::string("(module"));
                 assert_eq!(result.err(), Some(LoadError::wat_parsing_failed("expected `)`\n     --> <anon>:1:8\n      |\n    1 | (module\n      |        ^")));
             }
+
+            #[test]
+            fn validation_fails() {
+                let mut ti = Environment::default();
+                let result = ti.load(wat_source::string("(module (func (result i32)))"));
+                assert!(matches!(result.err(), Some(LoadError::ValidationFailed(_))), "Loading an invalid module succeeded");
+            }
+
+            #[test]
+            fn feature_disabled() {
+                let mut ti = Environment::with_features(WasmFeatures::mvp());
+                let result = ti.load(wat_source::string("(module (memory 1) (func (memory.fill (i32.const 0) (i32.const 0) (i32.const 0))))"));
+                assert_eq!(result.err(), Some(LoadError::wasm_parsing_failed("Disabled feature: bulk memory")));
+
+                let result = ti.load(wat_source::string("(module (func (result i32 i32) (i32.const 1) (i32.const 2)))"));
+                assert!(
+                    matches!(result.err(), Some(LoadError::ValidationFailed(e)) if *e.kind() == ValidationErrorKind::FeatureDisabled(WasmFeature::MultiValue)),
+                    "Loading a module with disabled multi-value succeeded"
+                );
+                assert_eq!(ti.features(), &WasmFeatures::mvp());
+            }
         }
     }
 
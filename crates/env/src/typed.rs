@@ -0,0 +1,27 @@
+use alloc::rc::Weak;
+use core::marker::PhantomData;
+
+use hal_core::module::FunctionAddress;
+use hal_core::Trap;
+use hal_process::{Processor, TypedValues};
+
+use crate::Instance;
+
+/// An exported function whose signature was checked against `P` and `R` once, by
+/// [`Instance::typed_func`], so that calls need neither a lookup by name nor boxed values.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+pub struct TypedFunc<P, R> {
+    pub(crate) addr: FunctionAddress,
+    pub(crate) processor: Weak<Processor>,
+    pub(crate) signature: PhantomData<fn(P) -> R>,
+}
+
+impl<P: TypedValues, R: TypedValues> TypedFunc<P, R> {
+    /// Calls the function on the stack of `instance` with `params`, returning its results.
+    ///
+    /// Traps with [`Trap::ForeignFunction`] if `instance` belongs to another environment.
+    pub fn call(&self, instance: &mut Instance, params: P) -> Result<R, Trap> {
+        let processor = instance.owned_processor(&self.processor)?;
+        processor.call_typed(&mut instance.process, self.addr, params)
+    }
+}
@@ -0,0 +1,92 @@
+use hal_core::module::Value::I32;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn br_carries_value() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (result i32)
+                (block (result i32)
+                  (i32.const 1)
+                  (i32.const 42)
+                  (br 0)
+                  (drop)
+                  (i32.const 2)
+                )
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", []).unwrap();
+    assert_eq!(result.as_ref(), [I32(42)]);
+}
+
+#[test]
+fn br_if_nested() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (param i32) (result i32)
+                (block (result i32)
+                  (block
+                    (i32.const 10)
+                    (br_if 1 (local.get 0))
+                    (drop)
+                  )
+                  (i32.const 20)
+                )
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", [I32(1)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(10)]);
+
+    let result = instance.invoke("test", [I32(0)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(20)]);
+}
+
+#[test]
+fn br_to_function_body() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (result i32)
+                (block
+                  (block
+                    (i32.const 7)
+                    (br 2)
+                  )
+                )
+                (i32.const 8)
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", []).unwrap();
+    assert_eq!(result.as_ref(), [I32(7)]);
+}
+
+#[test]
+fn return_unwinds_stack() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (result i32)
+                (i32.const 1)
+                (i64.const 2)
+                (block
+                  (i32.const 3)
+                  (return)
+                )
+                (drop)
+                (drop)
+                (i32.const 4)
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", []).unwrap();
+    assert_eq!(result.as_ref(), [I32(3)]);
+}
@@ -0,0 +1,52 @@
+use hal_core::module::Value::I32;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn selects_label() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (param i32) (result i32)
+                (block
+                  (block
+                    (block
+                      (br_table 0 1 2 (local.get 0))
+                    )
+                    (return (i32.const 100))
+                  )
+                  (return (i32.const 101))
+                )
+                (i32.const 102)
+              )
+            )"#
+    )).unwrap();
+
+    for (arg, expected) in [(0, 100), (1, 101), (2, 102), (3, 102), (-1, 102)] {
+        let result = instance.invoke("test", [I32(arg)]).unwrap();
+        assert_eq!(result.as_ref(), [I32(expected)], "br_table index {}", arg);
+    }
+}
+
+#[test]
+fn carries_value() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (param i32) (result i32)
+                (block (result i32)
+                  (block (result i32)
+                    (br_table 1 0 (i32.const 7) (local.get 0))
+                  )
+                  (drop)
+                  (i32.const 8)
+                )
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", [I32(0)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(7)]);
+
+    let result = instance.invoke("test", [I32(1)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(8)]);
+}
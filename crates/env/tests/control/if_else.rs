@@ -0,0 +1,67 @@
+use hal_core::module::Value::I32;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn with_else() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (param i32) (result i32)
+                (if (result i32) (local.get 0)
+                  (then (i32.const 1))
+                  (else (i32.const 2))
+                )
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", [I32(5)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(1)]);
+
+    let result = instance.invoke("test", [I32(0)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(2)]);
+}
+
+#[test]
+fn without_else() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (param i32) (result i32)
+                (local i32)
+                (local.set 1 (i32.const 10))
+                (if (local.get 0)
+                  (then (local.set 1 (i32.const 20)))
+                )
+                (local.get 1)
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", [I32(1)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(20)]);
+
+    let result = instance.invoke("test", [I32(0)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(10)]);
+}
+
+#[test]
+fn br_out_of_branches() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (param i32) (result i32)
+                (if (result i32) (local.get 0)
+                  (then (br 0 (i32.const 3)) (i32.const 4))
+                  (else (br 1 (i32.const 5)) (i32.const 6))
+                )
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", [I32(1)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(3)]);
+
+    let result = instance.invoke("test", [I32(0)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(5)]);
+}
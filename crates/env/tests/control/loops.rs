@@ -0,0 +1,67 @@
+use hal_core::module::Value::I32;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn sum() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "sum") (param i32) (result i32)
+                (local i32)
+                (loop
+                  (local.set 1 (i32.add (local.get 1) (local.get 0)))
+                  (local.set 0 (i32.add (local.get 0) (i32.const -1)))
+                  (br_if 0 (local.get 0))
+                )
+                (local.get 1)
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("sum", [I32(10)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(55)]);
+}
+
+#[test]
+fn br_out_of_loop() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "count") (param i32) (result i32)
+                (local i32)
+                (block
+                  (loop
+                    (br_if 1 (i32.eqz (local.get 0)))
+                    (local.set 1 (i32.add (local.get 1) (i32.const 1)))
+                    (local.set 0 (i32.add (local.get 0) (i32.const -1)))
+                    (br 0)
+                  )
+                )
+                (local.get 1)
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("count", [I32(0)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(0)]);
+
+    let result = instance.invoke("count", [I32(5)]).unwrap();
+    assert_eq!(result.as_ref(), [I32(5)]);
+}
+
+#[test]
+fn loop_with_result() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (result i32)
+                (loop (result i32)
+                  (select (i32.const 1) (i32.const 2) (i32.const 0))
+                )
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", []).unwrap();
+    assert_eq!(result.as_ref(), [I32(2)]);
+}
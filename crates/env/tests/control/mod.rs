@@ -0,0 +1,6 @@
+mod block;
+mod br_table;
+mod if_else;
+mod loops;
+mod multi_value;
+mod unreachable;
@@ -0,0 +1,91 @@
+use hal_core::module::Value::{I32, I64};
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn block_params_and_results() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (result i32 i32)
+                (i32.const 1)
+                (i32.const 2)
+                (block (param i32 i32) (result i32 i32)
+                  (i32.add)
+                  (i32.const 10)
+                )
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", []).unwrap();
+    assert_eq!(result.as_ref(), [I32(3), I32(10)]);
+}
+
+#[test]
+fn br_carries_values() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (result i32 i32)
+                (i32.const 0)
+                (block (result i32 i32)
+                  (i32.const 1)
+                  (i32.const 2)
+                  (br 0)
+                )
+                (drop)
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", []).unwrap();
+    assert_eq!(result.as_ref(), [I32(0), I32(1)]);
+}
+
+#[test]
+fn if_params() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (param i32) (result i32)
+                (i32.const 6)
+                (i32.const 2)
+                (if (param i32 i32) (result i32) (local.get 0)
+                  (then (i32.add))
+                  (else (i32.sub))
+                )
+              )
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("test", [I32(1)]).unwrap().as_ref(), [I32(8)]);
+    assert_eq!(instance.invoke("test", [I32(0)]).unwrap().as_ref(), [I32(4)]);
+}
+
+#[test]
+fn loop_params() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "fac") (param i64) (result i64)
+                (i64.const 1) (local.get 0)
+                (loop $l (param i64 i64) (result i64)
+                  (call $pick1) (call $pick1) (i64.mul)
+                  (call $pick1) (i64.const 1) (i64.sub)
+                  (call $pick0) (i64.const 0) (i64.gt_u)
+                  (br_if $l)
+                  (drop) (return)
+                )
+              )
+              (func $pick0 (param i64) (result i64 i64)
+                (local.get 0) (local.get 0)
+              )
+              (func $pick1 (param i64 i64) (result i64 i64 i64)
+                (local.get 0) (local.get 1) (local.get 0)
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("fac", [I64(25)]).unwrap();
+    assert_eq!(result.as_ref(), [I64(7034535277573963776)]);
+}
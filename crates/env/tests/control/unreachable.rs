@@ -0,0 +1,20 @@
+use hal_core::Trap;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn traps() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "test") (result i32)
+                (block (result i32)
+                  (unreachable)
+                )
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", []);
+    assert_eq!(result, Err(Trap::Unreachable));
+    assert_eq!(format!("{}", Trap::Unreachable), "unreachable");
+}
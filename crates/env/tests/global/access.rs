@@ -0,0 +1,45 @@
+use hal_core::module::Value::{F32, I32};
+use hal_core::module::ValueType;
+use hal_core::{Trap, TrapNotFound, TrapType};
+use hal_env::{Environment, SpawnWat, wat_source};
+
+const MODULE: &str = r#"(module
+  (global $counter (export "counter") (mut i32) (i32.const 0))
+  (global (export "half") f32 (f32.const 2.5))
+  (func (export "inc") (result i32)
+    (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+    (global.get $counter)
+  )
+)"#;
+
+#[test]
+fn read_exported() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    assert_eq!(instance.exported_global("half").unwrap().get(), F32(2.5));
+    assert!(instance.invoke("inc", []).is_ok());
+    assert_eq!(instance.exported_global("counter").unwrap().get(), I32(1));
+    assert_eq!(instance.global(0).unwrap().get(), I32(1));
+}
+
+#[test]
+fn write_exported() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    let counter = instance.exported_global("counter").unwrap();
+    assert_eq!(counter.set(I32(41)), Ok(()));
+    assert_eq!(instance.invoke("inc", []).unwrap().as_ref(), [I32(42)]);
+
+    assert_eq!(counter.set(F32(1.0)), Err(Trap::Type(TrapType::Mismatch(ValueType::I32, ValueType::F32))));
+    assert_eq!(instance.exported_global("half").unwrap().set(F32(3.0)), Err(Trap::Type(TrapType::Immutable)));
+}
+
+#[test]
+fn not_a_global() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    assert_eq!(instance.exported_global("inc").err(), Some(Trap::NotFound(TrapNotFound::ExportedGlobal("inc".to_string()))));
+}
@@ -0,0 +1,65 @@
+use std::rc::Rc;
+
+use hal_core::module::{Extern, Global, GlobalType, ValueType};
+use hal_core::module::Value::{F64, I32, I64};
+use hal_env::{Environment, EnvironmentError, LoadError, SpawnWat, wat_source};
+use hal_validate::{ValidationError, ValidationErrorKind};
+
+#[test]
+fn get_and_set() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (global $a i32 (i32.const 40))
+              (global $b (mut i64) (i64.const -1))
+              (global $c f64 (f64.const 1.5))
+              (func (export "get-a") (result i32) (global.get $a))
+              (func (export "get-b") (result i64) (global.get $b))
+              (func (export "get-c") (result f64) (global.get $c))
+              (func (export "inc-b") (global.set $b (i64.add (global.get $b) (i64.const 1))))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("get-a", []).unwrap().as_ref(), [I32(40)]);
+    assert_eq!(instance.invoke("get-b", []).unwrap().as_ref(), [I64(-1)]);
+    assert_eq!(instance.invoke("get-c", []).unwrap().as_ref(), [F64(1.5)]);
+
+    assert!(instance.invoke("inc-b", []).is_ok());
+    assert!(instance.invoke("inc-b", []).is_ok());
+    assert_eq!(instance.invoke("get-b", []).unwrap().as_ref(), [I64(1)]);
+}
+
+#[test]
+fn initialized_from_global() {
+    let mut env = Environment::default();
+    let offset = Global::new(GlobalType { value_type: ValueType::I32, mutable: false }, I32(8)).unwrap();
+    env.define("env", "offset", Extern::Global(Rc::new(offset)));
+
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (global $offset (import "env" "offset") i32)
+              (memory 1)
+              (global $copy i32 (global.get $offset))
+              (data (global.get $offset) "\2a")
+              (func (export "get") (result i32) (global.get $copy))
+              (func (export "load") (result i32) (i32.load8_u (i32.const 8)))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("get", []).unwrap().as_ref(), [I32(8)]);
+    assert_eq!(instance.invoke("load", []).unwrap().as_ref(), [I32(42)]);
+}
+
+#[test]
+fn set_immutable() {
+    let mut env = Environment::default();
+    let result = env.spawn(wat_source::string(
+        r#"(module
+              (global $a i32 (i32.const 40))
+              (func (export "set") (global.set $a (i32.const 1)))
+            )"#
+    ));
+
+    let invalid = ValidationError::Function { function: 0, offset: 1, kind: ValidationErrorKind::ImmutableGlobal(0) };
+    assert_eq!(result.err(), Some(EnvironmentError::LoadError(LoadError::ValidationFailed(invalid))));
+}
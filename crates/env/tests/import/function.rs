@@ -0,0 +1,106 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use hal_core::module::{FunctionSignature, Value, ValueType};
+use hal_core::module::Value::I32;
+use hal_core::Trap;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+fn signature(params: &[ValueType], results: &[ValueType]) -> FunctionSignature {
+    FunctionSignature::new(params.into(), results.into())
+}
+
+#[test]
+fn call_host_function() {
+    let mut env = Environment::default();
+    env.define_function("env", "add", signature(&[ValueType::I32, ValueType::I32], &[ValueType::I32]), |_, args| {
+        let (I32(l), I32(r)) = (&args[0], &args[1]) else { unreachable!() };
+        Ok([I32(l - r)].into())
+    });
+
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (import "env" "add" (func $sub (param i32 i32) (result i32)))
+              (func (export "sub") (param i32 i32) (result i32) (call $sub (local.get 0) (local.get 1)))
+              (func (export "twice") (param i32) (result i32) (call $sub (call $sub (local.get 0) (i32.const 1)) (i32.const 1)))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("sub", [I32(10), I32(3)]).unwrap().as_ref(), [I32(7)]);
+    assert_eq!(instance.invoke("twice", [I32(10)]).unwrap().as_ref(), [I32(8)]);
+}
+
+#[test]
+fn shifts_function_indices() {
+    let mut env = Environment::default();
+    let calls = Rc::new(Cell::new(0));
+    let counter = calls.clone();
+    env.define_function("env", "tick", signature(&[], &[]), move |_, _| {
+        counter.set(counter.get() + 1);
+        Ok([].into())
+    });
+
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (import "env" "tick" (func $tick))
+              (table 2 funcref)
+              (elem (i32.const 0) $tick $answer)
+              (type $v (func))
+              (type $i (func (result i32)))
+              (func $answer (result i32) (i32.const 42))
+              (func (export "answer") (result i32) (call $answer))
+              (func (export "indirect") (result i32)
+                (call_indirect (type $v) (i32.const 0))
+                (call_indirect (type $i) (i32.const 1))
+              )
+              (export "tick" (func $tick))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("answer", []).unwrap().as_ref(), [I32(42)]);
+    assert_eq!(instance.invoke("indirect", []).unwrap().as_ref(), [I32(42)]);
+    assert!(instance.invoke("tick", []).is_ok());
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn caller_memory() {
+    let mut env = Environment::default();
+    env.define_function("env", "sum", signature(&[ValueType::I32, ValueType::I32], &[ValueType::I32]), |memory, args| {
+        let (I32(addr), I32(len)) = (&args[0], &args[1]) else { unreachable!() };
+        let memory = memory.ok_or(Trap::Host("no memory".into()))?;
+        let bytes = memory.read(*addr as u32, *len as u32)?;
+        Ok([I32(bytes.iter().map(|b| *b as i32).sum())].into())
+    });
+
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (import "env" "sum" (func $sum (param i32 i32) (result i32)))
+              (memory 1)
+              (data (i32.const 8) "\01\02\03\04")
+              (func (export "sum") (param i32 i32) (result i32) (call $sum (local.get 0) (local.get 1)))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("sum", [I32(8), I32(4)]).unwrap().as_ref(), [I32(10)]);
+    assert_eq!(instance.invoke("sum", [I32(65535), I32(2)]), Err(Trap::MemoryOutOfBounds));
+}
+
+#[test]
+fn host_trap() {
+    let mut env = Environment::default();
+    env.define_function("env", "fail", signature(&[], &[]), |_, _| Err(Trap::Host("failed".into())));
+    env.define_function("env", "wrong", signature(&[], &[ValueType::I32]), |_, _| Ok([Value::I64(1)].into()));
+
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (import "env" "fail" (func $fail))
+              (import "env" "wrong" (func $wrong (result i32)))
+              (func (export "fail") (call $fail))
+              (func (export "wrong") (result i32) (call $wrong))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("fail", []), Err(Trap::Host("failed".into())));
+    assert!(matches!(instance.invoke("wrong", []), Err(Trap::Type(_))));
+}
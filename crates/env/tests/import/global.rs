@@ -0,0 +1,49 @@
+use std::rc::Rc;
+
+use hal_core::module::{Extern, Global, GlobalType, ValueType};
+use hal_core::module::Value::{I32, I64};
+use hal_env::{Environment, EnvironmentError, LinkError, SpawnWat, wat_source};
+
+#[test]
+fn stack_pointer() {
+    let mut env = Environment::default();
+    let stack_pointer = Rc::new(Global::new(GlobalType { value_type: ValueType::I32, mutable: true }, I32(1024)).unwrap());
+    env.define("env", "__stack_pointer", Extern::Global(stack_pointer.clone()));
+    let memory_base = Global::new(GlobalType { value_type: ValueType::I32, mutable: false }, I32(1024)).unwrap();
+    env.define("env", "__memory_base", Extern::Global(Rc::new(memory_base)));
+
+    env.spawn(wat_source::string(
+        r#"(module
+              (import "env" "__stack_pointer" (global $sp (mut i32)))
+              (import "env" "__memory_base" (global $memory_base i32))
+              (global $base i32 (global.get $memory_base))
+              (func (export "alloca") (param i32) (result i32)
+                (global.set $sp (i32.sub (global.get $sp) (local.get 0)))
+                (global.get $sp)
+              )
+              (func (export "base") (result i32) (global.get $base))
+            )"#
+    )).unwrap();
+
+    assert_eq!(env.invoke("alloca", [I32(16)]).unwrap().as_ref(), [I32(1008)]);
+    assert_eq!(stack_pointer.get(), I32(1008));
+    assert_eq!(env.invoke("base", []).unwrap().as_ref(), [I32(1024)]);
+}
+
+#[test]
+fn type_and_mutability() {
+    let mut env = Environment::default();
+    let global = Global::new(GlobalType { value_type: ValueType::I64, mutable: false }, I64(7)).unwrap();
+    env.define("env", "g", Extern::Global(Rc::new(global)));
+
+    let incompatible = Some(EnvironmentError::Unlinkable(LinkError::IncompatibleImportType("env".into(), "g".into())));
+
+    assert!(env.spawn(wat_source::string(r#"(module (import "env" "g" (global i64)))"#)).is_ok());
+
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "g" (global i32)))"#));
+    assert_eq!(result.err(), incompatible);
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "g" (global (mut i64))))"#));
+    assert_eq!(result.err(), incompatible);
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "g" (func)))"#));
+    assert_eq!(result.err(), incompatible);
+}
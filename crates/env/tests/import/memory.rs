@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+use hal_core::module::{Extern, Memory};
+use hal_core::module::Value::I32;
+use hal_env::{Environment, EnvironmentError, LinkError, SpawnWat, wat_source};
+
+#[test]
+fn shared_with_exporter() {
+    let mut env = Environment::default();
+    let exporter = env.spawn(wat_source::string(
+        r#"(module
+              (memory (export "memory") 1)
+              (data (i32.const 0) "\2a")
+              (func (export "load") (param i32) (result i32) (i32.load8_u (local.get 0)))
+            )"#
+    )).unwrap();
+    let memory = exporter.memory(0).unwrap();
+    env.define("env", "memory", Extern::Memory(memory.clone()));
+
+    env.spawn(wat_source::string(
+        r#"(module
+              (import "env" "memory" (memory 1))
+              (data (i32.const 1) "\07")
+              (func (export "load") (param i32) (result i32) (i32.load8_u (local.get 0)))
+              (func (export "store") (param i32 i32) (i32.store8 (local.get 0) (local.get 1)))
+              (func (export "grow") (result i32) (memory.grow (i32.const 1)))
+            )"#
+    )).unwrap();
+
+    assert_eq!(env.invoke("load", [I32(0)]).unwrap().as_ref(), [I32(42)]);
+    assert!(env.invoke("store", [I32(2), I32(9)]).is_ok());
+    assert_eq!(memory.read(0, 3), Ok(vec![42, 7, 9]));
+
+    assert_eq!(env.invoke("grow", []).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(memory.pages(), 2);
+}
+
+#[test]
+fn limits() {
+    let mut env = Environment::default();
+    env.define("env", "bounded", Extern::Memory(Rc::new(Memory::new(1, Some(2)).unwrap())));
+    env.define("env", "unbounded", Extern::Memory(Rc::new(Memory::new(1, None).unwrap())));
+
+    let incompatible = |name: &str| Some(EnvironmentError::Unlinkable(LinkError::IncompatibleImportType("env".into(), name.into())));
+
+    assert!(env.spawn(wat_source::string(r#"(module (import "env" "bounded" (memory 0 3)))"#)).is_ok());
+    assert!(env.spawn(wat_source::string(r#"(module (import "env" "unbounded" (memory 1)))"#)).is_ok());
+
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "bounded" (memory 2)))"#));
+    assert_eq!(result.err(), incompatible("bounded"));
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "bounded" (memory 1 1)))"#));
+    assert_eq!(result.err(), incompatible("bounded"));
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "unbounded" (memory 1 2)))"#));
+    assert_eq!(result.err(), incompatible("unbounded"));
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "bounded" (table 1 funcref)))"#));
+    assert_eq!(result.err(), incompatible("bounded"));
+}
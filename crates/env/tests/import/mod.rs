@@ -0,0 +1,6 @@
+mod function;
+mod global;
+mod memory;
+mod register;
+mod table;
+mod unlinkable;
@@ -0,0 +1,77 @@
+use hal_core::module::Value::I32;
+use hal_env::{Environment, EnvironmentError, SpawnWat, wat_source};
+
+#[test]
+fn function_runs_against_exporter() {
+    let mut env = Environment::default();
+    let counter = env.spawn(wat_source::string(
+        r#"(module
+              (global $count (mut i32) (i32.const 0))
+              (memory 1)
+              (func (export "increment") (result i32)
+                (global.set $count (i32.add (global.get $count) (i32.const 1)))
+                (i32.store (i32.const 0) (global.get $count))
+                (global.get $count)
+              )
+              (func (export "load") (result i32) (i32.load (i32.const 0)))
+            )"#
+    )).unwrap().id();
+    env.register("counter", counter).unwrap();
+
+    env.spawn(wat_source::string(
+        r#"(module
+              (import "counter" "increment" (func $increment (result i32)))
+              (memory 1)
+              (func (export "twice") (result i32) (drop (call $increment)) (call $increment))
+              (func (export "load") (result i32) (i32.load (i32.const 0)))
+            )"#
+    )).unwrap();
+
+    assert_eq!(env.invoke("twice", []).unwrap().as_ref(), [I32(2)]);
+    assert_eq!(env.invoke("load", []).unwrap().as_ref(), [I32(0)]);
+    assert_eq!(env.named_instance("counter").unwrap().invoke("load", []).unwrap().as_ref(), [I32(2)]);
+}
+
+#[test]
+fn shared_table_calls_across_instances() {
+    let mut env = Environment::default();
+    let owner = env.spawn(wat_source::string(
+        r#"(module
+              (type $get (func (result i32)))
+              (table (export "table") 2 funcref)
+              (func $seven (result i32) (i32.const 7))
+              (elem (i32.const 0) $seven)
+              (func (export "call") (param i32) (result i32) (call_indirect (type $get) (local.get 0)))
+            )"#
+    )).unwrap().id();
+    env.register("owner", owner).unwrap();
+
+    env.spawn(wat_source::string(
+        r#"(module
+              (import "owner" "table" (table 2 funcref))
+              (global $value i32 (i32.const 42))
+              (func $value (result i32) (global.get $value))
+              (elem (i32.const 1) $value)
+            )"#
+    )).unwrap();
+
+    let owner = env.instance(owner).unwrap();
+    assert_eq!(owner.invoke("call", [I32(0)]).unwrap().as_ref(), [I32(7)]);
+    assert_eq!(owner.invoke("call", [I32(1)]).unwrap().as_ref(), [I32(42)]);
+}
+
+#[test]
+fn instances_by_id_and_name() {
+    let mut env = Environment::default();
+    let first = env.spawn(wat_source::string(r#"(module (func (export "f") (result i32) (i32.const 1)))"#)).unwrap().id();
+    let second = env.spawn(wat_source::string(r#"(module (func (export "f") (result i32) (i32.const 2)))"#)).unwrap().id();
+    assert_ne!(first, second);
+
+    env.register("first", first).unwrap();
+    assert_eq!(env.invoke("f", []).unwrap().as_ref(), [I32(2)]);
+    assert_eq!(env.instance(first).unwrap().invoke("f", []).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(env.named_instance("first").unwrap().invoke("f", []).unwrap().as_ref(), [I32(1)]);
+    assert!(env.named_instance("second").is_none());
+
+    assert_eq!(env.register("third", 3), Err(EnvironmentError::UnknownInstance(3)));
+}
@@ -0,0 +1,48 @@
+use std::rc::Rc;
+
+use hal_core::module::{Extern, Table, ValueType};
+use hal_core::module::Value::{ExternRef, I32};
+use hal_env::{Environment, EnvironmentError, LinkError, SpawnWat, wat_source};
+
+#[test]
+fn shared_with_exporter() {
+    let mut env = Environment::default();
+    let exporter = env.spawn(wat_source::string(
+        r#"(module
+              (table (export "table") 2 externref)
+            )"#
+    )).unwrap();
+    let table = exporter.table(0).unwrap();
+    env.define("env", "table", Extern::Table(table.clone()));
+
+    env.spawn(wat_source::string(
+        r#"(module
+              (import "env" "table" (table 1 externref))
+              (func (export "set") (param i32 externref) (table.set (local.get 0) (local.get 1)))
+              (func (export "grow") (result i32) (table.grow (ref.null extern) (i32.const 3)))
+            )"#
+    )).unwrap();
+
+    assert!(env.invoke("set", [I32(1), ExternRef(Some(5))]).is_ok());
+    assert_eq!(table.get(1), Ok(ExternRef(Some(5))));
+    assert_eq!(env.invoke("grow", []).unwrap().as_ref(), [I32(2)]);
+    assert_eq!(table.size(), 5);
+}
+
+#[test]
+fn element_type_and_limits() {
+    let mut env = Environment::default();
+    env.define("env", "table", Extern::Table(Rc::new(Table::new(ValueType::FuncRef, 2, Some(4)))));
+
+    let incompatible = Some(EnvironmentError::Unlinkable(LinkError::IncompatibleImportType("env".into(), "table".into())));
+
+    assert!(env.spawn(wat_source::string(r#"(module (import "env" "table" (table 1 funcref)))"#)).is_ok());
+    assert!(env.spawn(wat_source::string(r#"(module (import "env" "table" (table 2 4 funcref)))"#)).is_ok());
+
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "table" (table 1 externref)))"#));
+    assert_eq!(result.err(), incompatible);
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "table" (table 3 funcref)))"#));
+    assert_eq!(result.err(), incompatible);
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "table" (table 1 3 funcref)))"#));
+    assert_eq!(result.err(), incompatible);
+}
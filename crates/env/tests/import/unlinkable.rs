@@ -0,0 +1,31 @@
+use hal_core::module::{FunctionSignature, ValueType};
+use hal_env::{Environment, EnvironmentError, LinkError, SpawnWat, wat_source};
+
+#[test]
+fn unknown_import() {
+    let mut env = Environment::default();
+    env.define_function("env", "f", FunctionSignature::new([].into(), [].into()), |_, _| Ok([].into()));
+
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "g" (func)))"#));
+    assert_eq!(result.err(), Some(EnvironmentError::Unlinkable(LinkError::UnknownImport("env".into(), "g".into()))));
+
+    let result = env.spawn(wat_source::string(r#"(module (import "other" "f" (func)))"#));
+    assert_eq!(result.err(), Some(EnvironmentError::Unlinkable(LinkError::UnknownImport("other".into(), "f".into()))));
+
+    let result = env.spawn(wat_source::string(r#"(module (import "other" "f" (func)))"#));
+    assert_eq!(result.err().map(|e| e.to_string()).as_deref(), Some("unknown import other.f"));
+}
+
+#[test]
+fn incompatible_signature() {
+    let mut env = Environment::default();
+    env.define_function("env", "f", FunctionSignature::new([ValueType::I32].into(), [].into()), |_, _| Ok([].into()));
+
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "f" (func (param i64))))"#));
+    assert_eq!(result.err(), Some(EnvironmentError::Unlinkable(LinkError::IncompatibleImportType("env".into(), "f".into()))));
+
+    let result = env.spawn(wat_source::string(r#"(module (import "env" "f" (func (param i32) (result i32))))"#));
+    assert_eq!(result.err(), Some(EnvironmentError::Unlinkable(LinkError::IncompatibleImportType("env".into(), "f".into()))));
+
+    assert!(env.spawn(wat_source::string(r#"(module (import "env" "f" (func (param i32))))"#)).is_ok());
+}
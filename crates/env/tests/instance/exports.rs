@@ -0,0 +1,84 @@
+use hal_core::module::Value;
+use hal_core::{Trap, TrapNotFound};
+use hal_env::{Environment, Exported, SpawnWat, wat_source};
+
+const MODULE: &str = r#"(module
+      (memory (export "memory") 1)
+      (table (export "table") 2 funcref)
+      (global (export "base") (mut i32) (i32.const 0))
+      (elem (i32.const 1) $load)
+      (func $load (export "load") (result i32)
+        (i32.load8_u (global.get 0))
+      )
+    )"#;
+
+#[test]
+fn get_export() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    assert!(matches!(instance.get_export("memory"), Ok(Exported::Memory(memory)) if memory.pages() == 1));
+    assert!(matches!(instance.get_export("table"), Ok(Exported::Table(table)) if table.size() == 2));
+    assert!(matches!(instance.get_export("base"), Ok(Exported::Global(global)) if global.get() == Value::I32(0)));
+    assert!(matches!(instance.get_export("load"), Ok(Exported::Function(_))));
+    assert_eq!(instance.get_export("missing").err(), Some(Trap::NotFound(TrapNotFound::Export("missing".to_string()))));
+}
+
+#[test]
+fn call_function() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+    instance.exported_memory("memory").unwrap().write(0, &[7]).unwrap();
+
+    let Ok(Exported::Function(load)) = instance.get_export("load") else {
+        panic!("load is not an exported function");
+    };
+    assert_eq!(load.call(instance, []), Ok([Value::I32(7)].into()));
+    assert!(load.call(instance, [Value::I32(1)]).is_err());
+}
+
+#[test]
+fn call_function_of_another_environment() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+    let Ok(Exported::Function(load)) = instance.get_export("load") else {
+        panic!("load is not an exported function");
+    };
+
+    let mut other = Environment::default();
+    let foreign = other.spawn(wat_source::string(MODULE)).unwrap();
+    assert_eq!(load.call(foreign, []), Err(Trap::ForeignFunction));
+}
+
+#[test]
+fn memory_and_global() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    let memory = instance.exported_memory("memory").unwrap();
+    memory.write(8, b"hal").unwrap();
+    instance.exported_global("base").unwrap().set(Value::I32(9)).unwrap();
+
+    assert_eq!(instance.invoke("load", []).unwrap().as_ref(), &[Value::I32(b'a' as i32)]);
+    assert_eq!(memory.read(8, 3).unwrap(), b"hal");
+}
+
+#[test]
+fn table() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    let table = instance.exported_table("table").unwrap();
+    assert_eq!(table.get(0), Ok(Value::FuncRef(None)));
+    assert!(matches!(table.get(1), Ok(Value::FuncRef(Some(_)))));
+}
+
+#[test]
+fn wrong_kind() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    assert_eq!(instance.exported_memory("table").err(), Some(Trap::NotFound(TrapNotFound::ExportedMemory("table".to_string()))));
+    assert_eq!(instance.exported_table("memory").err(), Some(Trap::NotFound(TrapNotFound::ExportedTable("memory".to_string()))));
+    assert_eq!(instance.get_export("missing").unwrap_err().to_string(), "unknown export missing");
+}
@@ -0,0 +1,96 @@
+use std::rc::Rc;
+
+use hal_core::module::{Extern, Memory, Table, Value, ValueType};
+use hal_core::Trap;
+use hal_env::{Environment, EnvironmentError, SpawnWat, wat_source};
+
+#[test]
+fn runs_after_segments() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (memory 1)
+              (data (i32.const 0) "\2a")
+              (global $g (export "g") (mut i32) (i32.const 0))
+              (func $main
+                (global.set $g (i32.load8_u (i32.const 0)))
+              )
+              (start $main)
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.exported_global("g").unwrap().get(), Value::I32(42));
+}
+
+#[test]
+fn trap_fails_instantiation() {
+    let mut env = Environment::default();
+    let result = env.spawn(wat_source::string(
+        r#"(module
+              (func $main (unreachable))
+              (func (export "test") (result i32) (i32.const 1))
+              (start $main)
+            )"#
+    ));
+
+    assert_eq!(result.err(), Some(EnvironmentError::Trapped(Trap::Unreachable)));
+    assert!(env.instance(0).is_none());
+}
+
+#[test]
+fn failed_instance_is_dropped() {
+    let mut env = Environment::default();
+    let memory = Rc::new(Memory::new(1, None).unwrap());
+    env.define("env", "memory", Extern::Memory(memory.clone()));
+
+    for _ in 0..3 {
+        let result = env.spawn(wat_source::string(
+            r#"(module
+                  (import "env" "memory" (memory 1))
+                  (func $main (unreachable))
+                  (start $main)
+                )"#
+        ));
+        assert_eq!(result.err(), Some(EnvironmentError::Trapped(Trap::Unreachable)));
+    }
+
+    assert_eq!(Rc::strong_count(&memory), 2);
+}
+
+#[test]
+fn failed_instance_lives_while_referenced() {
+    let mut env = Environment::default();
+    let memory = Rc::new(Memory::new(1, None).unwrap());
+    let table = Rc::new(Table::new(ValueType::FuncRef, 1, None));
+    env.define("env", "memory", Extern::Memory(memory.clone()));
+    env.define("env", "table", Extern::Table(table.clone()));
+
+    let result = env.spawn(wat_source::string(
+        r#"(module
+              (import "env" "memory" (memory 1))
+              (import "env" "table" (table 1 funcref))
+              (elem (i32.const 0) $f)
+              (func $f (result i32) (i32.load8_u (i32.const 0)))
+              (func $main (unreachable))
+              (start $main)
+            )"#
+    ));
+    assert_eq!(result.err(), Some(EnvironmentError::Trapped(Trap::Unreachable)));
+    assert_eq!(Rc::strong_count(&memory), 3);
+
+    // the function left in the shared table still runs against its store
+    memory.write(0, &[42]).unwrap();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (import "env" "table" (table 1 funcref))
+              (func (export "call") (result i32) (call_indirect (result i32) (i32.const 0)))
+            )"#
+    )).unwrap();
+    assert_eq!(instance.invoke("call", []).unwrap().as_ref(), [Value::I32(42)]);
+
+    // once the reference is gone, the next failed instantiation drops the store
+    table.set(0, Value::FuncRef(None)).unwrap();
+    let result = env.spawn(wat_source::string(r#"(module (func $main (unreachable)) (start $main))"#));
+    assert_eq!(result.err(), Some(EnvironmentError::Trapped(Trap::Unreachable)));
+    assert_eq!(Rc::strong_count(&memory), 2);
+}
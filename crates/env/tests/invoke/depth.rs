@@ -0,0 +1,49 @@
+use hal_core::module::Value;
+use hal_core::{Trap, TrapOverflow};
+use hal_env::{Environment, SpawnWat, wat_source};
+
+const COUNT: &str = r#"(module
+    (func $count (export "count") (param i32) (result i32)
+        (if (result i32) (i32.eqz (local.get 0))
+            (then (i32.const 0))
+            (else (i32.add (i32.const 1) (call $count (i32.sub (local.get 0) (i32.const 1)))))
+        )
+    )
+)"#;
+
+#[test]
+fn deep_recursion() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(COUNT)).unwrap();
+
+    // far deeper than the host stack of a test thread would allow when recursing per call
+    let result = instance.invoke("count", [Value::I32(10_000)]).unwrap();
+    assert_eq!(result.as_ref(), [Value::I32(10_000)]);
+}
+
+#[test]
+fn max_call_depth() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(COUNT)).unwrap();
+    instance.set_max_call_depth(100);
+
+    // the invoked function is the first of the nested calls
+    let result = instance.invoke("count", [Value::I32(99)]).unwrap();
+    assert_eq!(result.as_ref(), [Value::I32(99)]);
+
+    let result = instance.invoke("count", [Value::I32(100)]);
+    assert_eq!(result, Err(Trap::Overflow(TrapOverflow::Stack)));
+}
+
+#[test]
+fn runaway() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+            (func $runaway (export "runaway") (call $runaway))
+        )"#
+    )).unwrap();
+
+    let trap = instance.invoke("runaway", []).unwrap_err();
+    assert_eq!(trap.to_string(), "call stack exhausted");
+}
@@ -1,2 +1,5 @@
 mod multiple;
 mod local;
+mod typed;
+mod depth;
+mod trap;
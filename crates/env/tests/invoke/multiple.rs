@@ -1,4 +1,5 @@
-use hal_core::module::Value;
+use hal_core::module::{Value, ValueType};
+use hal_core::{Trap, TrapType};
 use hal_env::{Environment, SpawnWat, wat_source};
 
 #[test]
@@ -23,3 +24,80 @@ fn call_local() {
     let result = instance.invoke("call_doubler", args).unwrap();
     assert_eq!(result.as_ref(), expected);
 }
+
+#[test]
+fn parameter_order() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+                      (func (export "sub") (param i32 i32) (result i32)
+                        (local.get 0)
+                        (local.get 1)
+                        i32.sub
+                      )
+                      (func (export "sub_const") (result i32)
+                        (i32.sub (i32.const 10) (i32.const 3))
+                      )
+                    )"#
+    )).unwrap();
+
+    let result = instance.invoke("sub", [Value::I32(10), Value::I32(3)]).unwrap();
+    assert_eq!(result.as_ref(), [Value::I32(7)]);
+
+    let result = instance.invoke("sub_const", []).unwrap();
+    assert_eq!(result.as_ref(), [Value::I32(7)]);
+}
+
+#[test]
+fn result_order() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func $values (result i32 i64 f32)
+                (i32.const 1)
+                (i64.const 2)
+                (f32.const 3)
+              )
+              (func (export "values") (result i32 i64 f32)
+                (call $values)
+              )
+              (func (export "swap") (param i32 i32) (result i32 i32)
+                (local.get 1)
+                (local.get 0)
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("values", []).unwrap();
+    assert_eq!(result.as_ref(), [Value::I32(1), Value::I64(2), Value::F32(3.0)]);
+
+    let result = instance.invoke("swap", [Value::I32(1), Value::I32(2)]).unwrap();
+    assert_eq!(result.as_ref(), [Value::I32(2), Value::I32(1)]);
+}
+
+#[test]
+fn arguments_checked() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "sub") (param i32 i64) (result i64)
+                (i64.sub (i64.extend_i32_s (local.get 0)) (local.get 1))
+              )
+            )"#
+    )).unwrap();
+
+    let expected: Box<[ValueType]> = [ValueType::I32, ValueType::I64].into();
+
+    let result = instance.invoke("sub", [Value::I32(10)]);
+    assert_eq!(result, Err(Trap::Type(TrapType::ArgumentMismatch(expected.clone(), [ValueType::I32].into()))));
+
+    let result = instance.invoke("sub", [Value::I32(10), Value::I32(3)]);
+    assert_eq!(result, Err(Trap::Type(TrapType::ArgumentMismatch(expected.clone(), [ValueType::I32, ValueType::I32].into()))));
+    assert_eq!(result.unwrap_err().to_string(), "expected arguments [i32 i64], got [i32 i32]");
+
+    let result = instance.invoke("sub", [Value::I32(10), Value::I64(3), Value::I64(1)]);
+    assert_eq!(result, Err(Trap::Type(TrapType::ArgumentMismatch(expected, [ValueType::I32, ValueType::I64, ValueType::I64].into()))));
+
+    let result = instance.invoke("sub", [Value::I32(10), Value::I64(3)]).unwrap();
+    assert_eq!(result.as_ref(), [Value::I64(7)]);
+}
@@ -0,0 +1,98 @@
+use hal_core::module::{FunctionSignature, Value, ValueType};
+use hal_core::module::Value::I32;
+use hal_core::{Trap, TrapDivisionByZero};
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn invoke_after_trap() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func $div (param i32 i32) (result i32) (i32.div_s (local.get 0) (local.get 1)))
+              (func (export "nested") (param i32) (result i32)
+                (i32.add (i32.const 1)
+                  (block (result i32)
+                    (i32.mul (i32.const 2) (call $div (i32.const 10) (local.get 0)))
+                  )
+                )
+              )
+              (func (export "pair") (result i32 i64) (i32.const 1) (i64.const 2))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("nested", [I32(0)]), Err(Trap::DivisionByZero(TrapDivisionByZero::Integer)));
+    assert_eq!(instance.invoke("nested", [I32(5)]).unwrap().as_ref(), [I32(5)]);
+    assert_eq!(instance.invoke("pair", []).unwrap().as_ref(), [I32(1), Value::I64(2)]);
+}
+
+#[test]
+fn repeated_exhaustion() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func $runaway (export "runaway") (param i32) (result i32)
+                (i32.add (local.get 0) (call $runaway (local.get 0)))
+              )
+              (func $count (export "count") (param i32) (result i32)
+                (if (result i32) (i32.eqz (local.get 0))
+                  (then (i32.const 0))
+                  (else (i32.add (i32.const 1) (call $count (i32.sub (local.get 0) (i32.const 1)))))
+                )
+              )
+            )"#
+    )).unwrap();
+    instance.set_max_call_depth(1000);
+
+    // every run leaves an operand per frame behind, which would fill the value stack eventually
+    for _ in 0..100 {
+        assert!(instance.invoke("runaway", [I32(1)]).is_err());
+    }
+    assert_eq!(instance.invoke("count", [I32(999)]).unwrap().as_ref(), [I32(999)]);
+}
+
+#[test]
+fn host_trap() {
+    let mut env = Environment::default();
+    let signature = FunctionSignature::new([ValueType::I32].into(), [ValueType::I32].into());
+    env.define_function("env", "check", signature, |_, args| match &args[0] {
+        I32(0) => Err(Trap::Host("zero".into())),
+        value => Ok([value.clone()].into()),
+    });
+
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (import "env" "check" (func $check (param i32) (result i32)))
+              (func (export "check") (param i32) (result i32)
+                (i32.add (i32.const 1) (call $check (local.get 0)))
+              )
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("check", [I32(0)]), Err(Trap::Host("zero".into())));
+    assert_eq!(instance.invoke("check", [I32(41)]).unwrap().as_ref(), [I32(42)]);
+}
+
+#[test]
+fn imported_function_trap() {
+    let mut env = Environment::default();
+    let exporter = env.spawn(wat_source::string(
+        r#"(module
+              (global $value (mut i32) (i32.const 1))
+              (func (export "fail") (global.set $value (i32.const 2)) (unreachable))
+            )"#
+    )).unwrap().id();
+    env.register("exporter", exporter).unwrap();
+
+    env.spawn(wat_source::string(
+        r#"(module
+              (import "exporter" "fail" (func $fail))
+              (global $value (mut i32) (i32.const 3))
+              (func (export "fail") (call $fail))
+              (func (export "value") (result i32) (global.get $value))
+            )"#
+    )).unwrap();
+
+    assert_eq!(env.invoke("fail", []), Err(Trap::Unreachable));
+    // the importer runs against its own store again
+    assert_eq!(env.invoke("value", []).unwrap().as_ref(), [I32(3)]);
+}
@@ -0,0 +1,60 @@
+use hal_core::module::{FunctionSignature, ValueType};
+use hal_core::{Trap, TrapNotFound, TrapType};
+use hal_env::{Environment, SpawnWat, TypedFunc, wat_source};
+
+const MODULE: &str = r#"(module
+      (func (export "add") (param i32 i32) (result i32)
+        (i32.add (local.get 0) (local.get 1))
+      )
+      (func (export "split") (param i64) (result i32 i32 f64)
+        (i32.wrap_i64 (i64.shr_u (local.get 0) (i64.const 32)))
+        (i32.wrap_i64 (local.get 0))
+        (f64.convert_i64_s (local.get 0))
+      )
+      (func (export "nothing"))
+      (global (export "g") i32 (i32.const 0))
+    )"#;
+
+#[test]
+fn calls_with_tuples() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    let add: TypedFunc<(i32, i32), i32> = instance.typed_func("add").unwrap();
+    assert_eq!(add.call(instance, (40, 2)), Ok(42));
+    assert_eq!(add.call(instance, (i32::MAX, 1)), Ok(i32::MIN));
+
+    let split = instance.typed_func::<i64, (i32, i32, f64)>("split").unwrap();
+    assert_eq!(split.call(instance, 0x1_0000_0002), Ok((1, 2, 4294967298.0)));
+
+    let nothing = instance.typed_func::<(), ()>("nothing").unwrap();
+    assert_eq!(nothing.call(instance, ()), Ok(()));
+}
+
+#[test]
+fn call_on_instance_of_another_environment() {
+    let mut env = Environment::default();
+    let add = env.spawn(wat_source::string(MODULE)).unwrap().typed_func::<(i32, i32), i32>("add").unwrap();
+
+    let mut other = Environment::default();
+    let foreign = other.spawn(wat_source::string(MODULE)).unwrap();
+    assert_eq!(add.call(foreign, (40, 2)), Err(Trap::ForeignFunction));
+}
+
+#[test]
+fn signature_checked_at_lookup() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    let result = instance.typed_func::<(i32, i64), i32>("add");
+    assert_eq!(result.err(), Some(Trap::Type(TrapType::SignatureMismatch(
+        FunctionSignature::new([ValueType::I32, ValueType::I32].into(), [ValueType::I32].into()),
+        FunctionSignature::new([ValueType::I32, ValueType::I64].into(), [ValueType::I32].into()),
+    ))));
+
+    let result = instance.typed_func::<(i32, i32), ()>("add");
+    assert_eq!(result.err().map(|trap| trap.to_string()).as_deref(), Some("expected signature [i32 i32] -> [i32], got [i32 i32] -> []"));
+
+    let result = instance.typed_func::<(), ()>("g");
+    assert_eq!(result.err(), Some(Trap::NotFound(TrapNotFound::ExportedFunction("g".into()))));
+}
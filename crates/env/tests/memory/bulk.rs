@@ -0,0 +1,52 @@
+use hal_core::module::Value::I32;
+use hal_core::Trap;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+const MODULE: &str = r#"(module
+  (memory 1)
+  (data (i32.const 0) "\01\02\03\04")
+  (func (export "copy") (param i32 i32 i32)
+    (memory.copy (local.get 0) (local.get 1) (local.get 2))
+  )
+  (func (export "fill") (param i32 i32 i32)
+    (memory.fill (local.get 0) (local.get 1) (local.get 2))
+  )
+  (func (export "load") (param i32) (result i32)
+    (i32.load (local.get 0))
+  )
+)"#;
+
+#[test]
+fn copy_overlapping() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    assert!(instance.invoke("copy", [I32(1), I32(0), I32(3)]).is_ok());
+    assert_eq!(instance.invoke("load", [I32(0)]).unwrap().as_ref(), [I32(0x03_02_01_01)]);
+
+    assert!(instance.invoke("copy", [I32(0), I32(1), I32(3)]).is_ok());
+    assert_eq!(instance.invoke("load", [I32(0)]).unwrap().as_ref(), [I32(0x03_03_02_01)]);
+}
+
+#[test]
+fn copy_out_of_bounds() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    assert_eq!(instance.invoke("copy", [I32(65534), I32(0), I32(4)]), Err(Trap::MemoryOutOfBounds));
+    assert_eq!(instance.invoke("copy", [I32(0), I32(65534), I32(4)]), Err(Trap::MemoryOutOfBounds));
+    assert!(instance.invoke("copy", [I32(65536), I32(65536), I32(0)]).is_ok());
+    assert_eq!(instance.invoke("load", [I32(0)]).unwrap().as_ref(), [I32(0x04_03_02_01)]);
+}
+
+#[test]
+fn fill() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    assert!(instance.invoke("fill", [I32(1), I32(0x1AA), I32(2)]).is_ok());
+    assert_eq!(instance.invoke("load", [I32(0)]).unwrap().as_ref(), [I32(0x04_AA_AA_01)]);
+
+    assert_eq!(instance.invoke("fill", [I32(65535), I32(0), I32(2)]), Err(Trap::MemoryOutOfBounds));
+    assert_eq!(instance.invoke("fill", [I32(65537), I32(0), I32(0)]), Err(Trap::MemoryOutOfBounds));
+}
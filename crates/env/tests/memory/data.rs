@@ -0,0 +1,91 @@
+use hal_core::module::Value::I32;
+use hal_core::Trap;
+use hal_env::{Environment, EnvironmentError, LoadWasm, SpawnWat, wat_source};
+
+#[test]
+fn active() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (memory 1)
+              (data (i32.const 0) "\01\02")
+              (data (i32.const 1) "\03\04")
+              (func (export "test") (result i32)
+                (i32.load (i32.const 0))
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", []).unwrap();
+    assert_eq!(result.as_ref(), [I32(0x0004_0301)]);
+}
+
+#[test]
+fn passive() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (memory 1)
+              (data "\aa\bb\cc\dd")
+              (func (export "init")
+                (memory.init 0 (i32.const 0) (i32.const 1) (i32.const 2))
+              )
+              (func (export "init_out_of_bounds")
+                (memory.init 0 (i32.const 0) (i32.const 1) (i32.const 4))
+              )
+              (func (export "init_nothing")
+                (memory.init 0 (i32.const 0) (i32.const 0) (i32.const 0))
+              )
+              (func (export "drop")
+                (data.drop 0)
+              )
+              (func (export "load") (result i32)
+                (i32.load (i32.const 0))
+              )
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("load", []).unwrap().as_ref(), [I32(0)]);
+
+    assert!(instance.invoke("init", []).is_ok());
+    assert_eq!(instance.invoke("load", []).unwrap().as_ref(), [I32(0xccbb)]);
+    assert_eq!(instance.invoke("init_out_of_bounds", []), Err(Trap::MemoryOutOfBounds));
+
+    assert!(instance.invoke("drop", []).is_ok());
+    assert!(instance.invoke("init_nothing", []).is_ok());
+    assert_eq!(instance.invoke("init", []), Err(Trap::MemoryOutOfBounds));
+}
+
+#[test]
+fn active_out_of_bounds() {
+    let mut env = Environment::default();
+    let result = env.spawn(wat_source::string(
+        r#"(module
+              (memory 1)
+              (data (i32.const 65535) "\01\02")
+            )"#
+    ));
+
+    assert!(matches!(result, Err(EnvironmentError::Trapped(Trap::MemoryOutOfBounds))));
+}
+
+#[test]
+fn instances_own_their_memory() {
+    let mut env = Environment::default();
+    let module = env.load(wat_source::string(
+        r#"(module
+              (memory 1)
+              (data (i32.const 0) "\01")
+              (func (export "store") (param i32) (i32.store (i32.const 0) (local.get 0)))
+              (func (export "load") (result i32) (i32.load (i32.const 0)))
+            )"#
+    )).unwrap();
+
+    let first = env.instantiate(module).unwrap().id();
+    env.instance(first).unwrap().invoke("store", [I32(42)]).unwrap();
+
+    // the data segment is copied into the new memory only
+    let second = env.instantiate(module).unwrap().id();
+    assert_eq!(env.instance(second).unwrap().invoke("load", []).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(env.instance(first).unwrap().invoke("load", []).unwrap().as_ref(), [I32(42)]);
+}
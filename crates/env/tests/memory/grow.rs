@@ -0,0 +1,76 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use hal_core::module::Value::I32;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+const MODULE: &str = r#"(module
+  (memory 1 3)
+  (func (export "grow") (param i32) (result i32)
+    (memory.grow (local.get 0))
+  )
+  (func (export "size") (result i32)
+    (memory.size)
+  )
+)"#;
+
+#[test]
+fn grows_up_to_max() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    assert_eq!(instance.invoke("size", []).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(instance.invoke("grow", [I32(0)]).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(instance.invoke("grow", [I32(2)]).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(instance.invoke("size", []).unwrap().as_ref(), [I32(3)]);
+    assert_eq!(instance.invoke("grow", [I32(1)]).unwrap().as_ref(), [I32(-1)]);
+    assert_eq!(instance.invoke("grow", [I32(-1)]).unwrap().as_ref(), [I32(-1)]);
+    assert_eq!(instance.invoke("size", []).unwrap().as_ref(), [I32(3)]);
+    assert_eq!(instance.memory(0).unwrap().size(), 3 * 65536);
+}
+
+#[test]
+fn hook_vetoes_growth() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+    instance.set_memory_grow_hook(|_, _, requested| requested <= 2);
+
+    assert_eq!(instance.invoke("grow", [I32(2)]).unwrap().as_ref(), [I32(-1)]);
+    assert_eq!(instance.invoke("grow", [I32(1)]).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(instance.invoke("grow", [I32(1)]).unwrap().as_ref(), [I32(-1)]);
+    assert_eq!(instance.invoke("size", []).unwrap().as_ref(), [I32(2)]);
+}
+
+#[test]
+fn hook_observes_growth() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    let observed = Rc::new(Cell::new(None));
+    let hook_observed = observed.clone();
+    instance.set_memory_grow_hook(move |addr, current, requested| {
+        hook_observed.set(Some((addr, current, requested)));
+        true
+    });
+
+    assert_eq!(instance.invoke("grow", [I32(2)]).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(observed.get(), Some((0, 1, 3)));
+}
+
+#[test]
+fn hook_not_asked_beyond_max() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(MODULE)).unwrap();
+
+    let asked = Rc::new(Cell::new(0));
+    let hook_asked = asked.clone();
+    instance.set_memory_grow_hook(move |_, _, _| {
+        hook_asked.set(hook_asked.get() + 1);
+        true
+    });
+
+    assert_eq!(instance.invoke("grow", [I32(3)]).unwrap().as_ref(), [I32(-1)]);
+    assert_eq!(asked.get(), 0);
+    assert_eq!(instance.invoke("grow", [I32(2)]).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(asked.get(), 1);
+}
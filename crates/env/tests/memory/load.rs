@@ -0,0 +1,93 @@
+use hal_core::module::Value::{I32, I64};
+use hal_core::Trap;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn sign_and_zero_extension() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (memory 1)
+              (func $fill (i64.store (i32.const 0) (i64.const -1)))
+              (func (export "i32.load8_s") (result i32) (call $fill) (i32.load8_s (i32.const 0)))
+              (func (export "i32.load16_u") (result i32) (call $fill) (i32.load16_u (i32.const 0)))
+              (func (export "i64.load8_u") (result i64) (call $fill) (i64.load8_u (i32.const 0)))
+              (func (export "i64.load16_s") (result i64) (call $fill) (i64.load16_s (i32.const 0)))
+              (func (export "i64.load32_u") (result i64) (call $fill) (i64.load32_u (i32.const 0)))
+            )"#
+    )).unwrap();
+
+    for (name, expected) in [
+        ("i32.load8_s", I32(-1)),
+        ("i32.load16_u", I32(0xFFFF)),
+        ("i64.load8_u", I64(0xFF)),
+        ("i64.load16_s", I64(-1)),
+        ("i64.load32_u", I64(0xFFFF_FFFF)),
+    ] {
+        let result = instance.invoke(name, []).unwrap();
+        assert_eq!(result.as_ref(), [expected], "{}", name);
+    }
+}
+
+#[test]
+fn narrow_store() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (memory 1)
+              (func (export "test") (result i64)
+                (i64.store32 (i32.const 0) (i64.const 0x1122334455667788))
+                (i32.store8 (i32.const 4) (i32.const 0x1FF))
+                (i64.load (i32.const 0))
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", []).unwrap();
+    assert_eq!(result.as_ref(), [I64(0xFF_55667788)]);
+}
+
+#[test]
+fn offset() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (memory 1)
+              (func (export "test") (result i32)
+                (i32.store16 offset=2 (i32.const 4) (i32.const 0xABCD))
+                (i32.load16_u (i32.const 6))
+              )
+            )"#
+    )).unwrap();
+
+    let result = instance.invoke("test", []).unwrap();
+    assert_eq!(result.as_ref(), [I32(0xABCD)]);
+}
+
+#[test]
+fn out_of_bounds() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (memory 1)
+              (func (export "last") (result i32)
+                (i32.load (i32.const 65532))
+              )
+              (func (export "straddle") (result i32)
+                (i32.load (i32.const 65533))
+              )
+              (func (export "wrap") (result i32)
+                (i32.load offset=4294967295 (i32.const 1))
+              )
+              (func (export "store")
+                (i64.store (i32.const -1) (i64.const 0))
+              )
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("last", []).unwrap().as_ref(), [I32(0)]);
+    assert_eq!(instance.invoke("straddle", []), Err(Trap::MemoryOutOfBounds));
+    assert_eq!(instance.invoke("wrap", []), Err(Trap::MemoryOutOfBounds));
+    assert_eq!(instance.invoke("store", []), Err(Trap::MemoryOutOfBounds));
+    assert_eq!(format!("{}", Trap::MemoryOutOfBounds), "out of bounds memory access");
+}
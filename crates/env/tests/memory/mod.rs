@@ -1 +1,5 @@
-mod store;
\ No newline at end of file
+mod bulk;
+mod data;
+mod grow;
+mod load;
+mod store;
@@ -1,4 +1,10 @@
+mod control;
+mod global;
+mod import;
+mod instance;
 mod invoke;
 mod memory;
 mod numeric;
+mod reference;
 mod spec;
+mod table;
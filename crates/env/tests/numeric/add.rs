@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use hal_core::module::{Value, ValueType};
 use hal_env::{Environment, SpawnWat, wat_source};
-use Value::{I32, I64};
+use Value::{F32, F64, I32, I64};
 
 #[test]
 fn i32() {
@@ -14,6 +14,15 @@ fn i64() {
     test_method(ValueType::I64, [I64(1), I64(2)], I64(3))
 }
 
+#[test]
+fn f32() {
+    test_method(ValueType::F32, [F32(1.5), F32(2.25)], F32(3.75))
+}
+
+#[test]
+fn f64() {
+    test_method(ValueType::F64, [F64(1.5), F64(-2.25)], F64(-0.75))
+}
 
 fn test_method(vt: ValueType, args: impl AsRef<[Value]>, expected: Value) {
     let args = &args;
@@ -0,0 +1,52 @@
+use hal_core::module::Value;
+use hal_core::module::Value::I32;
+use hal_env::{Environment, HostRef, SpawnWat, wat_source};
+
+#[test]
+fn round_trip() {
+    let mut env = Environment::default();
+    let greeting = env.host_ref("hello");
+    let answer = env.host_ref(42u64);
+
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (table $t 2 externref)
+              (func (export "store") (param i32 externref) (table.set $t (local.get 0) (local.get 1)))
+              (func (export "load") (param i32) (result externref) (table.get $t (local.get 0)))
+            )"#
+    )).unwrap();
+
+    assert!(instance.invoke("store", [I32(0), answer.into()]).is_ok());
+    assert!(instance.invoke("store", [I32(1), greeting.into()]).is_ok());
+    let loaded = instance.invoke("load", [I32(0)]).unwrap();
+
+    let handle = HostRef::from_value(&loaded[0]).unwrap();
+    assert_eq!(handle, answer);
+    assert_eq!(env.host_object::<u64>(handle).as_deref(), Some(&42));
+    assert_eq!(env.host_object::<&str>(greeting).as_deref(), Some(&"hello"));
+}
+
+#[test]
+fn mismatched_type() {
+    let mut env = Environment::default();
+    let handle = env.host_ref(42u64);
+
+    assert!(env.host_object::<u32>(handle).is_none());
+    assert_eq!(HostRef::from_value(&Value::ExternRef(None)), None);
+    assert_eq!(HostRef::from_value(&I32(0)), None);
+}
+
+#[test]
+fn release() {
+    let mut env = Environment::default();
+    let answer = env.host_ref(42u64);
+
+    assert!(env.release_host_ref(answer));
+    assert!(env.host_object::<u64>(answer).is_none());
+    assert!(!env.release_host_ref(answer));
+
+    let greeting = env.host_ref("hello");
+    assert_ne!(greeting, answer);
+    assert_eq!(env.host_object::<&str>(greeting).as_deref(), Some(&"hello"));
+    assert!(env.host_object::<&str>(answer).is_none());
+}
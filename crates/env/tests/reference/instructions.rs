@@ -0,0 +1,79 @@
+use hal_core::module::Value::{ExternRef, FuncRef, I32, I64};
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn null_and_is_null() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "null-func") (result funcref) (ref.null func))
+              (func (export "null-extern") (result externref) (ref.null extern))
+              (func (export "is-null") (param externref) (result i32) (ref.is_null (local.get 0)))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("null-func", []).unwrap().as_ref(), [FuncRef(None)]);
+    assert_eq!(instance.invoke("null-extern", []).unwrap().as_ref(), [ExternRef(None)]);
+    assert_eq!(instance.invoke("is-null", [ExternRef(None)]).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(instance.invoke("is-null", [ExternRef(Some(0))]).unwrap().as_ref(), [I32(0)]);
+}
+
+#[test]
+fn func() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func $f)
+              (func $g)
+              (elem declare func $f $g)
+              (func (export "ref-g") (result funcref) (ref.func $g))
+              (func (export "is-null") (result i32) (ref.is_null (ref.func $f)))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("ref-g", []).unwrap().as_ref(), [FuncRef(Some(1))]);
+    assert_eq!(instance.invoke("is-null", []).unwrap().as_ref(), [I32(0)]);
+}
+
+#[test]
+fn locals_and_globals() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (global $g (mut externref) (ref.null extern))
+              (func (export "swap") (param externref) (result externref) (local $old externref)
+                (local.set $old (global.get $g))
+                (global.set $g (local.get 0))
+                (local.get $old)
+              )
+              (func (export "tee") (param externref) (result i32) (local $r externref)
+                (ref.is_null (local.tee $r (local.get 0)))
+              )
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("swap", [ExternRef(Some(1))]).unwrap().as_ref(), [ExternRef(None)]);
+    assert_eq!(instance.invoke("swap", [ExternRef(Some(2))]).unwrap().as_ref(), [ExternRef(Some(1))]);
+    assert_eq!(instance.invoke("tee", [ExternRef(Some(2))]).unwrap().as_ref(), [I32(0)]);
+    assert_eq!(instance.invoke("tee", [ExternRef(None)]).unwrap().as_ref(), [I32(1)]);
+}
+
+#[test]
+fn typed_select() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (func (export "select-ref") (param externref externref i32) (result externref)
+                (select (result externref) (local.get 0) (local.get 1) (local.get 2))
+              )
+              (func (export "select-i64") (param i64 i64 i32) (result i64)
+                (select (result i64) (local.get 0) (local.get 1) (local.get 2))
+              )
+            )"#
+    )).unwrap();
+
+    let args = |c| [ExternRef(Some(1)), ExternRef(None), I32(c)];
+    assert_eq!(instance.invoke("select-ref", args(1)).unwrap().as_ref(), [ExternRef(Some(1))]);
+    assert_eq!(instance.invoke("select-ref", args(0)).unwrap().as_ref(), [ExternRef(None)]);
+    assert_eq!(instance.invoke("select-i64", [I64(1), I64(2), I32(0)]).unwrap().as_ref(), [I64(2)]);
+}
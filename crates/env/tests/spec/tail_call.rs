@@ -0,0 +1,17 @@
+use hal_wasm::WasmFeatures;
+
+use crate::spec::run_test_with_features;
+
+macro_rules! test {
+    ($file: ident) => {
+        #[test]
+        fn $file(){
+            let features = WasmFeatures { tail_call: true, ..WasmFeatures::default() };
+            run_test_with_features("tail-call", stringify!($file), &features);
+        }
+    };
+}
+
+
+test!(return_call);
+test!(return_call_indirect);
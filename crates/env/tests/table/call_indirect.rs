@@ -0,0 +1,66 @@
+use hal_core::module::Value::{I32, I64};
+use hal_core::Trap;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn dispatch() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (type $binary (func (param i32 i32) (result i32)))
+              (table 3 funcref)
+              (elem (i32.const 0) $add $sub $mul)
+              (func $add (param i32 i32) (result i32) (i32.add (local.get 0) (local.get 1)))
+              (func $sub (param i32 i32) (result i32) (i32.sub (local.get 0) (local.get 1)))
+              (func $mul (param i32 i32) (result i32) (i32.mul (local.get 0) (local.get 1)))
+              (func (export "apply") (param i32 i32 i32) (result i32)
+                (call_indirect (type $binary) (local.get 1) (local.get 2) (local.get 0))
+              )
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("apply", [I32(0), I32(7), I32(3)]).unwrap().as_ref(), [I32(10)]);
+    assert_eq!(instance.invoke("apply", [I32(1), I32(7), I32(3)]).unwrap().as_ref(), [I32(4)]);
+    assert_eq!(instance.invoke("apply", [I32(2), I32(7), I32(3)]).unwrap().as_ref(), [I32(21)]);
+}
+
+#[test]
+fn structurally_equal_types() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (type $a (func (result i64)))
+              (type $b (func (result i64)))
+              (table funcref (elem $f))
+              (func $f (type $a) (i64.const 42))
+              (func (export "call") (result i64)
+                (call_indirect (type $b) (i32.const 0))
+              )
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("call", []).unwrap().as_ref(), [I64(42)]);
+}
+
+#[test]
+fn traps() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (type $void (func))
+              (table 3 funcref)
+              (elem (i32.const 0) $f $g)
+              (func $f)
+              (func $g (param i32))
+              (func (export "call") (param i32)
+                (call_indirect (type $void) (local.get 0))
+              )
+            )"#
+    )).unwrap();
+
+    assert!(instance.invoke("call", [I32(0)]).is_ok());
+    assert_eq!(instance.invoke("call", [I32(1)]), Err(Trap::IndirectCallTypeMismatch));
+    assert_eq!(instance.invoke("call", [I32(2)]), Err(Trap::UninitializedElement(2)));
+    assert_eq!(instance.invoke("call", [I32(3)]), Err(Trap::UndefinedElement));
+    assert_eq!(instance.invoke("call", [I32(-1)]), Err(Trap::UndefinedElement));
+}
@@ -0,0 +1,76 @@
+use hal_core::module::Value::{FuncRef, I32};
+use hal_core::Trap;
+use hal_env::{Environment, EnvironmentError, LoadWasm, SpawnWat, wat_source};
+
+#[test]
+fn active() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (table $t (export "table") 4 funcref)
+              (func $f)
+              (func $g)
+              (elem (i32.const 1) $f $g)
+              (elem (table $t) (i32.const 2) funcref (ref.func $f) (ref.null func))
+              (elem declare func $g)
+            )"#
+    )).unwrap();
+
+    let table = instance.table(0).unwrap();
+    assert_eq!(table.size(), 4);
+    assert_eq!(table.read(0, 4), Ok(vec![FuncRef(None), FuncRef(Some(0)), FuncRef(Some(0)), FuncRef(None)]));
+}
+
+#[test]
+fn active_out_of_bounds() {
+    let mut env = Environment::default();
+    let result = env.spawn(wat_source::string(
+        r#"(module
+              (table 2 funcref)
+              (func $f)
+              (elem (i32.const 1) $f $f)
+            )"#
+    ));
+
+    assert!(matches!(result, Err(EnvironmentError::Trapped(Trap::TableOutOfBounds))));
+}
+
+#[test]
+fn passive_is_not_copied() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (table 1 funcref)
+              (func $f (result i32) (i32.const 1))
+              (elem func $f)
+              (func (export "call") (result i32)
+                (call_indirect (result i32) (i32.const 0))
+              )
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("call", []), Err(Trap::UninitializedElement(0)));
+    assert_eq!(instance.table(0).unwrap().get(0), Ok(FuncRef(None)));
+}
+
+#[test]
+fn instances_own_their_table() {
+    let mut env = Environment::default();
+    let module = env.load(wat_source::string(
+        r#"(module
+              (table 2 funcref)
+              (elem (i32.const 0) $f)
+              (func $f (result i32) (i32.const 1))
+              (func (export "clear") (table.set (i32.const 0) (ref.null func)))
+              (func (export "call") (result i32) (call_indirect (result i32) (i32.const 0)))
+            )"#
+    )).unwrap();
+
+    let first = env.instantiate(module).unwrap().id();
+    env.instance(first).unwrap().invoke("clear", []).unwrap();
+
+    // the active segment is copied into the new table only
+    let second = env.instantiate(module).unwrap().id();
+    assert_eq!(env.instance(second).unwrap().invoke("call", []).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(env.instance(first).unwrap().invoke("call", []), Err(Trap::UninitializedElement(0)));
+}
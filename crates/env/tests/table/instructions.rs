@@ -0,0 +1,96 @@
+use hal_core::module::Value::{ExternRef, FuncRef, I32};
+use hal_core::Trap;
+use hal_env::{Environment, SpawnWat, wat_source};
+
+#[test]
+fn get_and_set() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (table $t 2 externref)
+              (func (export "get") (param i32) (result externref) (table.get $t (local.get 0)))
+              (func (export "set") (param i32 externref) (table.set $t (local.get 0) (local.get 1)))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("get", [I32(1)]).unwrap().as_ref(), [ExternRef(None)]);
+    assert!(instance.invoke("set", [I32(1), ExternRef(Some(7))]).is_ok());
+    assert_eq!(instance.invoke("get", [I32(1)]).unwrap().as_ref(), [ExternRef(Some(7))]);
+
+    assert_eq!(instance.invoke("get", [I32(2)]), Err(Trap::TableOutOfBounds));
+    assert_eq!(instance.invoke("set", [I32(2), ExternRef(None)]), Err(Trap::TableOutOfBounds));
+}
+
+#[test]
+fn size_and_grow() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (table $t 1 3 externref)
+              (func (export "size") (result i32) (table.size $t))
+              (func (export "grow") (param i32 externref) (result i32) (table.grow $t (local.get 1) (local.get 0)))
+              (func (export "get") (param i32) (result externref) (table.get $t (local.get 0)))
+            )"#
+    )).unwrap();
+
+    assert_eq!(instance.invoke("size", []).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(instance.invoke("grow", [I32(2), ExternRef(Some(3))]).unwrap().as_ref(), [I32(1)]);
+    assert_eq!(instance.invoke("size", []).unwrap().as_ref(), [I32(3)]);
+    assert_eq!(instance.invoke("get", [I32(2)]).unwrap().as_ref(), [ExternRef(Some(3))]);
+
+    assert_eq!(instance.invoke("grow", [I32(1), ExternRef(None)]).unwrap().as_ref(), [I32(-1)]);
+    assert_eq!(instance.invoke("grow", [I32(-1), ExternRef(None)]).unwrap().as_ref(), [I32(-1)]);
+    assert_eq!(instance.invoke("size", []).unwrap().as_ref(), [I32(3)]);
+}
+
+#[test]
+fn fill_and_copy() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (table $a 4 externref)
+              (table $b 4 externref)
+              (func (export "fill") (param i32 externref i32) (table.fill $a (local.get 0) (local.get 1) (local.get 2)))
+              (func (export "copy-within") (param i32 i32 i32) (table.copy $a $a (local.get 0) (local.get 1) (local.get 2)))
+              (func (export "copy-to-b") (param i32 i32 i32) (table.copy $b $a (local.get 0) (local.get 1) (local.get 2)))
+            )"#
+    )).unwrap();
+
+    assert!(instance.invoke("fill", [I32(1), ExternRef(Some(1)), I32(2)]).is_ok());
+    assert!(instance.invoke("copy-within", [I32(2), I32(1), I32(2)]).is_ok());
+    assert!(instance.invoke("copy-to-b", [I32(0), I32(1), I32(3)]).is_ok());
+
+    let a = instance.table(0).unwrap();
+    let b = instance.table(1).unwrap();
+    assert_eq!(a.read(0, 4), Ok(vec![ExternRef(None), ExternRef(Some(1)), ExternRef(Some(1)), ExternRef(Some(1))]));
+    assert_eq!(b.read(0, 4), Ok(vec![ExternRef(Some(1)), ExternRef(Some(1)), ExternRef(Some(1)), ExternRef(None)]));
+
+    assert_eq!(instance.invoke("fill", [I32(3), ExternRef(None), I32(2)]), Err(Trap::TableOutOfBounds));
+    assert_eq!(instance.invoke("copy-to-b", [I32(2), I32(0), I32(3)]), Err(Trap::TableOutOfBounds));
+}
+
+#[test]
+fn init_and_drop() {
+    let mut env = Environment::default();
+    let instance = env.spawn(wat_source::string(
+        r#"(module
+              (table 4 funcref)
+              (func $f)
+              (func $g)
+              (elem $e func $f $g)
+              (func (export "init") (param i32 i32 i32) (table.init $e (local.get 0) (local.get 1) (local.get 2)))
+              (func (export "drop") (elem.drop $e))
+            )"#
+    )).unwrap();
+
+    assert!(instance.invoke("init", [I32(2), I32(0), I32(2)]).is_ok());
+    assert_eq!(instance.invoke("init", [I32(0), I32(1), I32(2)]), Err(Trap::TableOutOfBounds));
+    assert_eq!(instance.invoke("init", [I32(3), I32(0), I32(2)]), Err(Trap::TableOutOfBounds));
+
+    assert!(instance.invoke("drop", []).is_ok());
+    assert!(instance.invoke("init", [I32(0), I32(0), I32(0)]).is_ok());
+    assert_eq!(instance.invoke("init", [I32(0), I32(0), I32(1)]), Err(Trap::TableOutOfBounds));
+
+    let table = instance.table(0).unwrap();
+    assert_eq!(table.read(0, 4), Ok(vec![FuncRef(None), FuncRef(None), FuncRef(Some(0)), FuncRef(Some(1))]));
+}
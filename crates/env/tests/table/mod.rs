@@ -0,0 +1,3 @@
+mod call_indirect;
+mod element;
+mod instructions;
@@ -0,0 +1,48 @@
+use alloc::rc::{Rc, Weak};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use hal_core::module::{Function, FunctionAddress};
+use hal_core::{Trap, TrapNotFound};
+
+use crate::{Result, Store};
+
+/// The function instances of all stores of an environment.
+///
+/// Function references and imported functions use addresses into this space, so a reference
+/// keeps pointing to the same function no matter which instance reads it from a shared table.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Default)]
+pub struct Functions {
+    instances: RefCell<Vec<FunctionInstance>>,
+}
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone)]
+pub(crate) struct FunctionInstance {
+    pub(crate) function: Rc<Function>,
+    // the store the function body runs against, empty for host functions
+    pub(crate) store: Weak<Store>,
+}
+
+impl Functions {
+    /// Adds a host function, which does not belong to any store.
+    pub fn host(&self, function: Rc<Function>) -> FunctionAddress {
+        self.allocate(function, Weak::new())
+    }
+
+    /// Returns the function at `addr`.
+    pub fn function(&self, addr: FunctionAddress) -> core::result::Result<Rc<Function>, Trap> {
+        self.get(addr).map(|instance| instance.function)
+    }
+
+    pub(crate) fn allocate(&self, function: Rc<Function>, store: Weak<Store>) -> FunctionAddress {
+        let mut instances = self.instances.borrow_mut();
+        instances.push(FunctionInstance { function, store });
+        (instances.len() - 1) as FunctionAddress
+    }
+
+    pub(crate) fn get(&self, addr: FunctionAddress) -> Result<FunctionInstance> {
+        self.instances.borrow().get(addr as usize).cloned().ok_or(Trap::NotFound(TrapNotFound::FunctionLocal(addr)))
+    }
+}
@@ -0,0 +1,39 @@
+use alloc::boxed::Box;
+use core::fmt::{Debug, Formatter};
+
+use hal_core::module::MemoryAddress;
+
+/// Lets an embedder observe and veto the growth of a process' memories.
+pub trait MemoryGrowHook {
+    /// Called before memory `addr` grows from `current` to `requested` pages.
+    /// Returning `false` makes `memory.grow` fail with -1 and leaves the memory untouched.
+    fn grow(&self, addr: MemoryAddress, current: u32, requested: u32) -> bool;
+}
+
+impl<F> MemoryGrowHook for F
+    where
+        F: Fn(MemoryAddress, u32, u32) -> bool,
+{
+    fn grow(&self, addr: MemoryAddress, current: u32, requested: u32) -> bool {
+        self(addr, current, requested)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Hooks {
+    pub(crate) memory_grow: Option<Box<dyn MemoryGrowHook>>,
+}
+
+impl Hooks {
+    pub(crate) fn allow_memory_grow(&self, addr: MemoryAddress, current: u32, requested: u32) -> bool {
+        self.memory_grow.as_ref().map_or(true, |hook| hook.grow(addr, current, requested))
+    }
+}
+
+impl Debug for Hooks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Hooks")
+            .field("memory_grow", &self.memory_grow.is_some())
+            .finish()
+    }
+}
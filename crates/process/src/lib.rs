@@ -7,14 +7,20 @@ extern crate core;
 
 use hal_core::Trap;
 
-pub use crate::process::Process;
+pub use crate::hook::MemoryGrowHook;
+pub use crate::process::{DEFAULT_MAX_CALL_DEPTH, Process};
 pub use crate::processor::Processor;
-pub use crate::store::Store;
+pub use crate::function::Functions;
+pub use crate::store::{ExternValue, Store, StoreError};
+pub use crate::typed::{TypedValue, TypedValues};
 
+mod function;
+mod hook;
 mod numeric;
 mod process;
 mod processor;
 mod stack;
 mod store;
+mod typed;
 
 type Result<T> = core::result::Result<T, Trap>;
@@ -75,4 +75,196 @@ impl Integer for u64 {
             Ok(self % rhs)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Floating point operations with the semantics WebAssembly requires, which differ from the
+/// ones of the primitive types in how NaN and signed zeros are treated.
+pub(crate) trait Float where Self: Sized {
+    fn wasm_abs(self) -> Self;
+    fn wasm_neg(self) -> Self;
+    fn wasm_copysign(self, rhs: Self) -> Self;
+
+    fn wasm_ceil(self) -> Self;
+    fn wasm_floor(self) -> Self;
+    fn wasm_trunc(self) -> Self;
+    fn wasm_nearest(self) -> Self;
+    fn wasm_sqrt(self) -> Self;
+
+    fn wasm_min(self, rhs: Self) -> Self;
+    fn wasm_max(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($ty: ty, $bits: ty, $ceil: path, $floor: path, $trunc: path, $rint: path, $sqrt: path) => {
+        impl Float for $ty {
+            fn wasm_abs(self) -> Self {
+                <$ty>::from_bits(self.to_bits() & !SIGN)
+            }
+
+            fn wasm_neg(self) -> Self {
+                <$ty>::from_bits(self.to_bits() ^ SIGN)
+            }
+
+            fn wasm_copysign(self, rhs: Self) -> Self {
+                <$ty>::from_bits((self.to_bits() & !SIGN) | (rhs.to_bits() & SIGN))
+            }
+
+            fn wasm_ceil(self) -> Self {
+                quiet($ceil(self))
+            }
+
+            fn wasm_floor(self) -> Self {
+                quiet($floor(self))
+            }
+
+            fn wasm_trunc(self) -> Self {
+                quiet($trunc(self))
+            }
+
+            // rounds half-way cases to even
+            fn wasm_nearest(self) -> Self {
+                quiet($rint(self))
+            }
+
+            fn wasm_sqrt(self) -> Self {
+                quiet($sqrt(self))
+            }
+
+            fn wasm_min(self, rhs: Self) -> Self {
+                if self.is_nan() || rhs.is_nan() {
+                    return quiet(self + rhs);
+                }
+                if self == rhs {
+                    // -0.0 is smaller than +0.0
+                    return <$ty>::from_bits(self.to_bits() | rhs.to_bits());
+                }
+                if self < rhs { self } else { rhs }
+            }
+
+            fn wasm_max(self, rhs: Self) -> Self {
+                if self.is_nan() || rhs.is_nan() {
+                    return quiet(self + rhs);
+                }
+                if self == rhs {
+                    // +0.0 is greater than -0.0
+                    return <$ty>::from_bits(self.to_bits() & rhs.to_bits());
+                }
+                if self > rhs { self } else { rhs }
+            }
+        }
+
+        const SIGN: $bits = 1 << (<$bits>::BITS - 1);
+        const QUIET: $bits = 1 << (<$ty>::MANTISSA_DIGITS - 2);
+
+        /// Sets the quiet bit of a NaN, arithmetic instructions never return a signaling NaN.
+        fn quiet(value: $ty) -> $ty {
+            if value.is_nan() {
+                <$ty>::from_bits(value.to_bits() | QUIET)
+            } else {
+                value
+            }
+        }
+    };
+}
+
+mod float32 {
+    use super::Float;
+
+    impl_float!(f32, u32, libm::ceilf, libm::floorf, libm::truncf, libm::rintf, libm::sqrtf);
+
+    pub(crate) fn demote(value: f64) -> f32 {
+        quiet(value as f32)
+    }
+}
+
+mod float64 {
+    use super::Float;
+
+    impl_float!(f64, u64, libm::ceil, libm::floor, libm::trunc, libm::rint, libm::sqrt);
+
+    pub(crate) fn promote(value: f32) -> f64 {
+        quiet(value as f64)
+    }
+}
+
+pub(crate) use float32::demote;
+pub(crate) use float64::promote;
+
+/// Truncation of a float towards zero into an integer, trapping if the result is not representable.
+pub(crate) trait TruncChecked<T> {
+    fn trunc_checked(self) -> Result<T, Trap>;
+}
+
+macro_rules! impl_trunc_checked {
+    ($from: ty => $to: ty, $min: expr, $max_exclusive: expr) => {
+        impl TruncChecked<$to> for $from {
+            fn trunc_checked(self) -> Result<$to, Trap> {
+                if self.is_nan() {
+                    return Err(Trap::InvalidConversionToInteger);
+                }
+                // every f32 is exactly representable as f64
+                let value = libm::trunc(self as f64);
+                if ($min..$max_exclusive).contains(&value) {
+                    Ok(value as $to)
+                } else {
+                    Err(Trap::Overflow(TrapOverflow::Integer))
+                }
+            }
+        }
+    };
+}
+
+impl_trunc_checked!(f32 => i32, -2147483648.0, 2147483648.0);
+impl_trunc_checked!(f32 => u32, 0.0, 4294967296.0);
+impl_trunc_checked!(f32 => i64, -9223372036854775808.0, 9223372036854775808.0);
+impl_trunc_checked!(f32 => u64, 0.0, 18446744073709551616.0);
+impl_trunc_checked!(f64 => i32, -2147483648.0, 2147483648.0);
+impl_trunc_checked!(f64 => u32, 0.0, 4294967296.0);
+impl_trunc_checked!(f64 => i64, -9223372036854775808.0, 9223372036854775808.0);
+impl_trunc_checked!(f64 => u64, 0.0, 18446744073709551616.0);
+
+#[cfg(test)]
+mod tests {
+    use hal_core::{Trap, TrapOverflow};
+
+    use super::*;
+
+    #[test]
+    fn nearest_rounds_half_to_even() {
+        assert_eq!(0.5f32.wasm_nearest(), 0.0);
+        assert_eq!(1.5f32.wasm_nearest(), 2.0);
+        assert_eq!(2.5f32.wasm_nearest(), 2.0);
+        assert_eq!((-2.5f64).wasm_nearest(), -2.0);
+        assert_eq!((-0.5f64).wasm_nearest().to_bits(), (-0.0f64).to_bits());
+    }
+
+    #[test]
+    fn min_max_signed_zero() {
+        assert_eq!(0.0f32.wasm_min(-0.0).to_bits(), (-0.0f32).to_bits());
+        assert_eq!((-0.0f32).wasm_max(0.0).to_bits(), 0.0f32.to_bits());
+        assert_eq!(0.0f64.wasm_min(-0.0).to_bits(), (-0.0f64).to_bits());
+        assert_eq!((-0.0f64).wasm_max(0.0).to_bits(), 0.0f64.to_bits());
+    }
+
+    #[test]
+    fn min_max_propagate_nan() {
+        assert!(1.0f32.wasm_min(f32::NAN).is_nan());
+        assert!(f64::NAN.wasm_max(1.0).is_nan());
+    }
+
+    #[test]
+    fn unary_quiets_nan() {
+        let signaling = f32::from_bits(0x7FA0_0000);
+        assert_eq!(signaling.wasm_ceil().to_bits(), 0x7FE0_0000);
+        assert_eq!(signaling.wasm_neg().to_bits(), 0xFFA0_0000);
+    }
+
+    #[test]
+    fn trunc_checked() {
+        assert_eq!(TruncChecked::<i32>::trunc_checked(-2147483648.0f32), Ok(i32::MIN));
+        assert_eq!(TruncChecked::<i32>::trunc_checked(2147483648.0f32), Err(Trap::Overflow(TrapOverflow::Integer)));
+        assert_eq!(TruncChecked::<u32>::trunc_checked(-0.9f64), Ok(0));
+        assert_eq!(TruncChecked::<u32>::trunc_checked(-1.0f64), Err(Trap::Overflow(TrapOverflow::Integer)));
+        assert_eq!(TruncChecked::<u64>::trunc_checked(f64::NAN), Err(Trap::InvalidConversionToInteger));
+    }
+}
@@ -1,41 +1,131 @@
+use alloc::boxed::Box;
+use alloc::format;
 use alloc::rc::Rc;
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use hal_core::module::{Export, Function, FunctionAddress, FunctionLocal, Memory, MemoryAddress, Value, ValueType};
-use hal_core::Trap;
+use hal_core::module::{DataAddress, ElementAddress, Export, ExportData, Function, FunctionAddress, FunctionHost, FunctionLocal, Global, GlobalAddress, Memory, MemoryAddress, MemoryOffset, Table, TableAddress, TypeAddress, Value, ValueType};
+use hal_core::{Trap, TrapNotFound, TrapType};
 
-use crate::Result;
+use crate::hook::Hooks;
+use crate::{MemoryGrowHook, Result};
 use crate::stack::{CallFrame, Stack, StackAccess};
-use crate::Store;
+use crate::{ExternValue, Store};
+
+/// The number of nested wasm calls a process allows unless configured otherwise.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1024 * 16;
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub struct Process {
-    pub(crate) state: Store,
+    pub(crate) state: Rc<Store>,
     pub(crate) stack: Stack,
+    pub(crate) hooks: Hooks,
+    pub(crate) max_call_depth: usize,
 }
 
 
 impl Process {
-    pub fn new(state: Store) -> Self {
+    /// Creates a process running the functions of `state`.
+    pub fn new(state: Rc<Store>) -> Self {
         Self {
             state,
             stack: Stack::default(),
+            hooks: Hooks::default(),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
         }
     }
 
+    /// Returns the store the functions of this process run against.
+    pub fn state(&self) -> &Rc<Store> {
+        &self.state
+    }
+
+    /// Limits the number of nested wasm calls, a call beyond it traps with "call stack exhausted".
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.max_call_depth = depth;
+    }
+
+    /// Asks `hook` before `memory.grow` grows a memory.
+    pub fn set_memory_grow_hook(&mut self, hook: impl MemoryGrowHook + 'static) {
+        self.hooks.memory_grow = Some(Box::new(hook));
+    }
+
     pub fn function(&self, addr: FunctionAddress) -> core::result::Result<Rc<Function>, Trap> {
         self.state.function(addr)
     }
 
+    /// Returns the address of the exported function `name` within the function space, and the function.
+    pub fn exported_function(&self, name: impl Into<String>) -> core::result::Result<(FunctionAddress, Rc<Function>), Trap> {
+        let name = name.into();
+        let idx = match self.state.export(name.clone())?.data() {
+            ExportData::Function(idx) => *idx,
+            _ => return Err(Trap::NotFound(TrapNotFound::ExportedFunction(name))),
+        };
+        let addr = self.state.function_address(idx)?;
+        Ok((addr, self.state.function_space().function(addr)?))
+    }
+
     pub fn export(&self, name: impl Into<String>) -> core::result::Result<Rc<Export>, Trap> {
-        self.export(name)
+        self.state.export(name)
+    }
+
+    /// Returns the export `name`, resolved to the function address or the instance it refers to.
+    pub fn get_export(&self, name: impl Into<String>) -> core::result::Result<ExternValue, Trap> {
+        let name = name.into();
+        let Some(export) = self.state.exports().iter().find(|export| export.name() == name) else {
+            return Err(Trap::NotFound(TrapNotFound::Export(name)));
+        };
+        self.state.extern_value(export.data())
+    }
+
+    /// Returns the table at `addr`, counting imported tables first.
+    pub fn table(&self, addr: TableAddress) -> core::result::Result<Rc<Table>, Trap> {
+        self.state.table(addr)
     }
 
     pub fn memory(&self, addr: MemoryAddress) -> core::result::Result<Rc<Memory>, Trap> {
         self.state.memory(addr)
     }
 
+    /// Returns the global at `addr`, counting imported globals first.
+    pub fn global(&self, addr: GlobalAddress) -> core::result::Result<Rc<Global>, Trap> {
+        self.state.global(addr)
+    }
+
+    /// Returns the exports by name, resolved to the values importing modules are linked with.
+    pub fn extern_values(&self) -> core::result::Result<Vec<(String, ExternValue)>, Trap> {
+        self.state.exports().iter()
+            .map(|export| Ok((String::from(export.name()), self.state.extern_value(export.data())?)))
+            .collect()
+    }
+
+    /// Returns the global exported as `name`.
+    pub fn exported_global(&self, name: impl Into<String>) -> core::result::Result<Rc<Global>, Trap> {
+        let name = name.into();
+        match self.state.export(name.clone())?.data() {
+            ExportData::Global(addr) => self.state.global(*addr),
+            _ => Err(Trap::NotFound(TrapNotFound::ExportedGlobal(name))),
+        }
+    }
+
+    /// Returns the memory exported as `name`.
+    pub fn exported_memory(&self, name: impl Into<String>) -> core::result::Result<Rc<Memory>, Trap> {
+        let name = name.into();
+        match self.state.export(name.clone())?.data() {
+            ExportData::Memory(addr) => self.state.memory(*addr),
+            _ => Err(Trap::NotFound(TrapNotFound::ExportedMemory(name))),
+        }
+    }
+
+    /// Returns the table exported as `name`.
+    pub fn exported_table(&self, name: impl Into<String>) -> core::result::Result<Rc<Table>, Trap> {
+        let name = name.into();
+        match self.state.export(name.clone())?.data() {
+            ExportData::Table(addr) => self.state.table(*addr),
+            _ => Err(Trap::NotFound(TrapNotFound::ExportedTable(name))),
+        }
+    }
+
     pub(crate) fn unary<T, F>(&mut self, op: F) -> Result<()>
         where
             T: StackAccess,
@@ -64,13 +154,23 @@ impl Process {
         self.stack.push(result)
     }
 
+    pub(crate) fn unary_trap<T, U, F>(&mut self, op: F) -> Result<()>
+        where
+            T: StackAccess,
+            U: StackAccess,
+            F: FnOnce(T) -> Result<U>,
+    {
+        let result = op(self.stack.pop()?)?;
+        self.stack.push(result)
+    }
+
     pub(crate) fn binary<T, F>(&mut self, op: F) -> Result<()>
         where
             T: StackAccess,
             F: FnOnce(T, T) -> T,
     {
-        let l = self.stack.pop()?;
         let r = self.stack.pop()?;
+        let l = self.stack.pop()?;
         self.stack.push(op(l, r))
     }
 
@@ -79,8 +179,8 @@ impl Process {
             T: StackAccess,
             F: FnOnce(T, T) -> Result<T>,
     {
-        let l = self.stack.pop()?;
         let r = self.stack.pop()?;
+        let l = self.stack.pop()?;
         self.stack.push(op(l, r)?)
     }
 
@@ -89,24 +189,204 @@ impl Process {
             T: StackAccess,
             F: FnOnce(T, T) -> bool,
     {
-        let l = self.stack.pop()?;
         let r = self.stack.pop()?;
+        let l = self.stack.pop()?;
         let result = op(l, r);
         self.stack.push(if result { Value::I32(1) } else { Value::I32(0) })
     }
 
-    pub(crate) fn push_frame(&mut self, func: &FunctionLocal) -> Result<CallFrame> {
+    pub(crate) fn load<T, const N: usize, F>(&mut self, offset: MemoryOffset, op: F) -> Result<()>
+        where
+            T: StackAccess,
+            F: FnOnce([u8; N]) -> T,
+    {
+        let addr: u32 = self.stack.pop()?;
+        let bytes = self.state.memory(0)?.load(addr, offset)?;
+        self.stack.push(op(bytes))
+    }
+
+    pub(crate) fn store<T, const N: usize, F>(&mut self, offset: MemoryOffset, op: F) -> Result<()>
+        where
+            T: StackAccess,
+            F: FnOnce(T) -> [u8; N],
+    {
+        let value = self.stack.pop()?;
+        let addr: u32 = self.stack.pop()?;
+        self.state.memory(0)?.store(addr, offset, op(value))
+    }
+
+    pub(crate) fn memory_copy(&mut self, dest_addr: MemoryAddress, src_addr: MemoryAddress) -> Result<()> {
+        let len: u32 = self.stack.pop()?;
+        let src: u32 = self.stack.pop()?;
+        let dest: u32 = self.stack.pop()?;
+
+        let dest_memory = self.state.memory(dest_addr)?;
+        let src_memory = self.state.memory(src_addr)?;
+        if Rc::ptr_eq(&dest_memory, &src_memory) {
+            dest_memory.copy_within(dest, src, len)
+        } else {
+            dest_memory.write(dest, &src_memory.read(src, len)?)
+        }
+    }
+
+    pub(crate) fn memory_fill(&mut self, addr: MemoryAddress) -> Result<()> {
+        let len: u32 = self.stack.pop()?;
+        let value: i32 = self.stack.pop()?;
+        let dest: u32 = self.stack.pop()?;
+
+        self.state.memory(addr)?.fill(dest, value as u8, len)
+    }
+
+    pub(crate) fn memory_grow(&mut self, addr: MemoryAddress) -> Result<()> {
+        let delta: u32 = self.stack.pop()?;
+        let memory = self.state.memory(addr)?;
+
+        let current = memory.pages();
+        let result = match current.checked_add(delta) {
+            // the hook only sees requests within the maximum
+            Some(requested) if requested <= memory.max_pages() && self.hooks.allow_memory_grow(addr, current, requested) => memory.grow(delta),
+            _ => None,
+        };
+
+        self.stack.push(result.map_or(-1, |previous| previous as i32))
+    }
+
+    pub(crate) fn memory_init(&mut self, segment: DataAddress, addr: MemoryAddress) -> Result<()> {
+        let len: u32 = self.stack.pop()?;
+        let offset: u32 = self.stack.pop()?;
+        let dest: u32 = self.stack.pop()?;
+
+        let data = self.state.data(segment)?;
+        let bytes = data
+            .get(offset as usize..)
+            .and_then(|data| data.get(..len as usize))
+            .ok_or(Trap::MemoryOutOfBounds)?;
+        self.state.memory(addr)?.write(dest, bytes)
+    }
+
+    pub(crate) fn table_get(&mut self, addr: TableAddress) -> Result<()> {
+        let idx: u32 = self.stack.pop()?;
+        let value = self.state.table(addr)?.get(idx)?;
+        self.stack.push(value)
+    }
+
+    pub(crate) fn table_set(&mut self, addr: TableAddress) -> Result<()> {
+        let value: Value = self.stack.pop()?;
+        let idx: u32 = self.stack.pop()?;
+        self.state.table(addr)?.set(idx, value)
+    }
+
+    pub(crate) fn table_size(&mut self, addr: TableAddress) -> Result<()> {
+        let size = self.state.table(addr)?.size();
+        self.stack.push(size as i32)
+    }
+
+    pub(crate) fn table_grow(&mut self, addr: TableAddress) -> Result<()> {
+        let delta: u32 = self.stack.pop()?;
+        let init: Value = self.stack.pop()?;
+
+        let result = self.state.table(addr)?.grow(delta, init)?;
+        self.stack.push(result.map_or(-1, |previous| previous as i32))
+    }
+
+    pub(crate) fn table_fill(&mut self, addr: TableAddress) -> Result<()> {
+        let len: u32 = self.stack.pop()?;
+        let value: Value = self.stack.pop()?;
+        let dest: u32 = self.stack.pop()?;
+
+        self.state.table(addr)?.fill(dest, value, len)
+    }
+
+    pub(crate) fn table_copy(&mut self, dest_addr: TableAddress, src_addr: TableAddress) -> Result<()> {
+        let len: u32 = self.stack.pop()?;
+        let src: u32 = self.stack.pop()?;
+        let dest: u32 = self.stack.pop()?;
+
+        let dest_table = self.state.table(dest_addr)?;
+        let src_table = self.state.table(src_addr)?;
+        if Rc::ptr_eq(&dest_table, &src_table) {
+            dest_table.copy_within(dest, src, len)
+        } else {
+            dest_table.write(dest, &src_table.read(src, len)?)
+        }
+    }
+
+    pub(crate) fn table_init(&mut self, segment: ElementAddress, addr: TableAddress) -> Result<()> {
+        let len: u32 = self.stack.pop()?;
+        let offset: u32 = self.stack.pop()?;
+        let dest: u32 = self.stack.pop()?;
+
+        let element = self.state.element(segment)?;
+        let values = element
+            .get(offset as usize..)
+            .and_then(|element| element.get(..len as usize))
+            .ok_or(Trap::TableOutOfBounds)?;
+        self.state.table(addr)?.write(dest, values)
+    }
+
+    /// Pops the table index of `call_indirect` and resolves the address of the function it refers to.
+    pub(crate) fn indirect_function(&mut self, type_addr: TypeAddress, table_addr: TableAddress) -> Result<FunctionAddress> {
+        let idx: u32 = self.stack.pop()?;
+
+        let addr = match self.state.table(table_addr)?.get(idx) {
+            Ok(Value::FuncRef(Some(addr))) => addr,
+            Ok(Value::FuncRef(None)) => return Err(Trap::UninitializedElement(idx)),
+            Ok(_) => return Err(Trap::IndirectCallTypeMismatch),
+            Err(_) => return Err(Trap::UndefinedElement),
+        };
+
+        let function = self.state.function_space().function(addr)?;
+        let expected = self.state.function_type(type_addr)?;
+        if function.signature() != expected {
+            return Err(Trap::IndirectCallTypeMismatch);
+        }
+        Ok(addr)
+    }
+
+    // host functions see the memory of the calling instance, the results are checked against the signature
+    pub(crate) fn call_host(&mut self, func: &FunctionHost) -> Result<()> {
+        let signature = func.signature();
+        let mut args = Vec::with_capacity(signature.params().len());
+        for _ in signature.params().iter() {
+            args.push(self.stack.pop()?);
+        }
+        args.reverse();
+
+        let memory = self.state.memory(0).ok();
+        let results = func.call(memory.as_deref(), &args)?;
+
+        if results.len() != signature.results().len() {
+            return Err(Trap::Host(format!("host function returned {} values, expected {}", results.len(), signature.results().len())));
+        }
+        for (value, expected) in results.iter().zip(signature.results().iter()) {
+            if value.value_type() != *expected {
+                return Err(Trap::Type(TrapType::Mismatch(expected.clone(), value.value_type())));
+            }
+            self.stack.push(value.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Enters `func`, taking its arguments from the stack. `caller_store` is set when the caller
+    /// runs against another store, which is restored once the function returns.
+    pub(crate) fn push_frame(&mut self, func: &FunctionLocal, caller_store: Option<Rc<Store>>) -> Result<()> {
         let mut locals = Vec::with_capacity(func.parameter_count());
 
         for _ in func.parameters().iter() {
             locals.push(self.stack.pop()?);
         }
+        // the last parameter is on top of the stack
+        locals.reverse();
 
 
         for local in func.locals().iter() {
             match local {
                 ValueType::I32 => locals.push(Value::I32(0)),
                 ValueType::I64 => locals.push(Value::I64(0)),
+                ValueType::F32 => locals.push(Value::F32(0.0)),
+                ValueType::F64 => locals.push(Value::F64(0.0)),
+                ValueType::FuncRef => locals.push(Value::FuncRef(None)),
+                ValueType::ExternRef => locals.push(Value::ExternRef(None)),
             }
         }
 
@@ -118,13 +398,18 @@ impl Process {
             instructions: func.instructions().clone(),
             arity,
             locals: locals.into(),
+            labels: Vec::new(),
+            caller_store,
         };
 
-        Ok(self.stack.replace_frame(frame))
+        self.stack.push_frame(frame, self.max_call_depth)
     }
 
-
-    pub(crate) fn restore_frame(&mut self, frame: CallFrame) {
-        let _ = self.stack.restore(frame);
+    /// Returns to the caller of the current function, along with the store it runs against.
+    pub(crate) fn pop_frame(&mut self) -> Result<()> {
+        if let Some(store) = self.stack.pop_frame()?.caller_store {
+            self.state = store;
+        }
+        Ok(())
     }
 }
@@ -1,19 +1,21 @@
 use alloc::boxed::Box;
+use alloc::rc::Rc;
 use alloc::string::String;
 use alloc::vec;
 use core::ops::{BitAnd, BitOr, BitXor};
 
-use hal_core::{module, Trap, TrapNotFound, TrapNotImplemented};
-use hal_core::module::{ExportData, Function, Instruction, MemoryAddress, Value};
-use hal_core::module::FunctionAddress;
+use hal_core::{module, Trap, TrapNotFound, TrapNotImplemented, TrapType};
+use hal_core::module::{Function, Instruction, Value, ValueType};
+use hal_core::module::{FunctionAddress, TypeAddress};
 use hal_core::module::ValueType::I32;
-use module::FunctionLocal;
 
-use crate::numeric::Integer;
+use crate::numeric::{demote, Float, Integer, promote, TruncChecked};
 use crate::process::Process;
+use crate::stack::{Arity, Label, LabelKind};
+use crate::TypedValues;
 
 pub enum ProcessingState {
-    Break,
+    Break(u32),
     Continue,
     Return,
 }
@@ -32,23 +34,114 @@ impl Default for Processor {
 }
 
 impl Processor {
+    /// Runs until the current frame returns, the frames of the calls it makes are run by the same
+    /// loop instead of recursing.
     fn until_completion(&self, process: &mut Process) -> Result<(), Trap> {
+        let entry = process.stack.depth();
         loop {
-            match self.next(process) {
-                Ok(state) => {
-                    match state {
-                        ProcessingState::Break => todo!(),
-                        ProcessingState::Continue => continue,
-                        ProcessingState::Return => return Ok(())
-                    }
-                }
-                Err(t) => return Err(t)
+            let state = match self.next(process)? {
+                ProcessingState::Break(depth) => self.branch(process, depth)?,
+                state => state,
             };
+            if let ProcessingState::Return = state {
+                process.pop_frame()?;
+                if process.stack.depth() < entry {
+                    return Ok(());
+                }
+            }
         }
     }
 
     fn try_complete() {}
 
+    /// Branches to the label at the given depth, counted from the innermost label.
+    ///
+    /// A depth beyond the innermost label of the frame targets the function body itself and
+    /// returns from the function.
+    fn branch(&self, process: &mut Process, depth: u32) -> ProcessorResult {
+        let stack = &mut process.stack;
+        let depth = depth as usize;
+        let labels = stack.frame.labels.len();
+
+        if depth >= labels {
+            let (sp, arity) = (stack.frame.sp, stack.frame.arity);
+            stack.unwind(sp, arity)?;
+            return Ok(ProcessingState::Return);
+        }
+
+        let label = stack.frame.labels[labels - 1 - depth];
+        match label.kind {
+            LabelKind::Block => stack.frame.labels.truncate(labels - 1 - depth),
+            LabelKind::Loop => stack.frame.labels.truncate(labels - depth),
+        }
+        stack.unwind(label.sp, label.arity)?;
+        stack.frame.ip = label.ip;
+        Ok(ProcessingState::Continue)
+    }
+
+    /// Enters a block taking `params` values from the stack and leaving `results` values on it.
+    ///
+    /// A branch to a block carries its results, a branch to a loop carries its parameters.
+    fn enter(&self, process: &mut Process, kind: LabelKind, params: Arity, results: Arity, end: u32) {
+        let stack = &mut process.stack;
+        let (ip, arity) = match kind {
+            LabelKind::Block => (end as isize, results),
+            LabelKind::Loop => (stack.frame.ip, params),
+        };
+        let sp = stack.len() - params;
+        stack.frame.labels.push(Label { kind, arity, sp, ip });
+    }
+
+    fn enter_if(&self, process: &mut Process, params: Arity, results: Arity, target: u32) -> Result<(), Trap> {
+        let condition: i32 = process.stack.pop()?;
+        let end = match process.stack.frame.instructions.get(target as usize) {
+            Some(Instruction::Else(end)) => Some(*end),
+            _ => None,
+        };
+
+        match (condition != 0, end) {
+            (true, Some(end)) => self.enter(process, LabelKind::Block, params, results, end),
+            (true, None) => self.enter(process, LabelKind::Block, params, results, target),
+            (false, Some(end)) => {
+                self.enter(process, LabelKind::Block, params, results, end);
+                process.stack.frame.ip = target as isize;
+            }
+            // no else branch - skip the whole body including its end
+            (false, None) => process.stack.frame.ip = target as isize,
+        }
+        Ok(())
+    }
+
+    /// Calls the function at `addr` in place of the current one, returning whatever it returns.
+    ///
+    /// A local function replaces the current frame, so that tail recursion runs in constant space.
+    /// Host functions are called as usual before returning.
+    fn return_call(&self, process: &mut Process, addr: FunctionAddress) -> ProcessorResult {
+        let instance = process.state.function_space().get(addr)?;
+        let local = match &*instance.function {
+            Function::Host(host) => {
+                process.call_host(host)?;
+                let stack = &mut process.stack;
+                let (sp, arity) = (stack.frame.sp, stack.frame.arity);
+                stack.unwind(sp, arity)?;
+                return Ok(ProcessingState::Return);
+            }
+            Function::Local(local) => local,
+        };
+        let Some(store) = instance.store.upgrade() else {
+            return Err(Trap::NotFound(TrapNotFound::FunctionLocal(addr)));
+        };
+
+        // only the arguments survive the current frame
+        let sp = process.stack.frame.sp;
+        process.stack.unwind(sp, local.parameter_count())?;
+        let caller_store = process.stack.pop_frame()?.caller_store
+            .or_else(|| (!Rc::ptr_eq(&store, &process.state)).then(|| process.state.clone()));
+        process.push_frame(local, caller_store)?;
+        process.state = store;
+        Ok(ProcessingState::Continue)
+    }
+
     fn next(&self, process: &mut Process) -> ProcessorResult {
         let stack = &mut process.stack;
         stack.frame.ip += 1;
@@ -56,34 +149,111 @@ impl Processor {
         let inst = { stack.frame.instructions.get(stack.frame.ip as usize).unwrap().clone() };
 
         match inst {
+            Instruction::AbsF32 => process.unary(f32::wasm_abs)?,
+            Instruction::AbsF64 => process.unary(f64::wasm_abs)?,
+
+            Instruction::AddF32 => process.binary(|l: f32, r| l + r)?,
+            Instruction::AddF64 => process.binary(|l: f64, r| l + r)?,
             Instruction::AddI32 => process.binary(i32::wrapping_add)?,
             Instruction::AddI64 => process.binary(i64::wrapping_add)?,
             Instruction::AndI32 => process.binary(i32::bitand)?,
             Instruction::AndI64 => process.binary(i64::bitand)?,
 
-            Instruction::Call(addr) => {
-                let function = process.state.function(addr).unwrap();
-                match &*function {
-                    Function::Local(local) => invoke_internal(process, self, local)?
+            Instruction::Block(end) => self.enter(process, LabelKind::Block, 0, 0, end),
+            Instruction::BlockWithType(_, end) => self.enter(process, LabelKind::Block, 0, 1, end),
+            Instruction::BlockWithFuncType(type_addr, end) => {
+                let (params, results) = block_type(process, type_addr)?;
+                self.enter(process, LabelKind::Block, params, results, end);
+            }
+
+            Instruction::Br(depth) => return Ok(ProcessingState::Break(depth)),
+            Instruction::BrIf(depth) => {
+                let condition: i32 = process.stack.pop()?;
+                if condition != 0 {
+                    return Ok(ProcessingState::Break(depth));
+                }
+            }
+            Instruction::BrTable(count, default) => {
+                let idx: u32 = stack.pop()?;
+                let depth = if idx < count {
+                    match stack.frame.instructions.get(stack.frame.ip as usize + 1 + idx as usize) {
+                        Some(Instruction::BrLabel(depth)) => *depth,
+                        _ => default,
+                    }
+                } else {
+                    default
                 };
+                return Ok(ProcessingState::Break(depth));
             }
+
+            Instruction::Call(idx) => {
+                let addr = process.state.function_address(idx)?;
+                call(process, addr)?;
+            }
+            Instruction::CallIndirect(type_addr, table_addr) => {
+                let addr = process.indirect_function(type_addr, table_addr)?;
+                call(process, addr)?;
+            }
+            Instruction::CeilF32 => process.unary(f32::wasm_ceil)?,
+            Instruction::CeilF64 => process.unary(f64::wasm_ceil)?,
+
             Instruction::ClzI32 => process.unary(|v: i32| v.leading_zeros() as i32)?,
             Instruction::ClzI64 => process.unary(|v: i64| v.leading_zeros() as i64)?,
 
             Instruction::ConstI32(value) => process.stack.push(Value::I32(value))?,
             Instruction::ConstI64(value) => process.stack.push(Value::I64(value))?,
 
+            Instruction::ConstF32(value) => process.stack.push(Value::F32(value))?,
+            Instruction::ConstF64(value) => process.stack.push(Value::F64(value))?,
+
+            Instruction::ConvertI32SF32 => process.unary_map(|v: i32| v as f32)?,
+            Instruction::ConvertI32SF64 => process.unary_map(|v: i32| v as f64)?,
+            Instruction::ConvertI32UF32 => process.unary_map(|v: u32| v as f32)?,
+            Instruction::ConvertI32UF64 => process.unary_map(|v: u32| v as f64)?,
+            Instruction::ConvertI64SF32 => process.unary_map(|v: i64| v as f32)?,
+            Instruction::ConvertI64SF64 => process.unary_map(|v: i64| v as f64)?,
+            Instruction::ConvertI64UF32 => process.unary_map(|v: u64| v as f32)?,
+            Instruction::ConvertI64UF64 => process.unary_map(|v: u64| v as f64)?,
+
+            Instruction::CopysignF32 => process.binary(f32::wasm_copysign)?,
+            Instruction::CopysignF64 => process.binary(f64::wasm_copysign)?,
+
             Instruction::CtzI32 => process.unary(|v: i32| v.trailing_zeros() as i32)?,
             Instruction::CtzI64 => process.unary(|v: i64| v.trailing_zeros() as i64)?,
 
+            Instruction::DataDrop(segment) => process.state.drop_data(segment)?,
+
+            Instruction::DemoteF64F32 => process.unary_map(demote)?,
+
+            Instruction::DivF32 => process.binary(|l: f32, r| l / r)?,
+            Instruction::DivF64 => process.binary(|l: f64, r| l / r)?,
+
             Instruction::DivSI32 => process.binary_trap(i32::div_checked)?,
             Instruction::DivSI64 => process.binary_trap(i64::div_checked)?,
 
             Instruction::DivUI32 => process.binary_trap(u32::div_checked)?,
             Instruction::DivUI64 => process.binary_trap(u64::div_checked)?,
 
-            Instruction::End => { return Ok(ProcessingState::Return); }
+            Instruction::Drop32
+            | Instruction::Drop64
+            | Instruction::Drop128
+            | Instruction::DropRef => { stack.pop::<Value>()?; }
+
+            Instruction::ElemDrop(segment) => process.state.drop_element(segment)?,
+
+            Instruction::Else(end) => {
+                stack.frame.labels.pop();
+                stack.frame.ip = end as isize;
+            }
 
+            Instruction::End => {
+                if stack.frame.labels.pop().is_none() {
+                    return Ok(ProcessingState::Return);
+                }
+            }
+
+            Instruction::EqF32 => process.binary_test(|l: f32, r| l == r)?,
+            Instruction::EqF64 => process.binary_test(|l: f64, r| l == r)?,
             Instruction::EqI32 => process.binary_test(|l: i32, r| l == r)?,
             Instruction::EqI64 => process.binary_test(|l: i64, r| l == r)?,
 
@@ -96,47 +266,143 @@ impl Processor {
             Instruction::Extend16SI64 => process.unary_map(|v: i64| i64::from(v as i16))?,
             Instruction::Extend32SI64 => process.unary_map(|v: i64| i64::from(v as i32))?,
 
+            Instruction::ExtendI32SI64 => process.unary_map(|v: i32| v as i64)?,
+            Instruction::ExtendI32UI64 => process.unary_map(|v: u32| v as i64)?,
+
+            Instruction::FloorF32 => process.unary(f32::wasm_floor)?,
+            Instruction::FloorF64 => process.unary(f64::wasm_floor)?,
+
+            Instruction::GeF32 => process.binary_test(|l: f32, r| l >= r)?,
+            Instruction::GeF64 => process.binary_test(|l: f64, r| l >= r)?,
             Instruction::GeSI32 => process.binary_test(|l: i32, r| l >= r)?,
             Instruction::GeSI64 => process.binary_test(|l: i64, r| l >= r)?,
 
             Instruction::GeUI32 => process.binary_test(|l: i32, r| (l as u32) >= r as u32)?,
             Instruction::GeUI64 => process.binary_test(|l: i64, r| (l as u64) >= r as u64)?,
 
+            Instruction::GlobalGet(addr) => {
+                let value = process.state.global(addr)?.get();
+                stack.push(value)?;
+            }
+            Instruction::GlobalSet32(addr)
+            | Instruction::GlobalSet64(addr)
+            | Instruction::GlobalSet128(addr)
+            | Instruction::GlobalSetRef(addr) => {
+                let value: Value = stack.pop()?;
+                process.state.global(addr)?.set(value)?;
+            }
+
+            Instruction::GtF32 => process.binary_test(|l: f32, r| l > r)?,
+            Instruction::GtF64 => process.binary_test(|l: f64, r| l > r)?,
             Instruction::GtSI32 => process.binary_test(|l: i32, r| l > r)?,
             Instruction::GtSI64 => process.binary_test(|l: i64, r| l > r)?,
 
             Instruction::GtUI32 => process.binary_test(|l: i32, r| (l as u32) > r as u32)?,
             Instruction::GtUI64 => process.binary_test(|l: i64, r| (l as u64) > r as u64)?,
 
+            Instruction::If(target) => self.enter_if(process, 0, 0, target)?,
+            Instruction::IfWithType(_, target) => self.enter_if(process, 0, 1, target)?,
+            Instruction::IfWithFuncType(type_addr, target) => {
+                let (params, results) = block_type(process, type_addr)?;
+                self.enter_if(process, params, results, target)?;
+            }
+
+            Instruction::LeF32 => process.binary_test(|l: f32, r| l <= r)?,
+            Instruction::LeF64 => process.binary_test(|l: f64, r| l <= r)?,
             Instruction::LeSI32 => process.binary_test(|l: i32, r| l <= r)?,
             Instruction::LeSI64 => process.binary_test(|l: i64, r| l <= r)?,
 
             Instruction::LeUI32 => process.binary_test(|l: i32, r| (l as u32) <= r as u32)?,
             Instruction::LeUI64 => process.binary_test(|l: i64, r| (l as u64) <= r as u64)?,
 
+            Instruction::LoadF32 { offset, .. } => process.load(offset, f32::from_le_bytes)?,
+            Instruction::LoadF64 { offset, .. } => process.load(offset, f64::from_le_bytes)?,
+            Instruction::LoadI32 { offset, .. } => process.load(offset, i32::from_le_bytes)?,
+            Instruction::LoadI64 { offset, .. } => process.load(offset, i64::from_le_bytes)?,
+
+            Instruction::Load8SI32 { offset, .. } => process.load(offset, |b| i8::from_le_bytes(b) as i32)?,
+            Instruction::Load8SI64 { offset, .. } => process.load(offset, |b| i8::from_le_bytes(b) as i64)?,
+            Instruction::Load8UI32 { offset, .. } => process.load(offset, |b| u8::from_le_bytes(b) as i32)?,
+            Instruction::Load8UI64 { offset, .. } => process.load(offset, |b| u8::from_le_bytes(b) as i64)?,
+            Instruction::Load16SI32 { offset, .. } => process.load(offset, |b| i16::from_le_bytes(b) as i32)?,
+            Instruction::Load16SI64 { offset, .. } => process.load(offset, |b| i16::from_le_bytes(b) as i64)?,
+            Instruction::Load16UI32 { offset, .. } => process.load(offset, |b| u16::from_le_bytes(b) as i32)?,
+            Instruction::Load16UI64 { offset, .. } => process.load(offset, |b| u16::from_le_bytes(b) as i64)?,
+            Instruction::Load32SI64 { offset, .. } => process.load(offset, |b| i32::from_le_bytes(b) as i64)?,
+            Instruction::Load32UI64 { offset, .. } => process.load(offset, |b| u32::from_le_bytes(b) as i64)?,
+
+            Instruction::LtF32 => process.binary_test(|l: f32, r| l < r)?,
+            Instruction::LtF64 => process.binary_test(|l: f64, r| l < r)?,
             Instruction::LtSI32 => process.binary_test(|l: i32, r| l < r)?,
             Instruction::LtSI64 => process.binary_test(|l: i64, r| l < r)?,
 
             Instruction::LtUI32 => process.binary_test(|l: i32, r| (l as u32) < r as u32)?,
             Instruction::LtUI64 => process.binary_test(|l: i64, r| (l as u64) < r as u64)?,
 
+            Instruction::Loop(end) | Instruction::LoopWithType(_, end) => self.enter(process, LabelKind::Loop, 0, 0, end),
+            Instruction::LoopWithFuncType(type_addr, end) => {
+                let (params, results) = block_type(process, type_addr)?;
+                self.enter(process, LabelKind::Loop, params, results, end);
+            }
+
+            Instruction::MaxF32 => process.binary(f32::wasm_max)?,
+            Instruction::MaxF64 => process.binary(f64::wasm_max)?,
+
+            Instruction::MemoryInit(segment, addr) => process.memory_init(segment, addr)?,
+            Instruction::MemoryCopy(dest, src) => process.memory_copy(dest, src)?,
+            Instruction::MemoryFill(addr) => process.memory_fill(addr)?,
+            Instruction::MemoryGrow(addr) => process.memory_grow(addr)?,
+            Instruction::MemorySize(addr) => {
+                let pages = process.state.memory(addr)?.pages();
+                stack.push(pages as i32)?;
+            }
+
+            Instruction::MinF32 => process.binary(f32::wasm_min)?,
+            Instruction::MinF64 => process.binary(f64::wasm_min)?,
+
+            Instruction::MulF32 => process.binary(|l: f32, r| l * r)?,
+            Instruction::MulF64 => process.binary(|l: f64, r| l * r)?,
             Instruction::MulI32 => process.binary(i32::wrapping_mul)?,
             Instruction::MulI64 => process.binary(i64::wrapping_mul)?,
 
+            Instruction::Nop => {}
+
+            Instruction::NearestF32 => process.unary(f32::wasm_nearest)?,
+            Instruction::NearestF64 => process.unary(f64::wasm_nearest)?,
+
+            Instruction::NeF32 => process.binary_test(|l: f32, r| l != r)?,
+            Instruction::NeF64 => process.binary_test(|l: f64, r| l != r)?,
             Instruction::NeI32 => process.binary_test(|l: i32, r| l != r)?,
             Instruction::NeI64 => process.binary_test(|l: i64, r| l != r)?,
 
-            Instruction::LocalGet32(addr) => {
+            Instruction::NegF32 => process.unary(f32::wasm_neg)?,
+            Instruction::NegF64 => process.unary(f64::wasm_neg)?,
+
+            Instruction::LocalGet32(addr)
+            | Instruction::LocalGet64(addr)
+            | Instruction::LocalGet128(addr)
+            | Instruction::LocalGetRef(addr) => {
                 let Some(value) = stack.frame.locals.get(addr as usize) else {
                     panic!("not found local");
                 };
                 stack.push(value.clone())?;
             }
-            Instruction::LocalSet32(addr) => {
+            Instruction::LocalSet32(addr)
+            | Instruction::LocalSet64(addr)
+            | Instruction::LocalSet128(addr)
+            | Instruction::LocalSetRef(addr) => {
                 let value = stack.pop()?;
                 let addr = addr as usize;
                 stack.frame.locals[addr] = value;
             }
+            Instruction::LocalTee32(addr)
+            | Instruction::LocalTee64(addr)
+            | Instruction::LocalTee128(addr)
+            | Instruction::LocalTeeRef(addr) => {
+                let value = stack.peek()?;
+                let addr = addr as usize;
+                stack.frame.locals[addr] = value;
+            }
 
             Instruction::OrI32 => process.binary(i32::bitor)?,
             Instruction::OrI64 => process.binary(i64::bitor)?,
@@ -144,49 +410,65 @@ impl Processor {
             Instruction::PopcntI32 => process.unary(|v: i32| v.count_ones() as i32)?,
             Instruction::PopcntI64 => process.unary(|v: i64| v.count_ones() as i64)?,
 
+            Instruction::PromoteF32F64 => process.unary_map(promote)?,
+
+            Instruction::RefFunc(idx) => {
+                let addr = process.state.function_address(idx)?;
+                stack.push(Value::FuncRef(Some(addr)))?;
+            }
+            Instruction::RefIsNull => {
+                let is_null = match stack.pop()? {
+                    Value::FuncRef(addr) => addr.is_none(),
+                    Value::ExternRef(addr) => addr.is_none(),
+                    value => return Err(Trap::Type(TrapType::Mismatch(ValueType::FuncRef, value.value_type()))),
+                };
+                stack.push(is_null as i32)?;
+            }
+            Instruction::RefNull(value_type) => {
+                let Some(value) = Value::null(&value_type) else {
+                    return Err(Trap::Type(TrapType::Mismatch(ValueType::FuncRef, value_type)));
+                };
+                stack.push(value)?;
+            }
+
+            Instruction::ReinterpretF32I32 => process.unary_map(|v: f32| v.to_bits() as i32)?,
+            Instruction::ReinterpretF64I64 => process.unary_map(|v: f64| v.to_bits() as i64)?,
+            Instruction::ReinterpretI32F32 => process.unary_map(|v: u32| f32::from_bits(v))?,
+            Instruction::ReinterpretI64F64 => process.unary_map(|v: u64| f64::from_bits(v))?,
+
             Instruction::RemSI32 => process.binary_trap(i32::rem_wrapping)?,
             Instruction::RemSI64 => process.binary_trap(i64::rem_wrapping)?,
 
             Instruction::RemUI32 => process.binary_trap(u32::rem_wrapping)?,
             Instruction::RemUI64 => process.binary_trap(u64::rem_wrapping)?,
 
+            Instruction::Return => {
+                let (sp, arity) = (stack.frame.sp, stack.frame.arity);
+                stack.unwind(sp, arity)?;
+                return Ok(ProcessingState::Return);
+            }
+            Instruction::ReturnCall(idx) => {
+                let addr = process.state.function_address(idx)?;
+                return self.return_call(process, addr);
+            }
+            Instruction::ReturnCallIndirect(type_addr, table_addr) => {
+                let addr = process.indirect_function(type_addr, table_addr)?;
+                return self.return_call(process, addr);
+            }
+
             Instruction::RotlI32 => process.binary(|l: i32, r| l.rotate_left(r as u32))?,
             Instruction::RotlI64 => process.binary(|l: i64, r| l.rotate_left(r as u32))?,
 
             Instruction::RotrI32 => process.binary(|l: i32, r| l.rotate_right(r as u32))?,
             Instruction::RotrI64 => process.binary(|l: i64, r| l.rotate_right(r as u32))?,
 
-            Instruction::StoreI32 { flags: _, offset } => {
-                let (value, addr): (Value, Value) = (process.stack.pop()?, process.stack.pop()?);
-
-                let addr = Into::<i32>::into(addr) as usize;
-                let offset = (offset) as usize;
-                let at = addr + offset;
-                let end = at + size_of::<i32>();
-
-                let memory = process
-                    .state
-                    .memory(addr as MemoryAddress)
-                    .unwrap();
-
-                let value: i32 = value.into();
-                memory.data.borrow_mut()[at..end].copy_from_slice(&value.to_le_bytes());
-            }
-            Instruction::StoreI64 { flags: _, offset } => {
-                let (value, addr): (Value, Value) = (process.stack.pop()?, process.stack.pop()?);
-
-                let addr = Into::<i64>::into(addr) as usize;
-                let offset = (offset) as usize;
-                let at = addr + offset;
-                let end = at + size_of::<i64>();
-
-                let memory = process
-                    .state
-                    .memory(addr as MemoryAddress)
-                    .unwrap();
-
-                let value: i64 = value.into();
-                memory.data.borrow_mut()[at..end].copy_from_slice(&value.to_le_bytes());
+            Instruction::Select32
+            | Instruction::Select64
+            | Instruction::Select128
+            | Instruction::SelectRef => {
+                let condition: i32 = stack.pop()?;
+                let (r, l): (Value, Value) = (stack.pop()?, stack.pop()?);
+                stack.push(if condition != 0 { l } else { r })?;
             }
 
             Instruction::ShlI32 => process.binary(|l: i32, r| l.wrapping_shl(r as u32))?,
@@ -198,12 +480,61 @@ impl Processor {
             Instruction::ShrUI32 => process.binary(|l: u32, r| l.wrapping_shr(r))?,
             Instruction::ShrUI64 => process.binary(|l: u64, r| l.wrapping_shr(r as u32))?,
 
+            Instruction::SqrtF32 => process.unary(f32::wasm_sqrt)?,
+            Instruction::SqrtF64 => process.unary(f64::wasm_sqrt)?,
+
+            Instruction::StoreF32 { offset, .. } => process.store(offset, f32::to_le_bytes)?,
+            Instruction::StoreF64 { offset, .. } => process.store(offset, f64::to_le_bytes)?,
+            Instruction::StoreI32 { offset, .. } => process.store(offset, i32::to_le_bytes)?,
+            Instruction::StoreI64 { offset, .. } => process.store(offset, i64::to_le_bytes)?,
+            Instruction::Store8I32 { offset, .. } => process.store(offset, |v: i32| (v as u8).to_le_bytes())?,
+            Instruction::Store8I64 { offset, .. } => process.store(offset, |v: i64| (v as u8).to_le_bytes())?,
+            Instruction::Store16I32 { offset, .. } => process.store(offset, |v: i32| (v as u16).to_le_bytes())?,
+            Instruction::Store16I64 { offset, .. } => process.store(offset, |v: i64| (v as u16).to_le_bytes())?,
+            Instruction::Store32I64 { offset, .. } => process.store(offset, |v: i64| (v as u32).to_le_bytes())?,
+
+            Instruction::SubF32 => process.binary(|l: f32, r| l - r)?,
+            Instruction::SubF64 => process.binary(|l: f64, r| l - r)?,
             Instruction::SubI32 => process.binary(i32::wrapping_sub)?,
             Instruction::SubI64 => process.binary(i64::wrapping_sub)?,
 
+            Instruction::TableCopy { from, to } => process.table_copy(to, from)?,
+            Instruction::TableFill(addr) => process.table_fill(addr)?,
+            Instruction::TableGet(addr) => process.table_get(addr)?,
+            Instruction::TableGrow(addr) => process.table_grow(addr)?,
+            Instruction::TableInit(segment, addr) => process.table_init(segment, addr)?,
+            Instruction::TableSet(addr) => process.table_set(addr)?,
+            Instruction::TableSize(addr) => process.table_size(addr)?,
+
+            Instruction::TruncF32 => process.unary(f32::wasm_trunc)?,
+            Instruction::TruncF64 => process.unary(f64::wasm_trunc)?,
+
+            Instruction::TruncF32SI32 => process.unary_trap(|v: f32| TruncChecked::<i32>::trunc_checked(v))?,
+            Instruction::TruncF32SI64 => process.unary_trap(|v: f32| TruncChecked::<i64>::trunc_checked(v))?,
+            Instruction::TruncF32UI32 => process.unary_trap(|v: f32| TruncChecked::<u32>::trunc_checked(v))?,
+            Instruction::TruncF32UI64 => process.unary_trap(|v: f32| TruncChecked::<u64>::trunc_checked(v))?,
+            Instruction::TruncF64SI32 => process.unary_trap(|v: f64| TruncChecked::<i32>::trunc_checked(v))?,
+            Instruction::TruncF64SI64 => process.unary_trap(|v: f64| TruncChecked::<i64>::trunc_checked(v))?,
+            Instruction::TruncF64UI32 => process.unary_trap(|v: f64| TruncChecked::<u32>::trunc_checked(v))?,
+            Instruction::TruncF64UI64 => process.unary_trap(|v: f64| TruncChecked::<u64>::trunc_checked(v))?,
+
+            // `as` casts saturate and map NaN to 0
+            Instruction::TruncSatF32SI32 => process.unary_map(|v: f32| v as i32)?,
+            Instruction::TruncSatF32SI64 => process.unary_map(|v: f32| v as i64)?,
+            Instruction::TruncSatF32UI32 => process.unary_map(|v: f32| v as u32)?,
+            Instruction::TruncSatF32UI64 => process.unary_map(|v: f32| v as u64)?,
+            Instruction::TruncSatF64SI32 => process.unary_map(|v: f64| v as i32)?,
+            Instruction::TruncSatF64SI64 => process.unary_map(|v: f64| v as i64)?,
+            Instruction::TruncSatF64UI32 => process.unary_map(|v: f64| v as u32)?,
+            Instruction::TruncSatF64UI64 => process.unary_map(|v: f64| v as u64)?,
+
+            Instruction::WrapI32I64 => process.unary_map(|v: i64| v as i32)?,
+
             Instruction::XorI32 => process.binary(i32::bitxor)?,
             Instruction::XorI64 => process.binary(i64::bitxor)?,
 
+            Instruction::Unreachable => return Err(Trap::Unreachable),
+
             _ => return Err(Trap::NotImplemented(TrapNotImplemented::Instruction(inst.clone()))),
         }
 
@@ -211,46 +542,88 @@ impl Processor {
     }
 
     pub fn invoke(&self, process: &mut Process, name: impl Into<String>, args: impl AsRef<[Value]>) -> Result<Box<[Value]>, Trap> {
-        let name = name.into();
-
-        let idx = match process.state.export(name.clone())
-            .or_else(|_| Err(Trap::NotFound(TrapNotFound::ExportedFunction(name))))
-            .unwrap()
-            .data()
-        {
-            ExportData::Function(idx) => *idx as usize,
-        };
+        let (addr, _) = process.exported_function(name)?;
+        self.call(process, addr, args)
+    }
 
-        for arg in args.as_ref() {
-            process.stack.push(arg.clone())?;
-        }
+    /// Calls the function at `addr` of the function space with `args`, checked against its parameters.
+    pub fn call(&self, process: &mut Process, addr: FunctionAddress, args: impl AsRef<[Value]>) -> Result<Box<[Value]>, Trap> {
+        let function = process.state.function_space().function(addr)?;
 
-        let function = process.state.function(idx as FunctionAddress).unwrap();
-        let func_inst = match &*function {
-            Function::Local(local) => local
-        };
+        // nothing is pushed unless the arguments match the parameters in number and type
+        let params = function.signature().params();
+        let args = args.as_ref();
+        if args.len() != params.len() || args.iter().zip(params.iter()).any(|(arg, param)| arg.value_type() != *param) {
+            let got = args.iter().map(Value::value_type).collect();
+            return Err(Trap::Type(TrapType::ArgumentMismatch(params.clone(), got)));
+        }
 
-        invoke_internal(process, self, func_inst)?;
+        run(process, self, addr, |process| args.iter().try_for_each(|arg| process.stack.push(arg.clone())))?;
 
         let mut result = vec![];
-        for _ in 0..func_inst.result_count() {
+        for _ in 0..function.signature().results().len() {
             let value = process.stack.pop()?;
             result.push(value);
         }
+        // the last result is on top of the stack
+        result.reverse();
         Ok(result.into())
     }
+
+    /// Calls the function at `addr` of the function space, passing and returning values as is.
+    ///
+    /// The caller is expected to have checked `P` and `R` against the function's signature.
+    pub fn call_typed<P: TypedValues, R: TypedValues>(&self, process: &mut Process, addr: FunctionAddress, params: P) -> Result<R, Trap> {
+        run(process, self, addr, |process| params.push(process))?;
+        R::pop(process)
+    }
+
+    /// Runs the start function `idx` of the process's module, counting imported functions first.
+    pub fn start(&self, process: &mut Process, idx: FunctionAddress) -> Result<(), Trap> {
+        let addr = process.state.function_address(idx)?;
+        run(process, self, addr, |_| Ok(()))
+    }
 }
 
 
-fn invoke_internal(process: &mut Process, processor: &Processor, func: &FunctionLocal) -> Result<(), Trap> {
-    let previous_frame = process.push_frame(func)?;
+// the number of parameters and results of a block typed by a function type
+fn block_type(process: &Process, type_addr: TypeAddress) -> Result<(Arity, Arity), Trap> {
+    let signature = process.state.function_type(type_addr)?;
+    Ok((signature.params().len(), signature.results().len()))
+}
+
+// runs the function at `addr` to completion with the arguments `push` puts on the stack, a trap
+// leaves the stack and the frames as they were before, so that the process can be invoked again
+fn run(process: &mut Process, processor: &Processor, addr: FunctionAddress, push: impl FnOnce(&mut Process) -> Result<(), Trap>) -> Result<(), Trap> {
+    let (height, depth) = (process.stack.len(), process.stack.depth());
+    let result = push(process)
+        .and_then(|_| call(process, addr))
+        // a host function has returned already, a local one is yet to run
+        .and_then(|_| if process.stack.depth() > depth { processor.until_completion(process) } else { Ok(()) });
+
+    if result.is_err() {
+        while process.stack.depth() > depth {
+            process.pop_frame()?;
+        }
+        process.stack.unwind(height, 0)?;
+    }
+    result
+}
 
-    if let Err(e) = processor.until_completion(process) {
-        // self.cleanup();
-        // FIXME
-        return Err(e);
+// a host function is run right away, a local function is entered and runs against the store it
+// belongs to, which is not the caller's for imported functions
+fn call(process: &mut Process, addr: FunctionAddress) -> Result<(), Trap> {
+    let instance = process.state.function_space().get(addr)?;
+    let local = match &*instance.function {
+        Function::Host(host) => return process.call_host(host),
+        Function::Local(local) => local,
     };
 
-    process.restore_frame(previous_frame);
+    let Some(store) = instance.store.upgrade() else {
+        return Err(Trap::NotFound(TrapNotFound::FunctionLocal(addr)));
+    };
+    let caller_store = (!Rc::ptr_eq(&store, &process.state)).then(|| process.state.clone());
+    process.push_frame(local, caller_store)?;
+    process.state = store;
     Ok(())
 }
@@ -1,12 +1,13 @@
 use alloc::boxed::Box;
 use alloc::fmt;
+use alloc::rc::Rc;
 use alloc::vec::Vec;
 use core::mem;
 
 use hal_core::{Trap, TrapOverflow, TrapType, TrapUnderflow};
 use hal_core::module::{Instruction, Value, ValueType};
 
-use crate::Result;
+use crate::{Result, Store};
 
 pub type InstructionPointer = isize;
 pub type StackPointer = usize;
@@ -16,9 +17,12 @@ pub type Arity = usize;
 pub struct CallFrame {
     pub(crate) ip: InstructionPointer,
     pub(crate) sp: StackPointer,
-    pub(crate) instructions: Box<[Instruction]>,
+    pub(crate) instructions: Rc<[Instruction]>,
     pub(crate) arity: Arity,
     pub(crate) locals: Box<[Value]>,
+    pub(crate) labels: Vec<Label>,
+    /// The store of the caller, when the function belongs to another one.
+    pub(crate) caller_store: Option<Rc<Store>>,
 }
 
 impl Default for CallFrame {
@@ -26,13 +30,38 @@ impl Default for CallFrame {
         Self {
             ip: 0,
             sp: 0,
-            instructions: Box::new([]),
+            instructions: Rc::new([]),
             arity: 0,
             locals: Box::new([]),
+            labels: Vec::new(),
+            caller_store: None,
         }
     }
 }
 
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum LabelKind {
+    Block,
+    Loop,
+}
+
+/// A branch target of a structured control instruction within the current call frame.
+///
+/// A branch to a `Block` label continues after its `end`, a branch to a `Loop` label continues
+/// at the start of the loop body and keeps the label on the label stack.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy)]
+pub struct Label {
+    pub(crate) kind: LabelKind,
+    /// Number of values carried by a branch to this label.
+    pub(crate) arity: Arity,
+    /// Height of the value stack when the label was entered.
+    pub(crate) sp: StackPointer,
+    /// Instruction preceding the one to continue with after a branch.
+    pub(crate) ip: InstructionPointer,
+}
+
 pub(crate) const MAX_VALUE_STACK: usize = 1024 * 32;
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
@@ -40,6 +69,8 @@ pub struct Stack {
     bytes: Vec<u8>,
     types: Vec<ValueType>,
     pub(crate) frame: CallFrame,
+    /// The frames of the callers of the current frame, the innermost last.
+    frames: Vec<CallFrame>,
 }
 
 /// A trait that defines stack operations for a specific type.
@@ -95,6 +126,7 @@ impl Default for Stack {
             bytes: Default::default(),
             types: Default::default(),
             frame: Default::default(),
+            frames: Default::default(),
         }
     }
 }
@@ -143,17 +175,50 @@ impl StackAccess for u64 {
     }
 }
 
+impl StackAccess for f32 {
+    fn push(stack: &mut Stack, value: f32) -> Result<()> {
+        stack.push_bytes(&value.to_le_bytes(), ValueType::F32)
+    }
+
+    fn peek(stack: &Stack) -> Result<f32> {
+        stack.expect_type(ValueType::F32)?;
+        Ok(f32::from_le_bytes(stack.peek_bytes(size_of::<f32>())?))
+    }
+}
+
+impl StackAccess for f64 {
+    fn push(stack: &mut Stack, value: f64) -> Result<()> {
+        stack.push_bytes(&value.to_le_bytes(), ValueType::F64)
+    }
+
+    fn peek(stack: &Stack) -> Result<f64> {
+        stack.expect_type(ValueType::F64)?;
+        Ok(f64::from_le_bytes(stack.peek_bytes(size_of::<f64>())?))
+    }
+}
+
 impl StackAccess for Value {
     fn push(stack: &mut Stack, v: Self) -> Result<()> {
         match v {
             Value::I32(i) => StackAccess::push(stack, i),
             Value::I64(i) => StackAccess::push(stack, i),
+            Value::F32(f) => StackAccess::push(stack, f),
+            Value::F64(f) => StackAccess::push(stack, f),
+            Value::FuncRef(r) => stack.push_bytes(&encode_reference(r), ValueType::FuncRef),
+            Value::ExternRef(r) => stack.push_bytes(&encode_reference(r), ValueType::ExternRef),
         }
     }
     fn pop(stack: &mut Stack) -> Result<Self> {
         match stack.peek_type()? {
             ValueType::I32 => StackAccess::pop(stack).map(|v| Value::I32(v)),
             ValueType::I64 => StackAccess::pop(stack).map(|v| Value::I64(v)),
+            ValueType::F32 => StackAccess::pop(stack).map(|v| Value::F32(v)),
+            ValueType::F64 => StackAccess::pop(stack).map(|v| Value::F64(v)),
+            ValueType::FuncRef | ValueType::ExternRef => {
+                let value = Self::peek(stack)?;
+                stack.pop_bytes(REFERENCE_SIZE)?;
+                Ok(value)
+            }
         }
     }
 
@@ -161,10 +226,25 @@ impl StackAccess for Value {
         match stack.peek_type()? {
             ValueType::I32 => StackAccess::peek(stack).map(|v| Value::I32(v)),
             ValueType::I64 => StackAccess::peek(stack).map(|v| Value::I64(v)),
+            ValueType::F32 => StackAccess::peek(stack).map(|v| Value::F32(v)),
+            ValueType::F64 => StackAccess::peek(stack).map(|v| Value::F64(v)),
+            ValueType::FuncRef => Ok(Value::FuncRef(decode_reference(stack.peek_bytes(REFERENCE_SIZE)?))),
+            ValueType::ExternRef => Ok(Value::ExternRef(decode_reference(stack.peek_bytes(REFERENCE_SIZE)?))),
         }
     }
 }
 
+/// References occupy 8 bytes on the stack: zero is the null reference, anything else the address plus one.
+const REFERENCE_SIZE: usize = size_of::<u64>();
+
+fn encode_reference(addr: Option<u32>) -> [u8; REFERENCE_SIZE] {
+    addr.map_or(0, |addr| addr as u64 + 1).to_le_bytes()
+}
+
+fn decode_reference(bytes: [u8; REFERENCE_SIZE]) -> Option<u32> {
+    u64::from_le_bytes(bytes).checked_sub(1).map(|addr| addr as u32)
+}
+
 
 impl Stack {
     /// Pushes a value onto the stack.
@@ -180,7 +260,7 @@ impl Stack {
     /// # Returns
     ///
     /// - `Result<()>`: Returns `Ok(())` on success, or an error if the push operation fails.
-    pub fn push<V: StackAccess>(&mut self, v: V) -> Result<()> {
+    pub(crate) fn push<V: StackAccess>(&mut self, v: V) -> Result<()> {
         StackAccess::push(self, v)
     }
 
@@ -193,7 +273,7 @@ impl Stack {
     /// # Returns
     ///
     /// - `Result<V>`: The top value on the stack, or an error if the peek operation fails.
-    pub fn peek<V: StackAccess>(&mut self) -> Result<V> {
+    pub(crate) fn peek<V: StackAccess>(&mut self) -> Result<V> {
         StackAccess::peek(self)
     }
 
@@ -206,7 +286,7 @@ impl Stack {
     /// # Returns
     ///
     /// - `Result<V>`: The value that was popped from the stack, or an error if the pop operation fails.
-    pub fn pop<V: StackAccess>(&mut self) -> Result<V> {
+    pub(crate) fn pop<V: StackAccess>(&mut self) -> Result<V> {
         StackAccess::pop(self)
     }
 
@@ -319,39 +399,84 @@ impl Stack {
         self.types.len()
     }
 
-    /// Replaces the current call frame with a new one and returns the old frame.
+    /// Discards the values between the given stack height and the top `arity` values.
     ///
-    /// This function takes ownership of a new `CallFrame` and replaces the
-    /// current call frame stored within the object. The previous call frame
-    /// is returned as a result, allowing it to be further manipulated or
-    /// stored if needed.
+    /// This is used when branching out of a block or returning from a function: the values
+    /// which are carried by the branch stay on top of the stack while everything pushed after
+    /// the label (or frame) was entered is removed.
     ///
     /// # Parameters
     ///
-    /// - `frame`: The new `CallFrame` to be set as the current call frame.
+    /// - `height`: The height of the stack when the label or frame was entered.
+    /// - `arity`: The number of values on top of the stack to keep.
     ///
     /// # Returns
     ///
-    /// - The previous `CallFrame` that was replaced.
-    pub(crate) fn replace_frame(&mut self, frame: CallFrame) -> CallFrame {
-        mem::replace(&mut self.frame, frame)
+    /// - `Result<()>`: Returns `Ok(())` on success, or an error if the stack holds less than `height + arity` values.
+    pub(crate) fn unwind(&mut self, height: StackPointer, arity: Arity) -> Result<()> {
+        let len = self.types.len();
+        if height + arity > len {
+            return Err(Trap::Underflow(TrapUnderflow::Stack));
+        }
+
+        let keep: usize = self.types[len - arity..].iter().map(size_of_type).sum();
+        let discard: usize = self.types[height..len - arity].iter().map(size_of_type).sum();
+        if discard == 0 {
+            return Ok(());
+        }
+
+        let end = self.bytes.len();
+        self.bytes.copy_within(end - keep..end, end - keep - discard);
+        self.bytes.truncate(end - discard);
+        self.types.drain(height..len - arity);
+        Ok(())
     }
 
-    /// Restores a previous `CallFrame` as the current frame without returning the old one.
-    ///
-    /// This function replaces the current `CallFrame` with the provided one, effectively
-    /// restoring a previous state. Unlike `replace_frame`, this function does not return
-    /// the old frame, discarding it instead. This is useful in scenarios where the current
-    /// frame needs to be quickly swapped out without the need to retain the old frame.
+    /// Makes `frame` the current call frame, keeping the current one to return to.
     ///
     /// # Parameters
     ///
-    /// - `frame`: The `CallFrame` to be restored as the current frame.
-    pub(crate) fn restore(&mut self, frame: CallFrame) {
-        _ = mem::replace(&mut self.frame, frame);
+    /// - `frame`: The frame of the called function.
+    /// - `max_depth`: The number of frames the stack may hold.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<()>`: Returns `Ok(())` on success, or an error if `max_depth` frames are active already.
+    pub(crate) fn push_frame(&mut self, frame: CallFrame, max_depth: usize) -> Result<()> {
+        if self.frames.len() >= max_depth {
+            return Err(Trap::Overflow(TrapOverflow::Stack));
+        }
+        let caller = mem::replace(&mut self.frame, frame);
+        self.frames.push(caller);
+        Ok(())
+    }
+
+    /// Returns to the frame of the caller and returns the frame which was left.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<CallFrame>`: The frame which was left, or an error if no function is active.
+    pub(crate) fn pop_frame(&mut self) -> Result<CallFrame> {
+        let caller = self.frames.pop().ok_or(Trap::Underflow(TrapUnderflow::Stack))?;
+        Ok(mem::replace(&mut self.frame, caller))
+    }
+
+    /// Returns the number of active call frames.
+    pub(crate) fn depth(&self) -> usize {
+        self.frames.len()
     }
 }
 
+/// Returns the number of bytes a value of the given type occupies on the stack.
+fn size_of_type(vt: &ValueType) -> usize {
+    match vt {
+        ValueType::I32 => size_of::<i32>(),
+        ValueType::I64 => size_of::<i64>(),
+        ValueType::F32 => size_of::<f32>(),
+        ValueType::F64 => size_of::<f64>(),
+        ValueType::FuncRef | ValueType::ExternRef => REFERENCE_SIZE,
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -485,6 +610,65 @@ mod tests {
         assert_eq!(ti.pop::<Value>().unwrap(), Value::I64(i64::MAX));
     }
 
+    #[test]
+    fn f32_primitive() {
+        let mut ti = Stack::default();
+        ti.push(0f32).unwrap();
+        ti.push(-1.5f32).unwrap();
+        ti.push(f32::MAX).unwrap();
+        ti.push(f32::from_bits(0x7FA00001)).unwrap();
+
+        assert_eq!(ti.pop::<f32>().unwrap().to_bits(), 0x7FA00001);
+        assert_eq!(ti.pop::<f32>().unwrap(), f32::MAX);
+        assert_eq!(ti.peek::<f32>().unwrap(), -1.5);
+        assert_eq!(ti.pop::<f32>().unwrap(), -1.5);
+        assert_eq!(ti.pop::<f32>().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn f64_primitive() {
+        let mut ti = Stack::default();
+        ti.push(0f64).unwrap();
+        ti.push(-1.5f64).unwrap();
+        ti.push(f64::MIN).unwrap();
+        ti.push(f64::from_bits(0xFFF4000000000001)).unwrap();
+
+        assert_eq!(ti.pop::<f64>().unwrap().to_bits(), 0xFFF4000000000001);
+        assert_eq!(ti.pop::<f64>().unwrap(), f64::MIN);
+        assert_eq!(ti.peek::<f64>().unwrap(), -1.5);
+        assert_eq!(ti.pop::<f64>().unwrap(), -1.5);
+        assert_eq!(ti.pop::<f64>().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn float_mixed() {
+        let mut ti = Stack::default();
+        ti.push(Value::F32(1.5)).unwrap();
+        ti.push(2i32).unwrap();
+        ti.push(Value::F64(-2.5)).unwrap();
+
+        assert_eq!(ti.pop::<Value>().unwrap(), Value::F64(-2.5));
+        assert_eq!(ti.pop::<i32>().unwrap(), 2);
+        assert_eq!(ti.peek::<Value>().unwrap(), Value::F32(1.5));
+        assert_eq!(ti.pop::<f32>().unwrap(), 1.5);
+    }
+
+    #[test]
+    fn references() {
+        let mut ti = Stack::default();
+        ti.push(Value::FuncRef(Some(0))).unwrap();
+        ti.push(1i32).unwrap();
+        ti.push(Value::ExternRef(None)).unwrap();
+        ti.push(Value::ExternRef(Some(u32::MAX))).unwrap();
+
+        assert_eq!(ti.pop::<Value>().unwrap(), Value::ExternRef(Some(u32::MAX)));
+        assert_eq!(ti.peek::<Value>().unwrap(), Value::ExternRef(None));
+        ti.unwind(1, 1).unwrap();
+        assert_eq!(ti.pop::<Value>().unwrap(), Value::ExternRef(None));
+        assert_eq!(ti.pop::<Value>().unwrap(), Value::FuncRef(Some(0)));
+        assert_eq!(ti.len(), 0);
+    }
+
     #[test]
     fn type_mismatch_on_pop() {
         let mut ti = Stack::default();
@@ -558,6 +742,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unwind() {
+        let mut ti = Stack::default();
+        ti.push(1i32).unwrap();
+        ti.push(2i64).unwrap();
+        ti.push(3i32).unwrap();
+        ti.push(4i64).unwrap();
+        ti.push(5i32).unwrap();
+
+        ti.unwind(1, 2).unwrap();
+        assert_eq!(ti.len(), 3);
+        assert_eq!(ti.pop::<i32>().unwrap(), 5);
+        assert_eq!(ti.pop::<i64>().unwrap(), 4);
+        assert_eq!(ti.pop::<i32>().unwrap(), 1);
+    }
+
+    #[test]
+    fn unwind_nothing_to_discard() {
+        let mut ti = Stack::default();
+        ti.push(1i32).unwrap();
+        ti.push(2i64).unwrap();
+
+        ti.unwind(0, 2).unwrap();
+        assert_eq!(ti.pop::<i64>().unwrap(), 2);
+        assert_eq!(ti.pop::<i32>().unwrap(), 1);
+    }
+
+    #[test]
+    fn unwind_underflow() {
+        let mut ti = Stack::default();
+        ti.push(1i32).unwrap();
+
+        assert_eq!(ti.unwind(1, 1), Err(Trap::Underflow(TrapUnderflow::Stack)));
+    }
+
+    #[test]
+    fn frames() {
+        let mut ti = Stack::default();
+        assert_eq!(ti.depth(), 0);
+
+        ti.push_frame(CallFrame { ip: 1, ..CallFrame::default() }, 2).unwrap();
+        ti.push_frame(CallFrame { ip: 2, ..CallFrame::default() }, 2).unwrap();
+        assert_eq!(ti.depth(), 2);
+        assert_eq!(
+            ti.push_frame(CallFrame::default(), 2),
+            Err(Trap::Overflow(TrapOverflow::Stack))
+        );
+
+        assert_eq!(ti.pop_frame().unwrap().ip, 2);
+        assert_eq!(ti.frame.ip, 1);
+        assert_eq!(ti.pop_frame().unwrap().ip, 1);
+        assert_eq!(ti.depth(), 0);
+        assert!(ti.pop_frame().is_err());
+    }
+
     #[test]
     fn len() {
         let mut ti = Stack::default();
@@ -1,53 +1,256 @@
 use alloc::boxed::Box;
-use alloc::rc::Rc;
+use alloc::rc::{Rc, Weak};
 use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::{Ref, RefCell};
 
-use hal_core::{module, Trap, TrapNotFound};
-use hal_core::module::{Export, Memory};
+use hal_core::{module, Trap, TrapNotFound, TrapType};
+use hal_core::module::{ConstExpr, Data, DataAddress, DataMode, Element, ElementAddress, ElementMode, Export, ExportData, FunctionSignature, Global, GlobalAddress, GlobalDefinition, Memory, Table, TableAddress, TypeAddress, Value, ValueType};
 use hal_core::module::FunctionAddress;
 use hal_core::module::MemoryAddress;
 use hal_core::module::Module;
 use module::Function;
 
+use crate::Functions;
+
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub enum StoreError {
+    /// A memory of the initial number of pages could not be allocated.
+    MemoryAllocationFailed(u32),
     NotFoundFunction(String),
     NotFoundMemory(MemoryAddress),
     NotFoundModule(String),
     NotFoundTypes,
+    /// Initializing the store trapped, e.g. a segment is out of bounds.
+    Trapped(Trap),
 }
 
 
 impl core::fmt::Display for StoreError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
+            StoreError::MemoryAllocationFailed(pages) => write!(f, "failed to allocate memory of {} pages", pages),
             StoreError::NotFoundFunction(name) => write!(f, "Function not found: {}", name),
             StoreError::NotFoundModule(name) => write!(f, "Module not found: {}", name),
             StoreError::NotFoundMemory(addr) => write!(f, "Memory not found: {}", addr),
             StoreError::NotFoundTypes => write!(f, "Types not found"),
+            StoreError::Trapped(trap) => write!(f, "{}", trap),
         }
     }
 }
 
+/// A value an import is satisfied with, functions are addresses into the shared [`Functions`].
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone)]
+pub enum ExternValue {
+    /// A function, by its address in the shared function space.
+    Function(FunctionAddress),
+    /// A table.
+    Table(Rc<Table>),
+    /// A memory.
+    Memory(Rc<Memory>),
+    /// A global.
+    Global(Rc<Global>),
+}
+
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 pub struct Store {
-    functions: Box<[Rc<Function>]>,
+    types: Box<[FunctionSignature]>,
+    function_space: Rc<Functions>,
+    // maps the function indices of the module to addresses in the function space
+    functions: Box<[FunctionAddress]>,
     exports: Box<[Rc<Export>]>,
+    tables: Box<[Rc<Table>]>,
     memories: Box<[Rc<Memory>]>,
+    globals: Vec<Rc<Global>>,
+    elements: Box<[RefCell<Box<[Value]>>]>,
+    data: Box<[RefCell<Box<[u8]>>]>,
 }
 
 // FIXME own representation -- load from compiled module
 impl Store {
-    pub fn new(module: &Module) -> Result<Self, StoreError> {
-        Ok(Self {
-            functions: module.functions.clone(),
+    /// Allocates the instance of `module`, `imports` are the resolved externs in the order of the module's imports.
+    ///
+    /// The functions of the module are added to `function_space`, bound to the returned store. Active
+    /// segments are only written by [`Store::initialize`].
+    pub fn new(module: &Module, imports: &[ExternValue], function_space: Rc<Functions>) -> Result<Rc<Self>, StoreError> {
+        // memories are allocated first, a module which can not get its memories leaves no functions behind
+        let memories = module.memories.iter()
+            .map(|memory| Memory::new(memory.min, memory.max).map(Rc::new).ok_or(StoreError::MemoryAllocationFailed(memory.min)))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        let mut allocated = Ok(());
+        let store = Rc::new_cyclic(|this| {
+            let mut store = Self::allocate(module, imports, memories, function_space, this);
+            allocated = store.init_globals(&module.globals).and_then(|_| store.init_elements(&module.elements));
+            store
+        });
+        allocated.map_err(StoreError::Trapped)?;
+        Ok(store)
+    }
+
+    /// Copies the active element and data segments of `module` into their tables and memories.
+    ///
+    /// Segments written before a trap stay written, and as they may reference functions of this
+    /// store, it must outlive a failed initialization.
+    pub fn initialize(&self, module: &Module) -> Result<(), StoreError> {
+        self.init_active_elements(&module.elements)
+            .and_then(|_| self.init_data(&module.data))
+            .map_err(StoreError::Trapped)
+    }
+
+    // imports come first in their index spaces, imported tables and memories are shared with the exporter
+    fn allocate(module: &Module, imports: &[ExternValue], defined_memories: Vec<Rc<Memory>>, function_space: Rc<Functions>, this: &Weak<Self>) -> Self {
+        let mut functions = Vec::with_capacity(imports.len() + module.functions.len());
+        let mut tables = Vec::with_capacity(imports.len() + module.tables.len());
+        let mut memories = Vec::with_capacity(imports.len() + module.memories.len());
+        let mut globals = Vec::with_capacity(imports.len() + module.globals.len());
+        for import in imports {
+            match import {
+                ExternValue::Function(addr) => functions.push(*addr),
+                ExternValue::Table(table) => tables.push(table.clone()),
+                ExternValue::Memory(memory) => memories.push(memory.clone()),
+                ExternValue::Global(global) => globals.push(global.clone()),
+            }
+        }
+        for function in module.functions.iter() {
+            functions.push(function_space.allocate(function.clone(), this.clone()));
+        }
+        tables.extend(module.tables.iter().map(|table| Rc::new(Table::new(table.element_type.clone(), table.min, table.max))));
+        memories.extend(defined_memories);
+
+        Self {
+            types: module.types.clone(),
+            function_space,
+            functions: functions.into(),
             exports: module.exports.clone(),
-            memories: module.memories.clone(),
+            tables: tables.into(),
+            memories: memories.into(),
+            globals,
+            elements: Box::default(),
+            // active segments behave like dropped ones once they have been copied into memory
+            data: module.data.iter()
+                .map(|data| match data.mode {
+                    DataMode::Passive => RefCell::new(data.bytes.clone()),
+                    DataMode::Active { .. } => RefCell::default(),
+                })
+                .collect(),
+        }
+    }
+
+    // globals are created in order, so an initializer only sees the globals defined before it
+    fn init_globals(&mut self, globals: &[GlobalDefinition]) -> Result<(), Trap> {
+        for global in globals {
+            let value = self.evaluate(&global.init)?;
+            self.globals.push(Rc::new(Global::new(global.global_type.clone(), value)?));
+        }
+        Ok(())
+    }
+
+    // like data segments, active and declarative segments behave like dropped ones after instantiation
+    fn init_elements(&mut self, elements: &[Element]) -> Result<(), Trap> {
+        let mut segments = Vec::with_capacity(elements.len());
+        for element in elements {
+            let references = match element.mode {
+                ElementMode::Passive | ElementMode::Active { .. } => element.init.iter()
+                    .map(|expr| self.evaluate(expr))
+                    .collect::<Result<Box<[_]>, Trap>>()?,
+                ElementMode::Declarative => Box::default(),
+            };
+            segments.push(RefCell::new(references));
+        }
+        self.elements = segments.into();
+        Ok(())
+    }
+
+    fn init_active_elements(&self, elements: &[Element]) -> Result<(), Trap> {
+        for (segment, element) in self.elements.iter().zip(elements) {
+            if let ElementMode::Active { table, offset } = &element.mode {
+                let offset = match self.evaluate(offset)? {
+                    Value::I32(offset) => offset as u32,
+                    value => return Err(Trap::Type(TrapType::Mismatch(ValueType::I32, value.value_type()))),
+                };
+                let references = segment.take();
+                self.table(*table)?.write(offset, &references)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn init_data(&self, data: &[Data]) -> Result<(), Trap> {
+        for data in data {
+            if let DataMode::Active { memory, offset } = &data.mode {
+                let offset = match self.evaluate(offset)? {
+                    Value::I32(offset) => offset as u32,
+                    value => return Err(Trap::Type(TrapType::Mismatch(ValueType::I32, value.value_type()))),
+                };
+                self.memory(*memory)?.write(offset, &data.bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluates a constant expression against the globals and functions of this store.
+    pub fn evaluate(&self, expr: &ConstExpr) -> Result<Value, Trap> {
+        match expr {
+            ConstExpr::Const(value) => Ok(value.clone()),
+            ConstExpr::GlobalGet(addr) => Ok(self.global(*addr)?.get()),
+            ConstExpr::RefFunc(idx) => Ok(Value::FuncRef(Some(self.function_address(*idx)?))),
+            ConstExpr::RefNull(value_type) => Value::null(value_type)
+                .ok_or(Trap::Type(TrapType::Mismatch(ValueType::FuncRef, value_type.clone()))),
+        }
+    }
+
+    /// Returns the function signature at `addr` of the type section.
+    pub fn function_type(&self, addr: TypeAddress) -> Result<&FunctionSignature, Trap> {
+        self.types.get(addr as usize).ok_or(Trap::NotFound(TrapNotFound::Type(addr)))
+    }
+
+    /// Returns the address in the function space of the function with module index `idx`.
+    pub fn function_address(&self, idx: FunctionAddress) -> Result<FunctionAddress, Trap> {
+        self.functions.get(idx as usize).copied().ok_or(Trap::NotFound(TrapNotFound::FunctionLocal(idx)))
+    }
+
+    /// Returns the function with module index `idx`.
+    pub fn function(&self, idx: FunctionAddress) -> Result<Rc<Function>, Trap> {
+        self.function_space.function(self.function_address(idx)?)
+    }
+
+    /// Returns the addresses of the functions defined by this store, leaving out imported ones.
+    pub fn defined_functions(&self) -> impl Iterator<Item = FunctionAddress> + '_ {
+        self.functions.iter().copied().filter(|addr| {
+            self.function_space.get(*addr).is_ok_and(|instance| core::ptr::eq(instance.store.as_ptr(), self))
         })
     }
 
-    pub fn function(&self, addr: FunctionAddress) -> Result<Rc<Function>, Trap> {
-        self.functions.get(addr as usize).ok_or(Trap::NotFound(TrapNotFound::FunctionLocal(addr))).map(|rc| rc.clone())
+    /// Returns the functions referenced from the tables and globals of this store, imported ones included.
+    pub fn references(&self) -> Vec<FunctionAddress> {
+        let tables = self.tables.iter().flat_map(|table| table.read(0, table.size()).unwrap_or_default());
+        tables.chain(self.globals.iter().map(|global| global.get()))
+            .filter_map(|value| match value {
+                Value::FuncRef(Some(addr)) => Some(addr),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the function space shared by all stores of an environment.
+    pub fn function_space(&self) -> &Rc<Functions> {
+        &self.function_space
+    }
+
+    /// Returns the exports of the module this store was allocated for.
+    pub fn exports(&self) -> &[Rc<Export>] {
+        &self.exports
+    }
+
+    /// Resolves an export of this store to the value an importing module is linked with.
+    pub fn extern_value(&self, data: &ExportData) -> Result<ExternValue, Trap> {
+        match data {
+            ExportData::Function(idx) => self.function_address(*idx).map(ExternValue::Function),
+            ExportData::Global(addr) => self.global(*addr).map(ExternValue::Global),
+            ExportData::Memory(addr) => self.memory(*addr).map(ExternValue::Memory),
+            ExportData::Table(addr) => self.table(*addr).map(ExternValue::Table),
+        }
     }
 
     pub fn export(&self, name: impl Into<String>) -> Result<Rc<Export>, Trap> {
@@ -57,7 +260,41 @@ impl Store {
             .ok_or(Trap::NotFound(TrapNotFound::ExportedFunction(name)))
     }
 
+    /// Returns the table at `addr`, counting imported tables first.
+    pub fn table(&self, addr: TableAddress) -> Result<Rc<Table>, Trap> {
+        self.tables.get(addr as usize).ok_or(Trap::NotFound(TrapNotFound::Table(addr))).map(|rc| rc.clone())
+    }
+
     pub fn memory(&self, addr: MemoryAddress) -> Result<Rc<Memory>, Trap> {
         self.memories.get(addr as usize).ok_or(Trap::NotFound(TrapNotFound::Memory(addr))).map(|rc| rc.clone())
     }
-}
\ No newline at end of file
+
+    /// Returns the global at `addr`, counting imported globals first.
+    pub fn global(&self, addr: GlobalAddress) -> Result<Rc<Global>, Trap> {
+        self.globals.get(addr as usize).ok_or(Trap::NotFound(TrapNotFound::Global(addr))).map(|rc| rc.clone())
+    }
+
+    /// Returns the references of the element segment at `addr`, empty once it has been dropped.
+    pub fn element(&self, addr: ElementAddress) -> Result<Ref<'_, [Value]>, Trap> {
+        self.elements.get(addr as usize).map(|element| Ref::map(element.borrow(), |element| element.as_ref())).ok_or(Trap::NotFound(TrapNotFound::Element(addr)))
+    }
+
+    /// Empties the element segment at `addr`, as done by `elem.drop`.
+    pub fn drop_element(&self, addr: ElementAddress) -> Result<(), Trap> {
+        let element = self.elements.get(addr as usize).ok_or(Trap::NotFound(TrapNotFound::Element(addr)))?;
+        *element.borrow_mut() = Box::default();
+        Ok(())
+    }
+
+    /// Returns the bytes of the data segment at `addr`, empty once it has been dropped.
+    pub fn data(&self, addr: DataAddress) -> Result<Ref<'_, [u8]>, Trap> {
+        self.data.get(addr as usize).map(|data| Ref::map(data.borrow(), |data| data.as_ref())).ok_or(Trap::NotFound(TrapNotFound::Data(addr)))
+    }
+
+    /// Empties the data segment at `addr`, as done by `data.drop`.
+    pub fn drop_data(&self, addr: DataAddress) -> Result<(), Trap> {
+        let data = self.data.get(addr as usize).ok_or(Trap::NotFound(TrapNotFound::Data(addr)))?;
+        *data.borrow_mut() = Box::default();
+        Ok(())
+    }
+}
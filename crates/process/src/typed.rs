@@ -0,0 +1,118 @@
+use alloc::boxed::Box;
+
+use hal_core::module::{Value, ValueType};
+use hal_core::{Trap, TrapType};
+
+use crate::Process;
+use crate::Result;
+
+/// A value which is passed to or returned from a function as is, instead of as a [`Value`].
+pub trait TypedValue: Sized {
+    /// The type of the value on the stack.
+    const VALUE_TYPE: ValueType;
+
+    /// Wraps the value to be pushed onto the stack.
+    fn into_value(self) -> Value;
+
+    /// Unwraps a value popped off the stack, `None` if it has another type.
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+macro_rules! typed_value {
+    ($ty: ty, $variant: ident) => {
+        impl TypedValue for $ty {
+            const VALUE_TYPE: ValueType = ValueType::$variant;
+
+            fn into_value(self) -> Value {
+                Value::$variant(self)
+            }
+
+            fn from_value(value: Value) -> Option<Self> {
+                match value {
+                    Value::$variant(value) => Some(value),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+typed_value!(i32, I32);
+typed_value!(i64, I64);
+typed_value!(f32, F32);
+typed_value!(f64, F64);
+
+/// The parameters or results of a function: `()`, a single [`TypedValue`] or a tuple of them.
+pub trait TypedValues: Sized {
+    /// Returns the types of the values, in declaration order.
+    fn value_types() -> Box<[ValueType]>;
+
+    /// Pushes the values onto the stack of `process`, the first one first.
+    fn push(self, process: &mut Process) -> Result<()>;
+
+    /// Pops the values off the stack of `process`, where the last one is on top.
+    fn pop(process: &mut Process) -> Result<Self>;
+}
+
+impl TypedValues for () {
+    fn value_types() -> Box<[ValueType]> {
+        Box::new([])
+    }
+
+    fn push(self, _: &mut Process) -> Result<()> {
+        Ok(())
+    }
+
+    fn pop(_: &mut Process) -> Result<Self> {
+        Ok(())
+    }
+}
+
+impl<T: TypedValue> TypedValues for T {
+    fn value_types() -> Box<[ValueType]> {
+        Box::new([T::VALUE_TYPE])
+    }
+
+    fn push(self, process: &mut Process) -> Result<()> {
+        process.stack.push(self.into_value())
+    }
+
+    fn pop(process: &mut Process) -> Result<Self> {
+        pop_typed(process)
+    }
+}
+
+// the values are listed twice, the second time in reverse order to pop them off the stack
+macro_rules! typed_values {
+    ($($ty: ident $value: ident),+; $($reversed: ident),+) => {
+        impl<$($ty: TypedValue),+> TypedValues for ($($ty,)+) {
+            fn value_types() -> Box<[ValueType]> {
+                Box::new([$($ty::VALUE_TYPE),+])
+            }
+
+            fn push(self, process: &mut Process) -> Result<()> {
+                let ($($value,)+) = self;
+                $(process.stack.push($value.into_value())?;)+
+                Ok(())
+            }
+
+            fn pop(process: &mut Process) -> Result<Self> {
+                $(let $reversed = pop_typed(process)?;)+
+                Ok(($($value,)+))
+            }
+        }
+    };
+}
+
+typed_values!(A a; a);
+typed_values!(A a, B b; b, a);
+typed_values!(A a, B b, C c; c, b, a);
+typed_values!(A a, B b, C c, D d; d, c, b, a);
+typed_values!(A a, B b, C c, D d, E e; e, d, c, b, a);
+typed_values!(A a, B b, C c, D d, E e, F f; f, e, d, c, b, a);
+
+fn pop_typed<T: TypedValue>(process: &mut Process) -> Result<T> {
+    let value: Value = process.stack.pop()?;
+    let value_type = value.value_type();
+    T::from_value(value).ok_or(Trap::Type(TrapType::Mismatch(T::VALUE_TYPE, value_type)))
+}
@@ -0,0 +1,31 @@
+use std::fmt::{Display, Formatter};
+
+/// A wast file which could not be run at all, as opposed to a directive which failed.
+#[derive(Debug)]
+pub enum SpecError {
+    /// The file could not be read.
+    Read(std::io::Error),
+    /// The file is not valid wast text.
+    Parse(wast::Error),
+}
+
+impl Display for SpecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecError::Read(e) => write!(f, "failed to read wast: {}", e),
+            SpecError::Parse(e) => write!(f, "failed to parse wast: {}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for SpecError {
+    fn from(value: std::io::Error) -> Self {
+        SpecError::Read(value)
+    }
+}
+
+impl From<wast::Error> for SpecError {
+    fn from(value: wast::Error) -> Self {
+        SpecError::Parse(value)
+    }
+}
@@ -0,0 +1,15 @@
+#![warn(missing_docs, missing_debug_implementations, rust_2018_idioms, unreachable_pub)]
+#![forbid(unsafe_code)]
+
+//! Runs `.wast` spec test files against [`hal_env::Environment`], recording the outcome of each
+//! directive instead of stopping at the first failure.
+
+pub use error::SpecError;
+pub use report::{DirectiveReport, Outcome, Report, Summary};
+pub use runner::{run_file, run_file_with_features, run_wast, run_wast_with_features};
+
+mod error;
+mod report;
+mod runner;
+mod spectest;
+mod value;
@@ -0,0 +1,170 @@
+//! Runs the `.wast` files found in the given files and directories and prints a conformance
+//! report, optionally writing it as JSON as well.
+//!
+//! ```sh
+//! hal-spec [--enable <proposal>]... [--json <file>] <path>...
+//! ```
+//!
+//! Modules are loaded with the default proposals, `--enable` adds one such as `tail-call`.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::{fs, io, panic};
+
+use hal_spec::{Outcome, Report, Summary};
+use hal_wasm::WasmFeatures;
+
+// the columns of the matrix, any other directive is counted under `other`
+const KINDS: [&str; 8] = ["module", "assert_return", "assert_trap", "assert_invalid", "assert_malformed", "assert_unlinkable", "assert_exhaustion", "invoke"];
+
+fn main() -> ExitCode {
+    let mut json = None;
+    let mut features = WasmFeatures::default();
+    let mut paths = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--enable" => match args.next().as_deref().and_then(|proposal| enable(&mut features, proposal)) {
+                Some(()) => {}
+                None => return usage(),
+            },
+            "--json" => match args.next() {
+                Some(file) => json = Some(PathBuf::from(file)),
+                None => return usage(),
+            },
+            "-h" | "--help" => return usage(),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        return usage();
+    }
+
+    let mut files = Vec::new();
+    for path in paths.iter() {
+        if let Err(e) = collect(path, path, &mut files) {
+            eprintln!("{}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+    files.sort();
+
+    // panics are recorded as failed directives, printing them as well would bury the report
+    panic::set_hook(Box::new(|_| {}));
+    let summary = run(files, &features);
+
+    print_matrix(&summary);
+    print_reasons(&summary);
+    println!("total: {} passed, {} failed, {} skipped", summary.passed(), summary.failed(), summary.skipped());
+
+    if let Some(json) = json {
+        if let Err(e) = fs::write(&json, summary.to_json()) {
+            eprintln!("{}: {}", json.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn usage() -> ExitCode {
+    eprintln!("usage: hal-spec [--enable <proposal>]... [--json <file>] <path>...");
+    ExitCode::FAILURE
+}
+
+// proposals are named like their repositories, e.g. `tail-call` for WebAssembly/tail-call
+fn enable(features: &mut WasmFeatures, proposal: &str) -> Option<()> {
+    let feature = match proposal {
+        "bulk-memory" => &mut features.bulk_memory,
+        "extended-const" => &mut features.extended_const,
+        "exceptions" | "exception-handling" => &mut features.exceptions,
+        "memory64" => &mut features.memory64,
+        "multi-memory" => &mut features.multi_memory,
+        "multi-value" => &mut features.multi_value,
+        "reference-types" => &mut features.reference_types,
+        "simd" => &mut features.simd,
+        "tail-call" => &mut features.tail_call,
+        "threads" => &mut features.threads,
+        _ => return None,
+    };
+    *feature = true;
+    Some(())
+}
+
+// files are named relative to the directory they were found in, so that reports of different
+// checkouts can be compared
+fn collect(root: &Path, path: &Path, files: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            collect(root, &entry?.path(), files)?;
+        }
+    } else if path == root || path.extension().is_some_and(|extension| extension == "wast") {
+        let name = match path.strip_prefix(root) {
+            Ok(name) if !name.as_os_str().is_empty() => name,
+            _ => path,
+        };
+        files.push((name.display().to_string(), path.to_path_buf()));
+    }
+    Ok(())
+}
+
+fn run(files: Vec<(String, PathBuf)>, features: &WasmFeatures) -> Summary {
+    let mut summary = Summary::default();
+    for (name, path) in files {
+        let report = fs::read_to_string(&path)
+            .map_err(hal_spec::SpecError::from)
+            .and_then(|wast| hal_spec::run_wast_with_features(name.clone(), &wast, features));
+        match report {
+            Ok(report) => summary.reports.push(report),
+            Err(e) => eprintln!("{}: {}", name, e),
+        }
+    }
+    summary
+}
+
+fn print_matrix(summary: &Summary) {
+    let width = summary.reports.iter().map(|report| report.file.len()).max().unwrap_or(0).max("file".len());
+
+    print!("{:<width$}", "file");
+    for kind in KINDS.iter().chain(["other"].iter()) {
+        print!("  {:>w$}", kind, w = kind.len().max(9));
+    }
+    println!("  {:>9}", "result");
+
+    for report in summary.reports.iter() {
+        print!("{:<width$}", report.file);
+        for (i, kind) in KINDS.iter().chain(["other"].iter()).enumerate() {
+            let matches = |directive_kind: &str| if i < KINDS.len() { directive_kind == *kind } else { !KINDS.contains(&directive_kind) };
+            print!("  {:>w$}", cell(report, matches), w = kind.len().max(9));
+        }
+        let result = if report.failed() > 0 { "FAIL" } else { "ok" };
+        println!("  {:>9}", result);
+    }
+    println!();
+}
+
+// passed out of all directives of a kind, skipped ones are not counted
+fn cell(report: &Report, matches: impl Fn(&str) -> bool) -> String {
+    let (mut passed, mut total) = (0, 0);
+    for directive in report.directives.iter().filter(|directive| matches(directive.kind)) {
+        match directive.outcome {
+            Outcome::Passed => { passed += 1; total += 1; }
+            Outcome::Failed(_) => total += 1,
+            Outcome::Skipped(_) => {}
+        }
+    }
+    if total == 0 { "-".to_string() } else { format!("{}/{}", passed, total) }
+}
+
+fn print_reasons(summary: &Summary) {
+    for report in summary.reports.iter().filter(|report| report.failed() + report.skipped() > 0) {
+        println!("{}: {} passed, {} failed, {} skipped", report.file, report.passed(), report.failed(), report.skipped());
+        for directive in report.directives.iter() {
+            match &directive.outcome {
+                Outcome::Passed => {}
+                Outcome::Failed(reason) => println!("  line {} {} FAIL: {}", directive.line, directive.kind, reason),
+                Outcome::Skipped(reason) => println!("  line {} {} SKIP: {}", directive.line, directive.kind, reason),
+            }
+        }
+    }
+    println!();
+}
@@ -0,0 +1,189 @@
+use std::fmt::{Display, Formatter, Write};
+
+/// The outcome of a single directive of a wast file.
+#[derive(Debug)]
+pub enum Outcome {
+    /// The directive behaved as the spec expects.
+    Passed,
+    /// The directive did not behave as expected, with the reason.
+    Failed(String),
+    /// The directive relies on something the harness or the runtime does not support.
+    Skipped(String),
+}
+
+impl Outcome {
+    fn name(&self) -> &'static str {
+        match self {
+            Outcome::Passed => "passed",
+            Outcome::Failed(_) => "failed",
+            Outcome::Skipped(_) => "skipped",
+        }
+    }
+
+    fn reason(&self) -> Option<&str> {
+        match self {
+            Outcome::Passed => None,
+            Outcome::Failed(reason) | Outcome::Skipped(reason) => Some(reason),
+        }
+    }
+}
+
+/// A directive of a wast file, identified by its kind, e.g. `assert_return`, and the line it
+/// starts on.
+#[derive(Debug)]
+pub struct DirectiveReport {
+    /// The line the directive starts on, counted from 1.
+    pub line: usize,
+    /// The keyword of the directive, `module` for a module definition.
+    pub kind: &'static str,
+    /// What happened when the directive was run.
+    pub outcome: Outcome,
+}
+
+/// The outcome of every directive of a wast file, in file order.
+#[derive(Debug)]
+pub struct Report {
+    /// The name of the file, as passed to [`crate::run_wast`].
+    pub file: String,
+    /// The directives of the file.
+    pub directives: Vec<DirectiveReport>,
+}
+
+impl Report {
+    /// Returns the number of directives which passed.
+    pub fn passed(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Passed))
+    }
+
+    /// Returns the number of directives which failed.
+    pub fn failed(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Failed(_)))
+    }
+
+    /// Returns the number of directives which were skipped.
+    pub fn skipped(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Skipped(_)))
+    }
+
+    fn count(&self, filter: impl Fn(&Outcome) -> bool) -> usize {
+        self.directives.iter().filter(|directive| filter(&directive.outcome)).count()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}: {} passed, {} failed, {} skipped", self.file, self.passed(), self.failed(), self.skipped())?;
+        for directive in self.directives.iter() {
+            if let Outcome::Failed(reason) = &directive.outcome {
+                writeln!(f, "  line {} {}: {}", directive.line, directive.kind, reason)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The reports of several wast files, e.g. a whole spec suite.
+#[derive(Debug, Default)]
+pub struct Summary {
+    /// The reports in the order the files were run.
+    pub reports: Vec<Report>,
+}
+
+impl Summary {
+    /// Returns the number of directives which passed, over all files.
+    pub fn passed(&self) -> usize {
+        self.reports.iter().map(Report::passed).sum()
+    }
+
+    /// Returns the number of directives which failed, over all files.
+    pub fn failed(&self) -> usize {
+        self.reports.iter().map(Report::failed).sum()
+    }
+
+    /// Returns the number of directives which were skipped, over all files.
+    pub fn skipped(&self) -> usize {
+        self.reports.iter().map(Report::skipped).sum()
+    }
+
+    /// Returns the summary as a JSON object holding the totals and the outcome of each directive,
+    /// along with the version of hal which produced it.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        // writing to a String can not fail
+        let _ = self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, json: &mut String) -> std::fmt::Result {
+        write!(json, "{{\"version\":\"{}\",", env!("CARGO_PKG_VERSION"))?;
+        write!(json, "\"passed\":{},\"failed\":{},\"skipped\":{},", self.passed(), self.failed(), self.skipped())?;
+        json.push_str("\"files\":[");
+        for (i, report) in self.reports.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write!(json, "{{\"file\":\"{}\",", escape(&report.file))?;
+            write!(json, "\"passed\":{},\"failed\":{},\"skipped\":{},", report.passed(), report.failed(), report.skipped())?;
+            json.push_str("\"directives\":[");
+            for (j, directive) in report.directives.iter().enumerate() {
+                if j > 0 {
+                    json.push(',');
+                }
+                write!(json, "{{\"line\":{},\"kind\":\"{}\",\"outcome\":\"{}\"", directive.line, directive.kind, directive.outcome.name())?;
+                if let Some(reason) = directive.outcome.reason() {
+                    write!(json, ",\"reason\":\"{}\"", escape(reason))?;
+                }
+                json.push('}');
+            }
+            json.push_str("]}");
+        }
+        json.push_str("]}");
+        Ok(())
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json() {
+        let summary = Summary {
+            reports: vec![Report {
+                file: "core/nop.wast".to_string(),
+                directives: vec![
+                    DirectiveReport { line: 1, kind: "module", outcome: Outcome::Passed },
+                    DirectiveReport { line: 4, kind: "assert_return", outcome: Outcome::Failed("expected \"1\"\n".to_string()) },
+                    DirectiveReport { line: 7, kind: "assert_exhaustion", outcome: Outcome::Skipped("not limited".to_string()) },
+                ],
+            }],
+        };
+
+        assert_eq!(
+            summary.to_json(),
+            format!(
+                "{{\"version\":\"{}\",\"passed\":1,\"failed\":1,\"skipped\":1,\"files\":[{{\"file\":\"core/nop.wast\",\"passed\":1,\"failed\":1,\"skipped\":1,\"directives\":[\
+                 {{\"line\":1,\"kind\":\"module\",\"outcome\":\"passed\"}},\
+                 {{\"line\":4,\"kind\":\"assert_return\",\"outcome\":\"failed\",\"reason\":\"expected \\\"1\\\"\\n\"}},\
+                 {{\"line\":7,\"kind\":\"assert_exhaustion\",\"outcome\":\"skipped\",\"reason\":\"not limited\"}}]}}]}}",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+    }
+}
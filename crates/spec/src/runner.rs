@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::fs;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+
+use wast::{QuoteWat, Wast, WastDirective, WastExecute, WastInvoke, Wat};
+use wast::token::Id;
+use wast::lexer::Lexer;
+use wast::parser::ParseBuffer;
+
+use hal_core::module::Value;
+use hal_core::Trap;
+use hal_env::{Environment, EnvironmentError, Exported, InstanceId, LoadError, LoadWasm, SpawnWasm, wasm_source};
+use hal_wasm::WasmFeatures;
+
+use crate::error::SpecError;
+use crate::spectest::define_spectest;
+use crate::value::{map_wast_args, map_wast_return_value, matches_wast_return_value, DisplayValues};
+use crate::{DirectiveReport, Outcome, Report};
+
+/// Reads the wast file at `path` and runs it like [`run_wast`], naming the report after the path.
+pub fn run_file(path: &Path) -> Result<Report, SpecError> {
+    run_file_with_features(path, &WasmFeatures::default())
+}
+
+/// Runs the wast file at `path` like [`run_file`], loading modules with the proposals enabled in
+/// `features`.
+pub fn run_file_with_features(path: &Path, features: &WasmFeatures) -> Result<Report, SpecError> {
+    let wast = fs::read_to_string(path)?;
+    run_wast_with_features(path.display().to_string(), &wast, features)
+}
+
+/// Runs every directive of `wast` in a fresh environment providing the `spectest` module. Modules
+/// are instantiated in order and directives refer to the most recent one unless they name another.
+///
+/// Only text which is not valid wast is an error, the directives themselves fail independently of
+/// each other, including ones panicking within the runtime.
+pub fn run_wast(file: impl Into<String>, wast: &str) -> Result<Report, SpecError> {
+    run_wast_with_features(file, wast, &WasmFeatures::default())
+}
+
+/// Runs `wast` like [`run_wast`], loading modules with the proposals enabled in `features`.
+pub fn run_wast_with_features(file: impl Into<String>, wast: &str, features: &WasmFeatures) -> Result<Report, SpecError> {
+    let mut lexer = Lexer::new(wast);
+    lexer.allow_confusing_unicode(true);
+    let buf = ParseBuffer::new_with_lexer(lexer)?;
+    let wast_data = wast::parser::parse::<Wast<'_>>(&buf)?;
+
+    let mut runner = Runner::new(features);
+    let directives = wast_data.directives.into_iter().map(|directive| {
+        let (line, _) = directive.span().linecol_in(wast);
+        let kind = directive_kind(&directive);
+        let outcome = match catch_unwind(AssertUnwindSafe(|| runner.run(directive))) {
+            Ok(Ok(())) => Outcome::Passed,
+            Ok(Err(outcome)) => outcome,
+            Err(panic) => Outcome::Failed(format!("panicked: {}", panic_message(panic.as_ref()))),
+        };
+        DirectiveReport { line: line + 1, kind, outcome }
+    }).collect();
+
+    Ok(Report { file: file.into(), directives })
+}
+
+struct Runner {
+    env: Environment,
+    // instances by their wast identifier, e.g. `$M1`, the most recent one under `None`
+    instances: HashMap<Option<String>, InstanceId>,
+}
+
+impl Runner {
+    fn new(features: &WasmFeatures) -> Self {
+        let mut env = Environment::with_features(features.clone());
+        define_spectest(&mut env);
+        Self { env, instances: HashMap::new() }
+    }
+
+    fn run(&mut self, directive: WastDirective<'_>) -> Result<(), Outcome> {
+        use wast::WastDirective::*;
+
+        match directive {
+            Wat(module) => self.instantiate(module),
+
+            AssertReturn { span: _, exec, results } => {
+                let expected = map_wast_return_value(&results)?;
+                let actual = match exec {
+                    WastExecute::Invoke(invoke) => self.invoke(&invoke)?.map_err(|trap| failed(format!("trapped: {}", trap)))?,
+                    WastExecute::Wat(mut module) => {
+                        let bytes = module.encode().map_err(|e| failed(format!("failed to encode module: {}", e)))?;
+                        self.env.spawn(wasm_source::bytes(bytes)).map_err(|e| failed(e.to_string()))?;
+                        Box::new([])
+                    }
+                    WastExecute::Get { module, global, .. } => {
+                        let id = self.instance_id(module)?;
+                        match self.env.instance(id).unwrap().get_export(global) {
+                            Ok(Exported::Global(global)) => Box::new([global.get()]),
+                            Ok(_) => return Err(failed(format!("{} is not a global", global))),
+                            Err(trap) => return Err(failed(trap.to_string())),
+                        }
+                    }
+                };
+                if expected.len() == actual.len() && expected.iter().zip(actual.iter()).all(|(e, a)| matches_wast_return_value(e, a)) {
+                    Ok(())
+                } else {
+                    Err(failed(format!("expected {:?}, got {}", expected, DisplayValues(&actual))))
+                }
+            }
+
+            AssertMalformed { span: _, mut module, message: _ } => {
+                // quoted modules are malformed if they can not be parsed as text
+                let quoted = matches!(module, QuoteWat::QuoteModule(..));
+                let Ok(bytes) = module.encode() else {
+                    return Ok(());
+                };
+                match self.env.load(wasm_source::bytes(bytes)) {
+                    Err(LoadError::WasmParsingFailed(_) | LoadError::WatParsingFailed(_)) => Ok(()),
+                    Ok(_) => Err(failed("expected malformed module, but it was loaded")),
+                    // the text is malformed in a way the encoder does not check, its binary can only be rejected as invalid
+                    Err(LoadError::ValidationFailed(_)) if quoted => Err(Outcome::Skipped("text is encoded without checking it".to_string())),
+                    Err(e) => Err(failed(format!("expected malformed module, got {}", e))),
+                }
+            }
+
+            AssertInvalid { span: _, mut module, message } => {
+                let bytes = module.encode().map_err(|e| failed(format!("failed to encode module: {}", e)))?;
+                match self.env.load(wasm_source::bytes(bytes)) {
+                    Err(LoadError::ValidationFailed(e)) => {
+                        let actual = format!("{}", e.kind());
+                        expect_message(message, &actual)
+                    }
+                    Ok(_) => Err(failed("expected invalid module, but it was loaded")),
+                    Err(e) => Err(failed(format!("expected invalid module, got {}", e))),
+                }
+            }
+
+            AssertExhaustion { span: _, call, message } => match self.invoke(&call)? {
+                Ok(results) => Err(failed(format!("expected exhaustion, got {}", DisplayValues(&results)))),
+                Err(trap) => expect_trap_message(message, &trap),
+            },
+
+            AssertTrap { span: _, exec, message } => match exec {
+                WastExecute::Invoke(invoke) => match self.invoke(&invoke)? {
+                    Ok(results) => Err(failed(format!("expected trap, got {}", DisplayValues(&results)))),
+                    Err(trap) => expect_trap_message(message, &trap),
+                },
+                WastExecute::Wat(mut module) => {
+                    let bytes = module.encode().map_err(|e| failed(format!("failed to encode module: {}", e)))?;
+                    match self.env.spawn(wasm_source::bytes(bytes)) {
+                        Ok(_) => Err(failed("expected trap, but instantiation succeeded")),
+                        Err(EnvironmentError::Trapped(trap)) => expect_trap_message(message, &trap),
+                        Err(e) => Err(failed(format!("expected trap, got {}", e))),
+                    }
+                }
+                WastExecute::Get { .. } => Err(failed("reading a global can not trap")),
+            },
+
+            AssertUnlinkable { span: _, mut module, message } => {
+                let bytes = module.encode().map_err(|e| failed(format!("failed to encode module: {}", e)))?;
+                match self.env.spawn(wasm_source::bytes(bytes)) {
+                    Err(EnvironmentError::Unlinkable(e)) => expect_message(message, &format!("{}", e)),
+                    Ok(_) => Err(failed("expected unlinkable, but instantiation succeeded")),
+                    Err(e) => Err(failed(format!("expected unlinkable, got {}", e))),
+                }
+            }
+
+            Invoke(invoke) => {
+                self.invoke(&invoke)?.map_err(|trap| failed(format!("trapped: {}", trap)))?;
+                Ok(())
+            }
+
+            Register { span: _, name, module } => {
+                let id = self.instance_id(module)?;
+                self.env.register(name, id).map_err(|e| failed(e.to_string()))
+            }
+
+            AssertException { .. } => Err(Outcome::Skipped("exceptions are not supported".to_string())),
+
+            Thread(_) | Wait { .. } => Err(Outcome::Skipped("threads are not supported".to_string())),
+        }
+    }
+
+    fn instantiate(&mut self, mut module: QuoteWat<'_>) -> Result<(), Outcome> {
+        let name = match &module {
+            QuoteWat::Wat(Wat::Module(module)) => module.id.map(|id| id.name().to_string()),
+            QuoteWat::Wat(Wat::Component(_)) | QuoteWat::QuoteComponent(..) => {
+                return Err(Outcome::Skipped("components are not supported".to_string()));
+            }
+            QuoteWat::QuoteModule(..) => None,
+        };
+        // directives following a module which failed to instantiate must not run against its predecessor
+        self.instances.remove(&None);
+        if name.is_some() {
+            self.instances.remove(&name);
+        }
+
+        let bytes = module.encode().map_err(|e| failed(format!("failed to encode module: {}", e)))?;
+        let id = self.env.spawn(wasm_source::bytes(bytes)).map_err(|e| failed(e.to_string()))?.id();
+        if name.is_some() {
+            self.instances.insert(name, id);
+        }
+        self.instances.insert(None, id);
+        Ok(())
+    }
+
+    // the outer result fails the directive before anything is invoked, the inner one is the invocation
+    fn invoke(&mut self, invoke: &WastInvoke<'_>) -> Result<Result<Box<[Value]>, Trap>, Outcome> {
+        let id = self.instance_id(invoke.module)?;
+        let args = map_wast_args(&invoke.args)?;
+        Ok(self.env.instance(id).unwrap().invoke(invoke.name, args))
+    }
+
+    // directives without a module identifier refer to the most recent instance
+    fn instance_id(&self, module: Option<Id<'_>>) -> Result<InstanceId, Outcome> {
+        let name = module.map(|id| id.name().to_string());
+        self.instances.get(&name).copied().ok_or_else(|| match name {
+            Some(name) => failed(format!("module ${} is not instantiated", name)),
+            None => failed("no module is instantiated"),
+        })
+    }
+}
+
+fn failed(reason: impl Into<String>) -> Outcome {
+    Outcome::Failed(reason.into())
+}
+
+fn directive_kind(directive: &WastDirective<'_>) -> &'static str {
+    use wast::WastDirective::*;
+
+    match directive {
+        Wat(_) => "module",
+        AssertMalformed { .. } => "assert_malformed",
+        AssertInvalid { .. } => "assert_invalid",
+        Register { .. } => "register",
+        Invoke(_) => "invoke",
+        AssertTrap { .. } => "assert_trap",
+        AssertReturn { .. } => "assert_return",
+        AssertExhaustion { .. } => "assert_exhaustion",
+        AssertUnlinkable { .. } => "assert_unlinkable",
+        AssertException { .. } => "assert_exception",
+        Thread(_) => "thread",
+        Wait { .. } => "wait",
+    }
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    panic.downcast_ref::<String>().cloned()
+        .or_else(|| panic.downcast_ref::<&str>().map(|message| message.to_string()))
+        .unwrap_or_default()
+}
+
+fn expect_message(expected: &str, actual: &str) -> Result<(), Outcome> {
+    if actual.starts_with(expected) {
+        Ok(())
+    } else {
+        Err(failed(format!("expected {:?}, got {:?}", expected, actual)))
+    }
+}
+
+// like the reference interpreter, some traps append details, e.g. "uninitialized element 2"
+fn expect_trap_message(expected: &str, trap: &Trap) -> Result<(), Outcome> {
+    let actual = format!("{}", trap);
+    if actual.starts_with(expected) {
+        Ok(())
+    } else {
+        Err(failed(format!("expected trap {:?}, got {:?}", expected, actual)))
+    }
+}
+
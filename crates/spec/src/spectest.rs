@@ -0,0 +1,34 @@
+use std::rc::Rc;
+
+use hal_core::module::{Extern, FunctionSignature, Global, GlobalType, Memory, Table, Value, ValueType};
+use hal_env::Environment;
+
+// the imports every spec test can rely on, see the `spectest` module of the reference interpreter
+pub(crate) fn define_spectest(env: &mut Environment) {
+    let prints: [(&str, &[ValueType]); 6] = [
+        ("print", &[]),
+        ("print_i32", &[ValueType::I32]),
+        ("print_i64", &[ValueType::I64]),
+        ("print_f32", &[ValueType::F32]),
+        ("print_f64", &[ValueType::F64]),
+        ("print_i32_f32", &[ValueType::I32, ValueType::F32]),
+    ];
+    for (name, params) in prints {
+        env.define_function("spectest", name, FunctionSignature::new(params.into(), [].into()), |_, _| Ok([].into()));
+    }
+    env.define_function("spectest", "print_f64_f64", FunctionSignature::new([ValueType::F64, ValueType::F64].into(), [].into()), |_, _| Ok([].into()));
+
+    let globals = [
+        ("global_i32", Value::I32(666)),
+        ("global_i64", Value::I64(666)),
+        ("global_f32", Value::F32(666.6)),
+        ("global_f64", Value::F64(666.6)),
+    ];
+    for (name, value) in globals {
+        let global_type = GlobalType { value_type: value.value_type(), mutable: false };
+        env.define("spectest", name, Extern::Global(Rc::new(Global::new(global_type, value).unwrap_or_else(|trap| panic!("{}", trap)))));
+    }
+
+    env.define("spectest", "table", Extern::Table(Rc::new(Table::new(ValueType::FuncRef, 10, Some(20)))));
+    env.define("spectest", "memory", Extern::Memory(Rc::new(Memory::new(1, Some(2)).unwrap())));
+}
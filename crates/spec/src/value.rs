@@ -0,0 +1,89 @@
+use std::fmt::{Display, Formatter};
+
+use wast::{WastArg, WastRet};
+use wast::core::{AbstractHeapType, HeapType, NanPattern, WastArgCore, WastRetCore};
+
+use hal_core::module::Value;
+
+use crate::Outcome;
+
+pub(crate) fn map_wast_return_value<'a>(results: &'a [WastRet<'a>]) -> Result<Vec<&'a WastRetCore<'a>>, Outcome> {
+    results.iter().map(|result| {
+        let WastRet::Core(result) = result else {
+            return Err(Outcome::Skipped("component values are not supported".to_string()));
+        };
+        supported_return_value(result)?;
+        Ok(result)
+    }).collect()
+}
+
+fn supported_return_value(expected: &WastRetCore<'_>) -> Result<(), Outcome> {
+    match expected {
+        WastRetCore::I32(_) | WastRetCore::I64(_) | WastRetCore::F32(_) | WastRetCore::F64(_) => Ok(()),
+        WastRetCore::RefNull(_) | WastRetCore::RefExtern(_) | WastRetCore::RefFunc(_) => Ok(()),
+        WastRetCore::Either(alternatives) => alternatives.iter().try_for_each(supported_return_value),
+        WastRetCore::V128(_) => Err(Outcome::Skipped("v128 values are not supported".to_string())),
+        _ => Err(Outcome::Skipped(format!("{:?} results are not supported", expected))),
+    }
+}
+
+pub(crate) fn matches_wast_return_value(expected: &WastRetCore<'_>, actual: &Value) -> bool {
+    match (expected, actual) {
+        (WastRetCore::I32(e), Value::I32(a)) => e == a,
+        (WastRetCore::I64(e), Value::I64(a)) => e == a,
+        (WastRetCore::F32(e), Value::F32(a)) => match e {
+            NanPattern::CanonicalNan => a.to_bits() & 0x7FFF_FFFF == 0x7FC0_0000,
+            NanPattern::ArithmeticNan => a.to_bits() & 0x7FC0_0000 == 0x7FC0_0000,
+            NanPattern::Value(e) => e.bits == a.to_bits(),
+        },
+        (WastRetCore::F64(e), Value::F64(a)) => match e {
+            NanPattern::CanonicalNan => a.to_bits() & 0x7FFF_FFFF_FFFF_FFFF == 0x7FF8_0000_0000_0000,
+            NanPattern::ArithmeticNan => a.to_bits() & 0x7FF8_0000_0000_0000 == 0x7FF8_0000_0000_0000,
+            NanPattern::Value(e) => e.bits == a.to_bits(),
+        },
+        (WastRetCore::RefNull(None), Value::FuncRef(a)) => a.is_none(),
+        (WastRetCore::RefNull(None), Value::ExternRef(a)) => a.is_none(),
+        (WastRetCore::RefNull(Some(HeapType::Abstract { ty: AbstractHeapType::Func, .. })), Value::FuncRef(a)) => a.is_none(),
+        (WastRetCore::RefNull(Some(HeapType::Abstract { ty: AbstractHeapType::Extern, .. })), Value::ExternRef(a)) => a.is_none(),
+        (WastRetCore::RefExtern(None), Value::ExternRef(a)) => a.is_some(),
+        (WastRetCore::RefExtern(Some(e)), Value::ExternRef(a)) => Some(*e) == *a,
+        (WastRetCore::RefFunc(_), Value::FuncRef(a)) => a.is_some(),
+        (WastRetCore::Either(alternatives), actual) => alternatives.iter().any(|e| matches_wast_return_value(e, actual)),
+        _ => false,
+    }
+}
+
+pub(crate) fn map_wast_args(args: &[WastArg<'_>]) -> Result<Vec<Value>, Outcome> {
+    args.iter().map(|arg| {
+        let WastArg::Core(arg) = arg else {
+            return Err(Outcome::Skipped("component values are not supported".to_string()));
+        };
+        match arg {
+            WastArgCore::I32(v) => Ok(Value::I32(*v)),
+            WastArgCore::I64(v) => Ok(Value::I64(*v)),
+            WastArgCore::F32(v) => Ok(Value::F32(f32::from_bits(v.bits))),
+            WastArgCore::F64(v) => Ok(Value::F64(f64::from_bits(v.bits))),
+            WastArgCore::RefNull(HeapType::Abstract { ty: AbstractHeapType::Func, .. }) => Ok(Value::FuncRef(None)),
+            WastArgCore::RefNull(HeapType::Abstract { ty: AbstractHeapType::Extern, .. }) => Ok(Value::ExternRef(None)),
+            WastArgCore::RefExtern(v) => Ok(Value::ExternRef(Some(*v))),
+            WastArgCore::V128(_) => Err(Outcome::Skipped("v128 values are not supported".to_string())),
+            _ => Err(Outcome::Skipped(format!("{:?} arguments are not supported", arg))),
+        }
+    }).collect()
+}
+
+// the values returned by an invocation, e.g. `[1 2.5]`
+pub(crate) struct DisplayValues<'a>(pub(crate) &'a [Value]);
+
+impl Display for DisplayValues<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (idx, value) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        write!(f, "]")
+    }
+}
@@ -0,0 +1,516 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use hal_wasm::{WasmFunc, WasmFunctionBody, WasmInstruction, WasmValueType};
+use hal_wasm::WasmValueType::{ExternRef, F32, F64, FuncRef, I32, I64};
+
+use crate::error::ValidationErrorKind;
+use crate::error::ValidationErrorKind::{TypeMismatch, UnexpectedEnd};
+use crate::validator::Context;
+use crate::Result;
+
+// the type of an operand, `None` for operands of unreachable code which match any type
+type Operand = Option<WasmValueType>;
+
+#[derive(PartialEq)]
+enum FrameKind {
+    Function,
+    Block,
+    Loop,
+    If,
+    Else,
+}
+
+struct Frame {
+    kind: FrameKind,
+    params: Vec<WasmValueType>,
+    results: Vec<WasmValueType>,
+    // the operand stack height when the frame was entered
+    height: usize,
+    unreachable: bool,
+}
+
+/// Type checks a function body with the operand and control stack algorithm of the specification,
+/// an error carries the position of the offending instruction.
+pub(crate) fn validate_function(context: &Context<'_>, function_type: &WasmFunc, body: &WasmFunctionBody) -> Result<(), (u32, ValidationErrorKind)> {
+    let mut locals = function_type.params.to_vec();
+    for (count, value_type) in body.locals.iter() {
+        locals.extend(core::iter::repeat(value_type.clone()).take(*count as usize));
+    }
+
+    let mut validator = CodeValidator {
+        context,
+        locals,
+        operands: vec![],
+        frames: vec![],
+    };
+    validator.push_frame(FrameKind::Function, vec![], function_type.returns.to_vec());
+
+    for (at, instruction) in body.code.iter().enumerate() {
+        if validator.frames.is_empty() {
+            return Err((at as u32, UnexpectedEnd));
+        }
+        validator.instruction(&body.code, at, instruction).map_err(|kind| (at as u32, kind))?;
+    }
+
+    if !validator.frames.is_empty() {
+        return Err((body.code.len() as u32, UnexpectedEnd));
+    }
+    Ok(())
+}
+
+struct CodeValidator<'a> {
+    context: &'a Context<'a>,
+    locals: Vec<WasmValueType>,
+    operands: Vec<Operand>,
+    frames: Vec<Frame>,
+}
+
+impl<'a> CodeValidator<'a> {
+    fn instruction(&mut self, code: &[WasmInstruction], at: usize, instruction: &WasmInstruction) -> Result<(), ValidationErrorKind> {
+        match instruction {
+            WasmInstruction::AbsF32 | WasmInstruction::CeilF32 | WasmInstruction::FloorF32 | WasmInstruction::NearestF32 |
+            WasmInstruction::NegF32 | WasmInstruction::SqrtF32 | WasmInstruction::TruncF32 => self.operation(&[F32], &[F32]),
+            WasmInstruction::AbsF64 | WasmInstruction::CeilF64 | WasmInstruction::FloorF64 | WasmInstruction::NearestF64 |
+            WasmInstruction::NegF64 | WasmInstruction::SqrtF64 | WasmInstruction::TruncF64 => self.operation(&[F64], &[F64]),
+
+            WasmInstruction::AddF32 | WasmInstruction::CopysignF32 | WasmInstruction::DivF32 | WasmInstruction::MaxF32 |
+            WasmInstruction::MinF32 | WasmInstruction::MulF32 | WasmInstruction::SubF32 => self.operation(&[F32, F32], &[F32]),
+            WasmInstruction::AddF64 | WasmInstruction::CopysignF64 | WasmInstruction::DivF64 | WasmInstruction::MaxF64 |
+            WasmInstruction::MinF64 | WasmInstruction::MulF64 | WasmInstruction::SubF64 => self.operation(&[F64, F64], &[F64]),
+
+            WasmInstruction::AddI32 | WasmInstruction::AndI32 | WasmInstruction::DivSI32 | WasmInstruction::DivUI32 |
+            WasmInstruction::MulI32 | WasmInstruction::OrI32 | WasmInstruction::RemSI32 | WasmInstruction::RemUI32 |
+            WasmInstruction::RotlI32 | WasmInstruction::RotrI32 | WasmInstruction::ShlI32 | WasmInstruction::ShrSI32 |
+            WasmInstruction::ShrUI32 | WasmInstruction::SubI32 | WasmInstruction::XorI32 |
+            WasmInstruction::EqI32 | WasmInstruction::NeI32 | WasmInstruction::GeSI32 | WasmInstruction::GeUI32 |
+            WasmInstruction::GtSI32 | WasmInstruction::GtUI32 | WasmInstruction::LeSI32 | WasmInstruction::LeUI32 |
+            WasmInstruction::LtSI32 | WasmInstruction::LtUI32 => self.operation(&[I32, I32], &[I32]),
+            WasmInstruction::AddI64 | WasmInstruction::AndI64 | WasmInstruction::DivSI64 | WasmInstruction::DivUI64 |
+            WasmInstruction::MulI64 | WasmInstruction::OrI64 | WasmInstruction::RemSI64 | WasmInstruction::RemUI64 |
+            WasmInstruction::RotlI64 | WasmInstruction::RotrI64 | WasmInstruction::ShlI64 | WasmInstruction::ShrSI64 |
+            WasmInstruction::ShrUI64 | WasmInstruction::SubI64 | WasmInstruction::XorI64 => self.operation(&[I64, I64], &[I64]),
+
+            WasmInstruction::EqI64 | WasmInstruction::NeI64 | WasmInstruction::GeSI64 | WasmInstruction::GeUI64 |
+            WasmInstruction::GtSI64 | WasmInstruction::GtUI64 | WasmInstruction::LeSI64 | WasmInstruction::LeUI64 |
+            WasmInstruction::LtSI64 | WasmInstruction::LtUI64 => self.operation(&[I64, I64], &[I32]),
+            WasmInstruction::EqF32 | WasmInstruction::NeF32 | WasmInstruction::GeF32 | WasmInstruction::GtF32 |
+            WasmInstruction::LeF32 | WasmInstruction::LtF32 => self.operation(&[F32, F32], &[I32]),
+            WasmInstruction::EqF64 | WasmInstruction::NeF64 | WasmInstruction::GeF64 | WasmInstruction::GtF64 |
+            WasmInstruction::LeF64 | WasmInstruction::LtF64 => self.operation(&[F64, F64], &[I32]),
+
+            WasmInstruction::ClzI32 | WasmInstruction::CtzI32 | WasmInstruction::PopcntI32 | WasmInstruction::EqzI32 |
+            WasmInstruction::Extend8SI32 | WasmInstruction::Extend16SI32 => self.operation(&[I32], &[I32]),
+            WasmInstruction::ClzI64 | WasmInstruction::CtzI64 | WasmInstruction::PopcntI64 |
+            WasmInstruction::Extend8SI64 | WasmInstruction::Extend16SI64 | WasmInstruction::Extend32SI64 => self.operation(&[I64], &[I64]),
+            WasmInstruction::EqzI64 | WasmInstruction::WrapI32I64 => self.operation(&[I64], &[I32]),
+
+            WasmInstruction::ConstF32(_) => self.operation(&[], &[F32]),
+            WasmInstruction::ConstF64(_) => self.operation(&[], &[F64]),
+            WasmInstruction::ConstI32(_) => self.operation(&[], &[I32]),
+            WasmInstruction::ConstI64(_) => self.operation(&[], &[I64]),
+
+            WasmInstruction::ConvertI32SF32 | WasmInstruction::ConvertI32UF32 | WasmInstruction::ReinterpretI32F32 => self.operation(&[I32], &[F32]),
+            WasmInstruction::ConvertI32SF64 | WasmInstruction::ConvertI32UF64 => self.operation(&[I32], &[F64]),
+            WasmInstruction::ConvertI64SF32 | WasmInstruction::ConvertI64UF32 => self.operation(&[I64], &[F32]),
+            WasmInstruction::ConvertI64SF64 | WasmInstruction::ConvertI64UF64 | WasmInstruction::ReinterpretI64F64 => self.operation(&[I64], &[F64]),
+            WasmInstruction::DemoteF64F32 => self.operation(&[F64], &[F32]),
+            WasmInstruction::PromoteF32F64 => self.operation(&[F32], &[F64]),
+            WasmInstruction::ExtendI32SI64 | WasmInstruction::ExtendI32UI64 => self.operation(&[I32], &[I64]),
+            WasmInstruction::ReinterpretF32I32 | WasmInstruction::TruncF32SI32 | WasmInstruction::TruncF32UI32 |
+            WasmInstruction::TruncSatF32SI32 | WasmInstruction::TruncSatF32UI32 => self.operation(&[F32], &[I32]),
+            WasmInstruction::TruncF32SI64 | WasmInstruction::TruncF32UI64 |
+            WasmInstruction::TruncSatF32SI64 | WasmInstruction::TruncSatF32UI64 => self.operation(&[F32], &[I64]),
+            WasmInstruction::TruncF64SI32 | WasmInstruction::TruncF64UI32 |
+            WasmInstruction::TruncSatF64SI32 | WasmInstruction::TruncSatF64UI32 => self.operation(&[F64], &[I32]),
+            WasmInstruction::ReinterpretF64I64 | WasmInstruction::TruncF64SI64 | WasmInstruction::TruncF64UI64 |
+            WasmInstruction::TruncSatF64SI64 | WasmInstruction::TruncSatF64UI64 => self.operation(&[F64], &[I64]),
+
+            WasmInstruction::Block(_) | WasmInstruction::BlockWithType(..) | WasmInstruction::BlockWithFuncType(..) => {
+                let (params, results) = self.block_type(instruction)?;
+                self.pop_operands(&params)?;
+                self.push_frame(FrameKind::Block, params, results);
+                Ok(())
+            }
+            WasmInstruction::Loop(_) | WasmInstruction::LoopWithType(..) | WasmInstruction::LoopWithFuncType(..) => {
+                let (params, results) = self.block_type(instruction)?;
+                self.pop_operands(&params)?;
+                self.push_frame(FrameKind::Loop, params, results);
+                Ok(())
+            }
+            WasmInstruction::If(_) | WasmInstruction::IfWithType(..) | WasmInstruction::IfWithFuncType(..) => {
+                let (params, results) = self.block_type(instruction)?;
+                self.pop_expected(&I32)?;
+                self.pop_operands(&params)?;
+                self.push_frame(FrameKind::If, params, results);
+                Ok(())
+            }
+            WasmInstruction::Else(_) => {
+                let frame = self.pop_frame()?;
+                if frame.kind != FrameKind::If {
+                    return Err(TypeMismatch);
+                }
+                self.push_frame(FrameKind::Else, frame.params, frame.results);
+                Ok(())
+            }
+            WasmInstruction::End => {
+                let frame = self.pop_frame()?;
+                // an if without else passes its parameters through
+                if frame.kind == FrameKind::If && frame.params != frame.results {
+                    return Err(TypeMismatch);
+                }
+                if frame.kind != FrameKind::Function {
+                    self.push_operands(&frame.results);
+                }
+                Ok(())
+            }
+
+            WasmInstruction::Br(depth) => {
+                let types = self.label_types(*depth)?;
+                self.pop_operands(&types)?;
+                self.unreachable();
+                Ok(())
+            }
+            WasmInstruction::BrIf(depth) => {
+                self.pop_expected(&I32)?;
+                let types = self.label_types(*depth)?;
+                self.pop_operands(&types)?;
+                self.push_operands(&types);
+                Ok(())
+            }
+            WasmInstruction::BrTable(count, default) => {
+                self.pop_expected(&I32)?;
+                let types = self.label_types(*default)?;
+                // the labels follow the instruction
+                for label in code[at + 1..].iter().take(*count as usize) {
+                    let WasmInstruction::BrLabel(depth) = label else {
+                        return Err(UnexpectedEnd);
+                    };
+                    let label_types = self.label_types(*depth)?;
+                    if label_types.len() != types.len() {
+                        return Err(TypeMismatch);
+                    }
+                    let operands = self.pop_operands(&label_types)?;
+                    self.operands.extend(operands);
+                }
+                self.pop_operands(&types)?;
+                self.unreachable();
+                Ok(())
+            }
+            WasmInstruction::BrLabel(_) => Ok(()),
+
+            WasmInstruction::Call(idx) => {
+                let function = self.context.function(*idx)?;
+                self.operation(&function.params, &function.returns)
+            }
+            WasmInstruction::CallIndirect(type_idx, table_idx) => {
+                let function = self.indirect_function_type(*type_idx, *table_idx)?;
+                self.pop_expected(&I32)?;
+                self.operation(&function.params, &function.returns)
+            }
+            WasmInstruction::Return => {
+                let results = self.frames[0].results.clone();
+                self.pop_operands(&results)?;
+                self.unreachable();
+                Ok(())
+            }
+            WasmInstruction::ReturnCall(idx) => {
+                let function = self.context.function(*idx)?;
+                self.return_call(function)
+            }
+            WasmInstruction::ReturnCallIndirect(type_idx, table_idx) => {
+                let function = self.indirect_function_type(*type_idx, *table_idx)?;
+                self.pop_expected(&I32)?;
+                self.return_call(function)
+            }
+
+            WasmInstruction::Nop | WasmInstruction::EndBlockFrame => Ok(()),
+            WasmInstruction::Unreachable => {
+                self.unreachable();
+                Ok(())
+            }
+
+            WasmInstruction::Drop | WasmInstruction::Drop64 | WasmInstruction::Drop128 | WasmInstruction::DropRef => {
+                self.pop()?;
+                Ok(())
+            }
+            WasmInstruction::Select32 | WasmInstruction::Select64 | WasmInstruction::Select128 | WasmInstruction::SelectRef => {
+                self.pop_expected(&I32)?;
+                let first = self.pop()?;
+                let second = self.pop()?;
+                let operand = match (first, second) {
+                    (Some(first), Some(second)) if first != second => return Err(TypeMismatch),
+                    (first, second) => first.or(second),
+                };
+                // an untyped select (parsed as select32) only applies to numeric operands
+                let matches = match (instruction, &operand) {
+                    (_, None) => true,
+                    (WasmInstruction::Select32 | WasmInstruction::Select128, Some(value_type)) => !is_reference(value_type),
+                    (WasmInstruction::Select64, Some(value_type)) => matches!(value_type, I64 | F64),
+                    (_, Some(value_type)) => is_reference(value_type),
+                };
+                if !matches {
+                    return Err(TypeMismatch);
+                }
+                self.operands.push(operand);
+                Ok(())
+            }
+            WasmInstruction::SelectWithArity(_) => Err(ValidationErrorKind::InvalidResultArity),
+
+            WasmInstruction::LocalGet32(idx) | WasmInstruction::LocalGet64(idx) |
+            WasmInstruction::LocalGet128(idx) | WasmInstruction::LocalGetRef(idx) => {
+                let value_type = self.local(*idx)?;
+                self.operation(&[], &[value_type])
+            }
+            WasmInstruction::LocalSet32(idx) | WasmInstruction::LocalSet64(idx) |
+            WasmInstruction::LocalSet128(idx) | WasmInstruction::LocalSetRef(idx) => {
+                let value_type = self.local(*idx)?;
+                self.operation(&[value_type], &[])
+            }
+            WasmInstruction::LocalTee32(idx) | WasmInstruction::LocalTee64(idx) |
+            WasmInstruction::LocalTee128(idx) | WasmInstruction::LocalTeeRef(idx) => {
+                let value_type = [self.local(*idx)?];
+                self.operation(&value_type, &value_type)
+            }
+
+            WasmInstruction::GlobalGet(idx) => {
+                let global = self.context.global(*idx)?;
+                self.operation(&[], core::slice::from_ref(&global.value_type))
+            }
+            WasmInstruction::GlobalSet32(idx) | WasmInstruction::GlobalSet64(idx) |
+            WasmInstruction::GlobalSet128(idx) | WasmInstruction::GlobalSetRef(idx) => {
+                let global = self.context.global(*idx)?;
+                if !global.mutable {
+                    return Err(ValidationErrorKind::ImmutableGlobal(*idx));
+                }
+                self.operation(core::slice::from_ref(&global.value_type), &[])
+            }
+
+            WasmInstruction::LoadI32 { flags, .. } => self.load(*flags, 2, I32),
+            WasmInstruction::LoadI64 { flags, .. } => self.load(*flags, 3, I64),
+            WasmInstruction::LoadF32 { flags, .. } => self.load(*flags, 2, F32),
+            WasmInstruction::LoadF64 { flags, .. } => self.load(*flags, 3, F64),
+            WasmInstruction::Load8SI32 { flags, .. } | WasmInstruction::Load8UI32 { flags, .. } => self.load(*flags, 0, I32),
+            WasmInstruction::Load16SI32 { flags, .. } | WasmInstruction::Load16UI32 { flags, .. } => self.load(*flags, 1, I32),
+            WasmInstruction::Load8SI64 { flags, .. } | WasmInstruction::Load8UI64 { flags, .. } => self.load(*flags, 0, I64),
+            WasmInstruction::Load16SI64 { flags, .. } | WasmInstruction::Load16UI64 { flags, .. } => self.load(*flags, 1, I64),
+            WasmInstruction::Load32SI64 { flags, .. } | WasmInstruction::Load32UI64 { flags, .. } => self.load(*flags, 2, I64),
+
+            WasmInstruction::StoreI32 { flags, .. } => self.store(*flags, 2, I32),
+            WasmInstruction::StoreI64 { flags, .. } => self.store(*flags, 3, I64),
+            WasmInstruction::StoreF32 { flags, .. } => self.store(*flags, 2, F32),
+            WasmInstruction::StoreF64 { flags, .. } => self.store(*flags, 3, F64),
+            WasmInstruction::Store8I32 { flags, .. } => self.store(*flags, 0, I32),
+            WasmInstruction::Store16I32 { flags, .. } => self.store(*flags, 1, I32),
+            WasmInstruction::Store8I64 { flags, .. } => self.store(*flags, 0, I64),
+            WasmInstruction::Store16I64 { flags, .. } => self.store(*flags, 1, I64),
+            WasmInstruction::Store32I64 { flags, .. } => self.store(*flags, 2, I64),
+
+            WasmInstruction::MemorySize(idx) => {
+                self.context.memory(*idx)?;
+                self.operation(&[], &[I32])
+            }
+            WasmInstruction::MemoryGrow(idx) => {
+                self.context.memory(*idx)?;
+                self.operation(&[I32], &[I32])
+            }
+            WasmInstruction::MemoryFill(idx) => {
+                self.context.memory(*idx)?;
+                self.operation(&[I32, I32, I32], &[])
+            }
+            WasmInstruction::MemoryCopy(to, from) => {
+                self.context.memory(*to)?;
+                self.context.memory(*from)?;
+                self.operation(&[I32, I32, I32], &[])
+            }
+            WasmInstruction::MemoryInit(segment, idx) => {
+                self.context.memory(*idx)?;
+                self.context.data(*segment)?;
+                self.operation(&[I32, I32, I32], &[])
+            }
+            WasmInstruction::DataDrop(segment) => self.context.data(*segment),
+
+            WasmInstruction::TableGet(idx) => {
+                let table = self.context.table(*idx)?;
+                self.operation(&[I32], core::slice::from_ref(&table.element_type))
+            }
+            WasmInstruction::TableSet(idx) => {
+                let table = self.context.table(*idx)?;
+                self.operation(&[I32, table.element_type.clone()], &[])
+            }
+            WasmInstruction::TableSize(idx) => {
+                self.context.table(*idx)?;
+                self.operation(&[], &[I32])
+            }
+            WasmInstruction::TableGrow(idx) => {
+                let table = self.context.table(*idx)?;
+                self.operation(&[table.element_type.clone(), I32], &[I32])
+            }
+            WasmInstruction::TableFill(idx) => {
+                let table = self.context.table(*idx)?;
+                self.operation(&[I32, table.element_type.clone(), I32], &[])
+            }
+            WasmInstruction::TableCopy { from, to } => {
+                let to = self.context.table(*to)?;
+                let from = self.context.table(*from)?;
+                if to.element_type != from.element_type {
+                    return Err(TypeMismatch);
+                }
+                self.operation(&[I32, I32, I32], &[])
+            }
+            WasmInstruction::TableInit(segment, idx) => {
+                let table = self.context.table(*idx)?;
+                if self.context.element(*segment)? != &table.element_type {
+                    return Err(TypeMismatch);
+                }
+                self.operation(&[I32, I32, I32], &[])
+            }
+            WasmInstruction::ElemDrop(segment) => {
+                self.context.element(*segment)?;
+                Ok(())
+            }
+
+            WasmInstruction::RefFunc(idx) => {
+                self.context.function(*idx)?;
+                if !self.context.references.contains(idx) {
+                    return Err(ValidationErrorKind::UndeclaredFunctionReference(*idx));
+                }
+                self.operation(&[], &[FuncRef])
+            }
+            WasmInstruction::RefIsNull => {
+                if self.pop()?.is_some_and(|value_type| !is_reference(&value_type)) {
+                    return Err(TypeMismatch);
+                }
+                self.operation(&[], &[I32])
+            }
+            WasmInstruction::RefNull(value_type) => {
+                if !is_reference(value_type) {
+                    return Err(TypeMismatch);
+                }
+                self.operation(&[], core::slice::from_ref(value_type))
+            }
+        }
+    }
+
+    fn block_type(&self, instruction: &WasmInstruction) -> Result<(Vec<WasmValueType>, Vec<WasmValueType>), ValidationErrorKind> {
+        match instruction {
+            WasmInstruction::BlockWithType(value_type, _) | WasmInstruction::LoopWithType(value_type, _) |
+            WasmInstruction::IfWithType(value_type, _) => Ok((vec![], vec![value_type.clone()])),
+            WasmInstruction::BlockWithFuncType(idx, _) | WasmInstruction::LoopWithFuncType(idx, _) |
+            WasmInstruction::IfWithFuncType(idx, _) => {
+                let function_type = self.context.function_type(*idx)?;
+                Ok((function_type.params.to_vec(), function_type.returns.to_vec()))
+            }
+            _ => Ok((vec![], vec![])),
+        }
+    }
+
+    fn indirect_function_type(&self, type_idx: u32, table_idx: u32) -> Result<&'a WasmFunc, ValidationErrorKind> {
+        if self.context.table(table_idx)?.element_type != FuncRef {
+            return Err(TypeMismatch);
+        }
+        self.context.function_type(type_idx)
+    }
+
+    // a tail call replaces the caller, so both must return the same results
+    fn return_call(&mut self, function: &WasmFunc) -> Result<(), ValidationErrorKind> {
+        if function.returns.as_ref() != self.frames[0].results.as_slice() {
+            return Err(TypeMismatch);
+        }
+        self.pop_operands(&function.params)?;
+        self.unreachable();
+        Ok(())
+    }
+
+    fn load(&mut self, flags: u32, natural_alignment: u32, value_type: WasmValueType) -> Result<(), ValidationErrorKind> {
+        self.context.memory(0)?;
+        if flags > natural_alignment {
+            return Err(ValidationErrorKind::InvalidAlignment(flags));
+        }
+        self.operation(&[I32], &[value_type])
+    }
+
+    fn store(&mut self, flags: u32, natural_alignment: u32, value_type: WasmValueType) -> Result<(), ValidationErrorKind> {
+        self.context.memory(0)?;
+        if flags > natural_alignment {
+            return Err(ValidationErrorKind::InvalidAlignment(flags));
+        }
+        self.operation(&[I32, value_type], &[])
+    }
+
+    fn local(&self, idx: u32) -> Result<WasmValueType, ValidationErrorKind> {
+        self.locals.get(idx as usize).cloned().ok_or(ValidationErrorKind::UnknownLocal(idx))
+    }
+
+    // branches to a loop continue it and take its parameters, all other labels take the results
+    fn label_types(&self, depth: u32) -> Result<Vec<WasmValueType>, ValidationErrorKind> {
+        let frame = self.frames.iter().rev().nth(depth as usize).ok_or(ValidationErrorKind::UnknownLabel(depth))?;
+        Ok(match frame.kind {
+            FrameKind::Loop => frame.params.clone(),
+            _ => frame.results.clone(),
+        })
+    }
+
+    fn operation(&mut self, params: &[WasmValueType], results: &[WasmValueType]) -> Result<(), ValidationErrorKind> {
+        self.pop_operands(params)?;
+        self.push_operands(results);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<Operand, ValidationErrorKind> {
+        let frame = self.frames.last().ok_or(UnexpectedEnd)?;
+        if self.operands.len() == frame.height {
+            return if frame.unreachable { Ok(None) } else { Err(TypeMismatch) };
+        }
+        Ok(self.operands.pop().flatten())
+    }
+
+    fn pop_expected(&mut self, expected: &WasmValueType) -> Result<Operand, ValidationErrorKind> {
+        match self.pop()? {
+            Some(actual) if &actual != expected => Err(TypeMismatch),
+            operand => Ok(operand),
+        }
+    }
+
+    // pops the operands in reverse, returning them in order
+    fn pop_operands(&mut self, expected: &[WasmValueType]) -> Result<Vec<Operand>, ValidationErrorKind> {
+        let mut operands = Vec::with_capacity(expected.len());
+        for value_type in expected.iter().rev() {
+            operands.push(self.pop_expected(value_type)?);
+        }
+        operands.reverse();
+        Ok(operands)
+    }
+
+    fn push_operands(&mut self, types: &[WasmValueType]) {
+        self.operands.extend(types.iter().cloned().map(Some));
+    }
+
+    fn push_frame(&mut self, kind: FrameKind, params: Vec<WasmValueType>, results: Vec<WasmValueType>) {
+        let height = self.operands.len();
+        self.push_operands(&params);
+        self.frames.push(Frame { kind, params, results, height, unreachable: false });
+    }
+
+    fn pop_frame(&mut self) -> Result<Frame, ValidationErrorKind> {
+        let frame = self.frames.last().ok_or(UnexpectedEnd)?;
+        let results = frame.results.clone();
+        let height = frame.height;
+        self.pop_operands(&results)?;
+        if self.operands.len() != height {
+            return Err(TypeMismatch);
+        }
+        Ok(self.frames.pop().unwrap())
+    }
+
+    // the rest of the block can not be reached, so its operands may have any type
+    fn unreachable(&mut self) {
+        let frame = self.frames.last_mut().unwrap();
+        self.operands.truncate(frame.height);
+        frame.unreachable = true;
+    }
+}
+
+fn is_reference(value_type: &WasmValueType) -> bool {
+    matches!(value_type, FuncRef | ExternRef)
+}
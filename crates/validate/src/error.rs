@@ -0,0 +1,132 @@
+use alloc::string::String;
+use core::fmt::{Display, Formatter};
+
+use hal_wasm::WasmFeature;
+
+/// Describes why a module is invalid, displayed with the message used by the specification tests.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub enum ValidationErrorKind {
+    /// An initializer or offset uses an instruction which is not constant.
+    ConstantExpressionRequired,
+    /// `memory.init` or `data.drop` is used without a data count section.
+    DataCountRequired,
+    /// Two exports share the name.
+    DuplicateExportName(String),
+    /// The module uses a proposal which is not enabled.
+    FeatureDisabled(WasmFeature),
+    /// `global.set` targets the immutable global at the index.
+    ImmutableGlobal(u32),
+    /// The data count section and the data section disagree, the count comes first.
+    InconsistentDataCount(u32, u32),
+    /// The function and the code section disagree, the function count comes first.
+    InconsistentFunctionCount(u32, u32),
+    /// A memory access is aligned beyond its natural alignment.
+    InvalidAlignment(u32),
+    /// A minimum is greater than its maximum.
+    InvalidLimits,
+    /// A typed select names other than one result type.
+    InvalidResultArity,
+    /// The start function at the index takes parameters or returns results.
+    InvalidStartFunction(u32),
+    /// A memory has more pages than can be addressed.
+    MemorySizeTooLarge,
+    /// An instruction finds operands of other types than it expects.
+    TypeMismatch,
+    /// `ref.func` refers to the function at the index without it being declared.
+    UndeclaredFunctionReference(u32),
+    /// A function body ends with unclosed blocks, or an `end` closes no block.
+    UnexpectedEnd,
+    /// No data segment at the index.
+    UnknownDataSegment(u32),
+    /// No element segment at the index.
+    UnknownElementSegment(u32),
+    /// No function at the index.
+    UnknownFunction(u32),
+    /// No global at the index.
+    UnknownGlobal(u32),
+    /// No enclosing block at the depth.
+    UnknownLabel(u32),
+    /// No local at the index.
+    UnknownLocal(u32),
+    /// No memory at the index.
+    UnknownMemory(u32),
+    /// No table at the index.
+    UnknownTable(u32),
+    /// No function signature at the index.
+    UnknownType(u32),
+}
+
+/// A module which failed validation.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub enum ValidationError {
+    /// A definition outside of function bodies is invalid.
+    Module(ValidationErrorKind),
+
+    /// An instruction of a function body is invalid.
+    Function {
+        /// The index of the function, counting imported functions first.
+        function: u32,
+
+        /// The position of the instruction in the function's code.
+        offset: u32,
+
+        /// Why the instruction is invalid.
+        kind: ValidationErrorKind,
+    },
+}
+
+impl ValidationError {
+    /// Returns why the module is invalid, regardless of where.
+    pub fn kind(&self) -> &ValidationErrorKind {
+        match self {
+            ValidationError::Module(kind) => kind,
+            ValidationError::Function { kind, .. } => kind,
+        }
+    }
+}
+
+impl Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationErrorKind::ConstantExpressionRequired => write!(f, "constant expression required"),
+            ValidationErrorKind::DataCountRequired => write!(f, "data count section required"),
+            ValidationErrorKind::DuplicateExportName(name) => write!(f, "duplicate export name {}", name),
+            // the specification tests predate the proposal and expect the message of the MVP
+            ValidationErrorKind::FeatureDisabled(WasmFeature::MultiMemory) => write!(f, "multiple memories"),
+            ValidationErrorKind::FeatureDisabled(feature) => write!(f, "{} support is not enabled", feature),
+            ValidationErrorKind::ImmutableGlobal(idx) => write!(f, "global is immutable: {}", idx),
+            ValidationErrorKind::InconsistentDataCount(count, data) => write!(f, "data count and data section have inconsistent lengths: {} and {}", count, data),
+            ValidationErrorKind::InconsistentFunctionCount(functions, codes) => write!(f, "function and code section have inconsistent lengths: {} and {}", functions, codes),
+            ValidationErrorKind::InvalidAlignment(flags) => write!(f, "alignment must not be larger than natural: {}", flags),
+            ValidationErrorKind::InvalidLimits => write!(f, "size minimum must not be greater than maximum"),
+            ValidationErrorKind::InvalidResultArity => write!(f, "invalid result arity"),
+            ValidationErrorKind::InvalidStartFunction(idx) => write!(f, "start function {} must not take parameters or return results", idx),
+            ValidationErrorKind::MemorySizeTooLarge => write!(f, "memory size must be at most 65536 pages (4GiB)"),
+            ValidationErrorKind::TypeMismatch => write!(f, "type mismatch"),
+            ValidationErrorKind::UndeclaredFunctionReference(idx) => write!(f, "undeclared function reference {}", idx),
+            ValidationErrorKind::UnexpectedEnd => write!(f, "unexpected end"),
+            ValidationErrorKind::UnknownDataSegment(idx) => write!(f, "unknown data segment {}", idx),
+            ValidationErrorKind::UnknownElementSegment(idx) => write!(f, "unknown elem segment {}", idx),
+            ValidationErrorKind::UnknownFunction(idx) => write!(f, "unknown function {}", idx),
+            ValidationErrorKind::UnknownGlobal(idx) => write!(f, "unknown global {}", idx),
+            ValidationErrorKind::UnknownLabel(depth) => write!(f, "unknown label {}", depth),
+            ValidationErrorKind::UnknownLocal(idx) => write!(f, "unknown local {}", idx),
+            ValidationErrorKind::UnknownMemory(idx) => write!(f, "unknown memory {}", idx),
+            ValidationErrorKind::UnknownTable(idx) => write!(f, "unknown table {}", idx),
+            ValidationErrorKind::UnknownType(idx) => write!(f, "unknown type {}", idx),
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationError::Module(kind) => write!(f, "{}", kind),
+            ValidationError::Function { function, offset, kind } => {
+                write!(f, "{} (function {}, instruction {})", kind, function, offset)
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+//! Checks that parsed wasm modules are valid before they are compiled.
+
+#![no_std]
+#![warn(missing_docs, missing_debug_implementations, rust_2018_idioms, unreachable_pub)]
+#![forbid(unsafe_code)]
+
+extern crate alloc;
+
+pub use crate::error::{ValidationError, ValidationErrorKind};
+pub use crate::validator::Validator;
+
+mod code;
+mod error;
+mod validator;
+
+pub(crate) type Result<T, E = ValidationError> = core::result::Result<T, E>;
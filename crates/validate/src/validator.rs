@@ -0,0 +1,291 @@
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use hal_wasm::{WasmConstExpr, WasmDataMode, WasmElementMode, WasmExportDescriptor, WasmFeature, WasmFeatures, WasmFunc, WasmGlobalType, WasmImportDescriptor, WasmInstruction, WasmMemory, WasmModule, WasmResizableLimit, WasmTable, WasmValue, WasmValueType};
+
+use crate::code::validate_function;
+use crate::error::{ValidationError, ValidationErrorKind};
+use crate::Result;
+
+// the largest memory a 32-bit address space can hold, in pages of 64KiB
+const MAX_PAGES: u32 = 65536;
+
+/// Checks a decoded module against the validation rules of the specification, so that it can
+/// be compiled and run without further type checks.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+pub struct Validator {}
+
+impl Validator {
+    /// Validates `module`, reporting the first invalid definition or instruction.
+    pub fn validate(module: &WasmModule) -> Result<(), ValidationError> {
+        Self::validate_with_features(module, &WasmFeatures::default())
+    }
+
+    /// Validates `module` like [`Validator::validate`], rejecting definitions which rely on a
+    /// proposal disabled in `features`.
+    pub fn validate_with_features(module: &WasmModule, features: &WasmFeatures) -> Result<(), ValidationError> {
+        let context = Context::new(module).map_err(ValidationError::Module)?;
+        Self::validate_features(module, &context, features).map_err(ValidationError::Module)?;
+        Self::validate_definitions(module, &context).map_err(ValidationError::Module)?;
+
+        let imported = context.functions.len() - module.functions.len();
+        for (idx, (type_idx, body)) in module.functions.iter().zip(module.codes.iter()).enumerate() {
+            let function = (imported + idx) as u32;
+            validate_function(&context, &module.types[*type_idx as usize], body)
+                .map_err(|(offset, kind)| ValidationError::Function { function, offset, kind })?;
+        }
+        Ok(())
+    }
+
+    fn validate_features(module: &WasmModule, context: &Context<'_>, features: &WasmFeatures) -> Result<(), ValidationErrorKind> {
+        if !features.multi_value && module.types.iter().any(|function_type| function_type.returns.len() > 1) {
+            return Err(ValidationErrorKind::FeatureDisabled(WasmFeature::MultiValue));
+        }
+        if !features.multi_memory && context.memories.len() > 1 {
+            return Err(ValidationErrorKind::FeatureDisabled(WasmFeature::MultiMemory));
+        }
+
+        if !features.reference_types {
+            // without reference types, funcref may only be the element type of a single table
+            let externref = |value_type: &WasmValueType| *value_type == WasmValueType::ExternRef;
+            let references = |value_type: &WasmValueType| matches!(value_type, WasmValueType::FuncRef | WasmValueType::ExternRef);
+
+            let uses_references = context.tables.len() > 1
+                || context.tables.iter().any(|table| externref(&table.element_type))
+                || context.types.iter().any(|function_type| function_type.params.iter().chain(function_type.returns.iter()).any(references))
+                || context.globals.iter().any(|global_type| references(&global_type.value_type))
+                || module.codes.iter().any(|body| body.locals.iter().any(|(_, value_type)| references(value_type)));
+            if uses_references {
+                return Err(ValidationErrorKind::FeatureDisabled(WasmFeature::ReferenceTypes));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_definitions(module: &WasmModule, context: &Context<'_>) -> Result<(), ValidationErrorKind> {
+        if module.functions.len() != module.codes.len() {
+            return Err(ValidationErrorKind::InconsistentFunctionCount(module.functions.len() as u32, module.codes.len() as u32));
+        }
+        if let Some(count) = module.data_count {
+            if count as usize != module.data.len() {
+                return Err(ValidationErrorKind::InconsistentDataCount(count, module.data.len() as u32));
+            }
+        }
+
+        for table in context.tables.iter() {
+            validate_limits(&table.limits)?;
+        }
+        for memory in context.memories.iter() {
+            if memory.limits.min > MAX_PAGES || memory.limits.max.is_some_and(|max| max > MAX_PAGES) {
+                return Err(ValidationErrorKind::MemorySizeTooLarge);
+            }
+            validate_limits(&memory.limits)?;
+        }
+
+        for global in module.globals.iter() {
+            let value_type = context.const_expr(&global.init)?;
+            if value_type != global.global_type.value_type {
+                return Err(ValidationErrorKind::TypeMismatch);
+            }
+        }
+
+        let mut names = BTreeSet::new();
+        for export in module.exports.iter() {
+            match export.desc {
+                WasmExportDescriptor::Func(idx) => { context.function(idx)?; }
+                WasmExportDescriptor::Table(idx) => { context.table(idx)?; }
+                WasmExportDescriptor::Memory(idx) => context.memory(idx)?,
+                WasmExportDescriptor::Global(idx) => { context.global(idx)?; }
+            }
+            if !names.insert(export.name.as_ref()) {
+                return Err(ValidationErrorKind::DuplicateExportName(String::from_utf8_lossy(&export.name).into()));
+            }
+        }
+
+        if let Some(idx) = module.start_function {
+            let function = context.function(idx)?;
+            if !function.params.is_empty() || !function.returns.is_empty() {
+                return Err(ValidationErrorKind::InvalidStartFunction(idx));
+            }
+        }
+
+        for element in module.elements.iter() {
+            for init in element.init.iter() {
+                if context.const_expr(init)? != element.element_type {
+                    return Err(ValidationErrorKind::TypeMismatch);
+                }
+            }
+            if let WasmElementMode::Active { table_index, offset } = &element.mode {
+                if context.table(*table_index)?.element_type != element.element_type {
+                    return Err(ValidationErrorKind::TypeMismatch);
+                }
+                if context.const_expr(offset)? != WasmValueType::I32 {
+                    return Err(ValidationErrorKind::TypeMismatch);
+                }
+            }
+        }
+
+        for data in module.data.iter() {
+            if let WasmDataMode::Active { memory_index, offset } = &data.mode {
+                context.memory(*memory_index)?;
+                if context.const_expr(offset)? != WasmValueType::I32 {
+                    return Err(ValidationErrorKind::TypeMismatch);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_limits(limits: &WasmResizableLimit) -> Result<(), ValidationErrorKind> {
+    if limits.max.is_some_and(|max| max < limits.min) {
+        return Err(ValidationErrorKind::InvalidLimits);
+    }
+    Ok(())
+}
+
+/// The index spaces of a module, imports first.
+pub(crate) struct Context<'a> {
+    pub(crate) types: &'a [WasmFunc],
+    pub(crate) functions: Vec<&'a WasmFunc>,
+    pub(crate) tables: Vec<&'a WasmTable>,
+    pub(crate) memories: Vec<&'a WasmMemory>,
+    pub(crate) globals: Vec<&'a WasmGlobalType>,
+    pub(crate) imported_globals: usize,
+    pub(crate) elements: Vec<&'a WasmValueType>,
+    pub(crate) data_count: Option<u32>,
+    // functions which may be referenced by ref.func within function bodies
+    pub(crate) references: BTreeSet<u32>,
+}
+
+impl<'a> Context<'a> {
+    fn new(module: &'a WasmModule) -> Result<Self, ValidationErrorKind> {
+        let function_type = |idx: u32| module.types.get(idx as usize).ok_or(ValidationErrorKind::UnknownType(idx));
+
+        let mut context = Self {
+            types: &module.types,
+            functions: Vec::with_capacity(module.imports.len() + module.functions.len()),
+            tables: Vec::new(),
+            memories: Vec::new(),
+            globals: Vec::new(),
+            imported_globals: 0,
+            elements: module.elements.iter().map(|element| &element.element_type).collect(),
+            data_count: module.data_count,
+            references: BTreeSet::new(),
+        };
+
+        for import in module.imports.iter() {
+            match &import.desc {
+                WasmImportDescriptor::Func(idx) => context.functions.push(function_type(*idx)?),
+                WasmImportDescriptor::Table(table) => context.tables.push(table),
+                WasmImportDescriptor::Memory(memory) => context.memories.push(memory),
+                WasmImportDescriptor::Global(global_type) => context.globals.push(global_type),
+            }
+        }
+        context.imported_globals = context.globals.len();
+
+        for idx in module.functions.iter() {
+            context.functions.push(function_type(*idx)?);
+        }
+        context.tables.extend(module.tables.iter());
+        context.memories.extend(module.memories.iter());
+        context.globals.extend(module.globals.iter().map(|global| &global.global_type));
+
+        let exported = module.exports.iter().filter_map(|export| match export.desc {
+            WasmExportDescriptor::Func(idx) => Some(idx),
+            _ => None,
+        });
+        let initialized = module.globals.iter().map(|global| &global.init)
+            .chain(module.elements.iter().flat_map(|element| element.init.iter()))
+            .filter_map(|expr| match expr {
+                WasmConstExpr::RefFunc(idx) => Some(*idx),
+                _ => None,
+            });
+        context.references = exported.chain(initialized).collect();
+
+        Ok(context)
+    }
+
+    pub(crate) fn function_type(&self, idx: u32) -> Result<&'a WasmFunc, ValidationErrorKind> {
+        self.types.get(idx as usize).ok_or(ValidationErrorKind::UnknownType(idx))
+    }
+
+    pub(crate) fn function(&self, idx: u32) -> Result<&'a WasmFunc, ValidationErrorKind> {
+        self.functions.get(idx as usize).copied().ok_or(ValidationErrorKind::UnknownFunction(idx))
+    }
+
+    pub(crate) fn table(&self, idx: u32) -> Result<&'a WasmTable, ValidationErrorKind> {
+        self.tables.get(idx as usize).copied().ok_or(ValidationErrorKind::UnknownTable(idx))
+    }
+
+    pub(crate) fn memory(&self, idx: u32) -> Result<(), ValidationErrorKind> {
+        if (idx as usize) < self.memories.len() {
+            Ok(())
+        } else {
+            Err(ValidationErrorKind::UnknownMemory(idx))
+        }
+    }
+
+    pub(crate) fn global(&self, idx: u32) -> Result<&'a WasmGlobalType, ValidationErrorKind> {
+        self.globals.get(idx as usize).copied().ok_or(ValidationErrorKind::UnknownGlobal(idx))
+    }
+
+    pub(crate) fn element(&self, idx: u32) -> Result<&'a WasmValueType, ValidationErrorKind> {
+        self.elements.get(idx as usize).copied().ok_or(ValidationErrorKind::UnknownElementSegment(idx))
+    }
+
+    pub(crate) fn data(&self, idx: u32) -> Result<(), ValidationErrorKind> {
+        let count = self.data_count.ok_or(ValidationErrorKind::DataCountRequired)?;
+        if idx < count {
+            Ok(())
+        } else {
+            Err(ValidationErrorKind::UnknownDataSegment(idx))
+        }
+    }
+
+    // constant expressions only see imported globals, and only immutable ones are constant
+    fn const_expr(&self, expr: &WasmConstExpr) -> Result<WasmValueType, ValidationErrorKind> {
+        match expr {
+            WasmConstExpr::Const(value) => Ok(match value {
+                WasmValue::I32(_) => WasmValueType::I32,
+                WasmValue::I64(_) => WasmValueType::I64,
+                WasmValue::F32(_) => WasmValueType::F32,
+                WasmValue::F64(_) => WasmValueType::F64,
+            }),
+            WasmConstExpr::GlobalGet(idx) => {
+                let global = self.globals[..self.imported_globals].get(*idx as usize).ok_or(ValidationErrorKind::UnknownGlobal(*idx))?;
+                if global.mutable {
+                    return Err(ValidationErrorKind::ConstantExpressionRequired);
+                }
+                Ok(global.value_type.clone())
+            }
+            WasmConstExpr::RefFunc(idx) => {
+                self.function(*idx)?;
+                Ok(WasmValueType::FuncRef)
+            }
+            WasmConstExpr::RefNull(value_type) => Ok(value_type.clone()),
+            // the parser keeps single instructions only, but a sequence must still produce exactly one value
+            WasmConstExpr::Instructions(instructions) => {
+                let mut operands = Vec::with_capacity(instructions.len());
+                for instruction in instructions.iter() {
+                    let expr = match instruction {
+                        WasmInstruction::ConstI32(value) => WasmConstExpr::Const(WasmValue::I32(*value)),
+                        WasmInstruction::ConstI64(value) => WasmConstExpr::Const(WasmValue::I64(*value)),
+                        WasmInstruction::ConstF32(value) => WasmConstExpr::Const(WasmValue::F32(*value)),
+                        WasmInstruction::ConstF64(value) => WasmConstExpr::Const(WasmValue::F64(*value)),
+                        WasmInstruction::GlobalGet(idx) => WasmConstExpr::GlobalGet(*idx),
+                        WasmInstruction::RefFunc(idx) => WasmConstExpr::RefFunc(*idx),
+                        WasmInstruction::RefNull(value_type) => WasmConstExpr::RefNull(value_type.clone()),
+                        _ => return Err(ValidationErrorKind::ConstantExpressionRequired),
+                    };
+                    operands.push(self.const_expr(&expr)?);
+                }
+                match operands.as_slice() {
+                    [value_type] => Ok(value_type.clone()),
+                    _ => Err(ValidationErrorKind::TypeMismatch),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+extern crate alloc;
+extern crate core;
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+
+    use hal_validate::{ValidationError, ValidationErrorKind, Validator};
+    use hal_wasm::{WasmFeature, WasmFeatures, WasmParser};
+
+    #[test]
+    fn valid_module() {
+        let result = validate(r#"(module
+              (type $binary (func (param i32 i32) (result i32)))
+              (import "env" "g" (global $g i32))
+              (memory 1)
+              (table 1 funcref)
+              (global $h (mut i64) (i64.const 0))
+              (elem (global.get $g) $add)
+              (func $add (type $binary) (i32.add (local.get 0) (local.get 1)))
+              (func (export "run") (param i32) (result i32) (local i64)
+                (block $done (result i32)
+                  (loop $again
+                    (br_if $again (i32.eqz (local.get 0)))
+                  )
+                  (if (result i32) (local.get 0)
+                    (then (call_indirect (type $binary) (local.get 0) (i32.const 1) (i32.const 0)))
+                    (else (br $done (i32.const 2)))
+                  )
+                )
+                (global.set $h (i64.extend_i32_u (i32.load (i32.const 0))))
+              )
+            )"#);
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn unreachable_operands_match_any_type() {
+        let result = validate(r#"(module
+              (func (result i32) (unreachable) (i64.add) (drop) (i32.const 1))
+              (func (result f32) (block (result f32) (br 0 (f32.const 1)) (f32.neg)))
+            )"#);
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn type_mismatch() {
+        let result = validate(r#"(module
+              (func (result i32) (i32.const 1))
+              (func (result i32) (i64.const 1) (i32.const 2) (i32.add))
+            )"#);
+        assert_eq!(result, Err(ValidationError::Function { function: 1, offset: 2, kind: ValidationErrorKind::TypeMismatch }));
+
+        let result = validate(r#"(module (func (result i32) (block (i32.const 1))))"#);
+        assert_eq!(result, Err(ValidationError::Function { function: 0, offset: 2, kind: ValidationErrorKind::TypeMismatch }));
+    }
+
+    #[test]
+    fn function_index_counts_imports() {
+        let result = validate(r#"(module
+              (import "env" "f" (func))
+              (func (local.get 0) (drop))
+            )"#);
+        assert_eq!(result, Err(ValidationError::Function { function: 1, offset: 0, kind: ValidationErrorKind::UnknownLocal(0) }));
+    }
+
+    #[test]
+    fn unknown_indices() {
+        let kind = |wat| validate(wat).err().map(|e| e.kind().clone());
+
+        assert_eq!(kind(r#"(module (func (br 1)))"#), Some(ValidationErrorKind::UnknownLabel(1)));
+        assert_eq!(kind(r#"(module (func (call 3)))"#), Some(ValidationErrorKind::UnknownFunction(3)));
+        assert_eq!(kind(r#"(module (func (drop (global.get 0))))"#), Some(ValidationErrorKind::UnknownGlobal(0)));
+        assert_eq!(kind(r#"(module (func (drop (i32.load (i32.const 0)))))"#), Some(ValidationErrorKind::UnknownMemory(0)));
+        assert_eq!(kind(r#"(module (func (drop (table.size 0))))"#), Some(ValidationErrorKind::UnknownTable(0)));
+        assert_eq!(kind(r#"(module (func (elem.drop 0)))"#), Some(ValidationErrorKind::UnknownElementSegment(0)));
+        assert_eq!(kind(r#"(module (memory 1) (func (data.drop 0)))"#), Some(ValidationErrorKind::UnknownDataSegment(0)));
+    }
+
+    #[test]
+    fn globals() {
+        let kind = |wat| validate(wat).err().map(|e| e.kind().clone());
+
+        assert_eq!(kind(r#"(module (global i32 (i32.const 0)) (func (global.set 0 (i32.const 1))))"#), Some(ValidationErrorKind::ImmutableGlobal(0)));
+        assert_eq!(kind(r#"(module (global i32 (i64.const 0)))"#), Some(ValidationErrorKind::TypeMismatch));
+        assert_eq!(kind(r#"(module (global i32 (i32.const 0)) (global i32 (global.get 0)))"#), Some(ValidationErrorKind::UnknownGlobal(0)));
+        assert_eq!(kind(r#"(module (import "env" "g" (global (mut i32))) (global i32 (global.get 0)))"#), Some(ValidationErrorKind::ConstantExpressionRequired));
+    }
+
+    #[test]
+    fn constant_expressions() {
+        let kind = |wat| validate(wat).err().map(|e| e.kind().clone());
+
+        assert_eq!(kind(r#"(module (global i32 (i32.const 0) (nop)))"#), Some(ValidationErrorKind::ConstantExpressionRequired));
+        assert_eq!(kind(r#"(module (global i32 (i32.add (i32.const 0) (i32.const 1))))"#), Some(ValidationErrorKind::ConstantExpressionRequired));
+        assert_eq!(kind(r#"(module (global i32 (i32.const 0) (i32.const 0)))"#), Some(ValidationErrorKind::TypeMismatch));
+        assert_eq!(kind(r#"(module (memory 1) (data (offset) ""))"#), Some(ValidationErrorKind::TypeMismatch));
+    }
+
+    #[test]
+    fn select_arity() {
+        let result = validate(r#"(module (func (result i32) (select (result i32 i32) (i32.const 1) (i32.const 2) (i32.const 0))))"#);
+        assert_eq!(result.err().map(|e| e.kind().clone()), Some(ValidationErrorKind::InvalidResultArity));
+    }
+
+    #[test]
+    fn module_definitions() {
+        let kind = |wat| validate(wat).err().map(|e| e.kind().clone());
+
+        assert_eq!(kind(r#"(module (memory 2 1))"#), Some(ValidationErrorKind::InvalidLimits));
+        assert_eq!(kind(r#"(module (memory 65537))"#), Some(ValidationErrorKind::MemorySizeTooLarge));
+        assert_eq!(kind(r#"(module (table 1 externref) (elem (i32.const 0) func))"#), Some(ValidationErrorKind::TypeMismatch));
+        assert_eq!(kind(r#"(module (memory 1) (data (i64.const 0)))"#), Some(ValidationErrorKind::TypeMismatch));
+        assert_eq!(
+            kind(r#"(module (func (export "a")) (func (export "a")))"#),
+            Some(ValidationErrorKind::DuplicateExportName("a".into()))
+        );
+    }
+
+    #[test]
+    fn memory_alignment() {
+        let result = validate(r#"(module (memory 1) (func (drop (i32.load align=8 (i32.const 0)))))"#);
+        assert_eq!(result.err().map(|e| e.kind().clone()), Some(ValidationErrorKind::InvalidAlignment(3)));
+    }
+
+    #[test]
+    fn function_references() {
+        let result = validate(r#"(module (func $f) (func (drop (ref.func $f))))"#);
+        assert_eq!(result.err().map(|e| e.kind().clone()), Some(ValidationErrorKind::UndeclaredFunctionReference(0)));
+
+        let result = validate(r#"(module (func $f) (elem declare func $f) (func (drop (ref.func $f))))"#);
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn disabled_features() {
+        let kind = |wat, features: WasmFeatures| {
+            let wasm: Box<[u8]> = hal_wat::WatParser::parse_str(wat).unwrap();
+            let module = WasmParser::parse_with_features(&wasm, &features).unwrap();
+            Validator::validate_with_features(&module, &features).err().map(|e| e.kind().clone())
+        };
+
+        let wat = r#"(module (func (result i32 i32) (i32.const 1) (i32.const 2)))"#;
+        assert_eq!(kind(wat, WasmFeatures::default()), None);
+        assert_eq!(kind(wat, WasmFeatures::mvp()), Some(ValidationErrorKind::FeatureDisabled(WasmFeature::MultiValue)));
+
+        let wat = r#"(module (table 1 funcref) (table 1 funcref))"#;
+        assert_eq!(kind(wat, WasmFeatures::mvp()), Some(ValidationErrorKind::FeatureDisabled(WasmFeature::ReferenceTypes)));
+        assert_eq!(kind(r#"(module (func (param externref)))"#, WasmFeatures::mvp()), Some(ValidationErrorKind::FeatureDisabled(WasmFeature::ReferenceTypes)));
+
+        let wat = r#"(module (memory 1) (memory 1))"#;
+        assert_eq!(kind(wat, WasmFeatures::default()), Some(ValidationErrorKind::FeatureDisabled(WasmFeature::MultiMemory)));
+        assert_eq!(kind(wat, WasmFeatures { multi_memory: true, ..WasmFeatures::default() }), None);
+    }
+
+    #[test]
+    fn messages() {
+        let error = ValidationError::Function { function: 2, offset: 5, kind: ValidationErrorKind::UnknownLocal(1) };
+        assert_eq!(error.to_string(), "unknown local 1 (function 2, instruction 5)");
+        assert_eq!(ValidationError::Module(ValidationErrorKind::FeatureDisabled(WasmFeature::MultiMemory)).to_string(), "multiple memories");
+        assert_eq!(ValidationErrorKind::FeatureDisabled(WasmFeature::MultiValue).to_string(), "multi-value support is not enabled");
+    }
+
+    fn validate(content: &str) -> Result<(), ValidationError> {
+        let wasm: Box<[u8]> = hal_wat::WatParser::parse_str(content).unwrap();
+        Validator::validate(&WasmParser::parse(&wasm).unwrap())
+    }
+}
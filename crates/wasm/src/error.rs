@@ -1,6 +1,7 @@
 use hal_core::leb128::Leb128Error;
 use hal_core::reader::Error;
 
+use crate::features::WasmFeature;
 use crate::module::Opcode;
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
@@ -12,9 +13,23 @@ pub enum WasmParseError {
     InvalidLEB128Encoding,
     InvalidSectionCode(u8),
     OutOfBounds,
-    // InvalidUtf8String,
+    /// A name which is not encoded as UTF-8.
+    InvalidUtf8String,
     InvalidValueType(u8),
-    // InvalidElementType(u8),
+    /// A block type which is neither a value type nor a type index.
+    InvalidBlockType(i64),
+    /// Unknown flags of a data segment.
+    InvalidDataMode(u32),
+    /// A global which is neither mutable nor immutable.
+    InvalidMutability(u8),
+    /// An element type which is not a reference type.
+    InvalidElementType(u8),
+    /// Unknown flags of an element segment.
+    InvalidElementMode(u32),
+    /// Memory access flags with an alignment exponent of 32 or more.
+    InvalidMemoryFlags(u32),
+    /// A function declaring more locals than can be addressed.
+    TooManyLocals,
     // InvalidFunctionType(u8),
     // InvalidTableType(u8),
     // InvalidMemoryType(u8),
@@ -22,10 +37,15 @@ pub enum WasmParseError {
     InvalidImportDescriptor(u8),
     InvalidExportDescriptor(u8),
     InvalidOpcode(u8),
+    /// An unknown opcode after a prefix byte.
+    InvalidPrefixedOpcode(u8, u32),
     UnsupportedOpcode(Opcode),
     // InvalidIndex,
     // UnknownSection(u8),
-    // UnsupportedFeature(&'static str),
+    /// An instruction or section of a proposal which is not enabled.
+    FeatureDisabled(WasmFeature),
+    /// An instruction or section of a proposal which is not implemented.
+    UnsupportedFeature(WasmFeature),
 }
 
 impl From<hal_core::reader::Error> for WasmParseError {
@@ -48,9 +68,15 @@ impl core::fmt::Display for WasmParseError {
             WasmParseError::InvalidLEB128Encoding => write!(f, "Invalid encoding"),
             WasmParseError::InvalidSectionCode(code) => write!(f, "Invalid section code {}", code),
             // DecodingError::InvalidSectionId(id) => write!(f, "Invalid section ID: {}", id),
-            // DecodingError::InvalidUtf8String => write!(f, "Invalid UTF-8 string"),
+            WasmParseError::InvalidUtf8String => write!(f, "malformed UTF-8 encoding"),
             WasmParseError::InvalidValueType(value_type) => write!(f, "Invalid value types: {}", value_type),
-            // DecodingError::InvalidElementType(element_type) => write!(f, "Invalid element types: {}", element_type),
+            WasmParseError::InvalidBlockType(block_type) => write!(f, "Invalid block type: {}", block_type),
+            WasmParseError::InvalidDataMode(flags) => write!(f, "Invalid data segment mode: {}", flags),
+            WasmParseError::InvalidMutability(mutability) => write!(f, "Invalid mutability: {}", mutability),
+            WasmParseError::InvalidElementType(element_type) => write!(f, "Invalid element type: {}", element_type),
+            WasmParseError::InvalidElementMode(flags) => write!(f, "Invalid element segment mode: {}", flags),
+            WasmParseError::InvalidMemoryFlags(flags) => write!(f, "Invalid memory flags: {}", flags),
+            WasmParseError::TooManyLocals => write!(f, "Too many locals"),
             // DecodingError::InvalidFunctionType(function_type) => write!(f, "Invalid function types: {}", function_type),
             // DecodingError::InvalidTableType(table_type) => write!(f, "Invalid table types: {}", table_type),
             // DecodingError::InvalidMemoryType(memory_type) => write!(f, "Invalid memory types: {}", memory_type),
@@ -58,10 +84,12 @@ impl core::fmt::Display for WasmParseError {
             WasmParseError::InvalidImportDescriptor(descriptor) => write!(f, "Invalid import descriptor: {}", descriptor),
             WasmParseError::InvalidExportDescriptor(descriptor) => write!(f, "Invalid export descriptor: {}", descriptor),
             WasmParseError::InvalidOpcode(opcode) => write!(f, "Invalid opcode: {}", opcode),
+            WasmParseError::InvalidPrefixedOpcode(prefix, opcode) => write!(f, "Invalid opcode: {} {}", prefix, opcode),
             WasmParseError::UnsupportedOpcode(opcode) => write!(f, "Unsupported opcode: {:?}", opcode),
             // DecodingError::InvalidIndex => write!(f, "Invalid index"),
             // DecodingError::UnknownSection(section_id) => write!(f, "Unknown section ID: {}", section_id),
-            // DecodingError::UnsupportedFeature(feature) => write!(f, "Unsupported feature: {}", feature),
+            WasmParseError::FeatureDisabled(feature) => write!(f, "Disabled feature: {}", feature),
+            WasmParseError::UnsupportedFeature(feature) => write!(f, "Unsupported feature: {}", feature),
             // DecodingError::Custom(msg) => write!(f, "{}", msg),
         }
     }
@@ -0,0 +1,182 @@
+use core::fmt::{Display, Formatter};
+
+use crate::error::WasmParseError;
+use crate::module::WasmInstruction;
+use crate::Result;
+
+/// A post-MVP proposal which can be switched on or off with [`WasmFeatures`].
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum WasmFeature {
+    /// Bulk memory and table instructions and passive segments.
+    BulkMemory,
+    /// Arithmetic in constant expressions.
+    ExtendedConst,
+    /// Exception handling.
+    Exceptions,
+    /// Memories indexed with 64 bit addresses.
+    Memory64,
+    /// More than one memory per module.
+    MultiMemory,
+    /// Functions and blocks returning more than one value.
+    MultiValue,
+    /// `funcref` and `externref` values, typed select and multiple tables.
+    ReferenceTypes,
+    /// 128 bit vector instructions.
+    Simd,
+    /// `return_call` and `return_call_indirect`.
+    TailCall,
+    /// Shared memories and atomic instructions.
+    Threads,
+}
+
+impl Display for WasmFeature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WasmFeature::BulkMemory => write!(f, "bulk memory"),
+            WasmFeature::ExtendedConst => write!(f, "extended constant expressions"),
+            WasmFeature::Exceptions => write!(f, "exceptions"),
+            WasmFeature::Memory64 => write!(f, "memory64"),
+            WasmFeature::MultiMemory => write!(f, "multi-memory"),
+            WasmFeature::MultiValue => write!(f, "multi-value"),
+            WasmFeature::ReferenceTypes => write!(f, "reference types"),
+            WasmFeature::Simd => write!(f, "SIMD"),
+            WasmFeature::TailCall => write!(f, "tail calls"),
+            WasmFeature::Threads => write!(f, "threads"),
+        }
+    }
+}
+
+/// Selects which post-MVP proposals a module may use. The parser, the validator and the compiler
+/// reject a module relying on a disabled proposal, naming the [`WasmFeature`] it needs.
+///
+/// The default enables the proposals merged into WebAssembly 2.0 which are executed by the
+/// runtime. Proposals which are not implemented yet can be enabled, but modules using them are
+/// still rejected as unsupported.
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct WasmFeatures {
+    /// Passive segments and the `memory.copy`, `memory.fill`, `memory.init`, `data.drop`,
+    /// `table.copy`, `table.init` and `elem.drop` instructions.
+    pub bulk_memory: bool,
+
+    /// Arithmetic in constant expressions.
+    pub extended_const: bool,
+
+    /// Exception handling with `try`, `catch` and `throw`.
+    pub exceptions: bool,
+
+    /// Memories indexed with 64-bit addresses.
+    pub memory64: bool,
+
+    /// More than one memory per module.
+    pub multi_memory: bool,
+
+    /// Functions and blocks with several results, and blocks with parameters.
+    pub multi_value: bool,
+
+    /// `externref`, multiple tables, typed `select` and the `ref.*` and `table.*` instructions.
+    pub reference_types: bool,
+
+    /// 128-bit vector instructions.
+    pub simd: bool,
+
+    /// `return_call` and `return_call_indirect`.
+    pub tail_call: bool,
+
+    /// Shared memories and atomic instructions.
+    pub threads: bool,
+}
+
+impl Default for WasmFeatures {
+    fn default() -> Self {
+        Self {
+            bulk_memory: true,
+            multi_value: true,
+            reference_types: true,
+            ..Self::mvp()
+        }
+    }
+}
+
+impl WasmFeatures {
+    /// Disables every proposal, accepting only modules of the original WebAssembly 1.0 release.
+    pub fn mvp() -> Self {
+        Self {
+            bulk_memory: false,
+            extended_const: false,
+            exceptions: false,
+            memory64: false,
+            multi_memory: false,
+            multi_value: false,
+            reference_types: false,
+            simd: false,
+            tail_call: false,
+            threads: false,
+        }
+    }
+
+    /// Returns whether modules may use `feature`.
+    pub fn is_enabled(&self, feature: WasmFeature) -> bool {
+        match feature {
+            WasmFeature::BulkMemory => self.bulk_memory,
+            WasmFeature::ExtendedConst => self.extended_const,
+            WasmFeature::Exceptions => self.exceptions,
+            WasmFeature::Memory64 => self.memory64,
+            WasmFeature::MultiMemory => self.multi_memory,
+            WasmFeature::MultiValue => self.multi_value,
+            WasmFeature::ReferenceTypes => self.reference_types,
+            WasmFeature::Simd => self.simd,
+            WasmFeature::TailCall => self.tail_call,
+            WasmFeature::Threads => self.threads,
+        }
+    }
+
+    /// Checks that the proposal introducing `instruction` is enabled, returning it otherwise.
+    pub fn check(&self, instruction: &WasmInstruction) -> Result<(), WasmFeature> {
+        match required_feature(instruction) {
+            Some(feature) if !self.is_enabled(feature) => Err(feature),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn require(&self, feature: WasmFeature) -> Result<()> {
+        if self.is_enabled(feature) {
+            Ok(())
+        } else {
+            Err(WasmParseError::FeatureDisabled(feature))
+        }
+    }
+
+    // rejects a construct of a proposal the parser cannot decode, whether enabled or not
+    pub(crate) fn unsupported(&self, feature: WasmFeature) -> WasmParseError {
+        if self.is_enabled(feature) {
+            WasmParseError::UnsupportedFeature(feature)
+        } else {
+            WasmParseError::FeatureDisabled(feature)
+        }
+    }
+}
+
+fn required_feature(instruction: &WasmInstruction) -> Option<WasmFeature> {
+    match instruction {
+        WasmInstruction::BlockWithFuncType(..) | WasmInstruction::LoopWithFuncType(..) | WasmInstruction::IfWithFuncType(..) => {
+            Some(WasmFeature::MultiValue)
+        }
+
+        WasmInstruction::MemoryInit(..) | WasmInstruction::DataDrop(..) | WasmInstruction::MemoryCopy(..) |
+        WasmInstruction::MemoryFill(..) | WasmInstruction::TableInit(..) | WasmInstruction::ElemDrop(..) |
+        WasmInstruction::TableCopy { .. } => Some(WasmFeature::BulkMemory),
+
+        WasmInstruction::SelectRef | WasmInstruction::SelectWithArity(..) | WasmInstruction::RefNull(..) | WasmInstruction::RefIsNull |
+        WasmInstruction::RefFunc(..) | WasmInstruction::TableGet(..) | WasmInstruction::TableSet(..) |
+        WasmInstruction::TableSize(..) | WasmInstruction::TableGrow(..) | WasmInstruction::TableFill(..) => {
+            Some(WasmFeature::ReferenceTypes)
+        }
+        WasmInstruction::CallIndirect(_, table) if *table != 0 => Some(WasmFeature::ReferenceTypes),
+        WasmInstruction::MemorySize(memory) | WasmInstruction::MemoryGrow(memory) if *memory != 0 => Some(WasmFeature::MultiMemory),
+
+        WasmInstruction::ReturnCall(..) | WasmInstruction::ReturnCallIndirect(..) => Some(WasmFeature::TailCall),
+        _ => None,
+    }
+}
@@ -5,10 +5,12 @@
 extern crate alloc;
 
 pub use crate::error::WasmParseError;
+pub use crate::features::{WasmFeature, WasmFeatures};
 pub use crate::parse::WasmParser;
 pub use crate::module::*;
 
 mod error;
+mod features;
 mod module;
 mod parse;
 
@@ -1,5 +1,7 @@
 use hal_core::module::Instruction;
 
+use crate::module::WasmValueType;
+
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(Clone, PartialEq)]
 pub enum WasmInstruction {
@@ -16,7 +18,8 @@ pub enum WasmInstruction {
 
     Block(u32),
     BlockWithFuncType(u32, u32),
-    BlockWithType(u32, u32),
+    /// `block` with a single result of the given type, the second operand is the position of its `end`.
+    BlockWithType(WasmValueType, u32),
 
     Br(u32),
     BrIf(u32),
@@ -40,10 +43,35 @@ pub enum WasmInstruction {
     ConstI32(i32),
     ConstI64(i64),
 
+    /// `f32.convert_i32_s`
+    ConvertI32SF32,
+    /// `f64.convert_i32_s`
+    ConvertI32SF64,
+    /// `f32.convert_i32_u`
+    ConvertI32UF32,
+    /// `f64.convert_i32_u`
+    ConvertI32UF64,
+    /// `f32.convert_i64_s`
+    ConvertI64SF32,
+    /// `f64.convert_i64_s`
+    ConvertI64SF64,
+    /// `f32.convert_i64_u`
+    ConvertI64UF32,
+    /// `f64.convert_i64_u`
+    ConvertI64UF64,
+
     CtzI32,
     CtzI64,
 
+    /// `data.drop` of the data segment at the operand.
+    DataDrop(u32),
+
     DemoteF64F32,
+
+    /// `f32.div`
+    DivF32,
+    /// `f64.div`
+    DivF64,
     DivSI32,
     DivUI32,
     DivSI64,
@@ -54,6 +82,9 @@ pub enum WasmInstruction {
     Drop64,
     DropRef,
 
+    /// `elem.drop` of the element segment at the operand.
+    ElemDrop(u32),
+
     Else(u32),
     End,
     EndBlockFrame,
@@ -74,16 +105,6 @@ pub enum WasmInstruction {
     Extend8SI32,
     Extend8SI64,
 
-    ExtendI32SF32,
-    ExtendI32SF64,
-    ExtendI32UF32,
-    ExtendI32UF64,
-
-    ExtendI64SF32,
-    ExtendI64SF64,
-    ExtendI64UF32,
-    ExtendI64UF64,
-
     FloorF32,
     FloorF64,
 
@@ -107,6 +128,13 @@ pub enum WasmInstruction {
     GtUI32,
     GtUI64,
 
+    /// `if` without results, the operand is the position of its `else` or `end`.
+    If(u32),
+    /// `if` typed by the function type at the first operand, the second is the position of its `else` or `end`.
+    IfWithFuncType(u32, u32),
+    /// `if` with a single result of the given type, the second operand is the position of its `else` or `end`.
+    IfWithType(WasmValueType, u32),
+
     LeF32,
     LeF64,
     LeSI32,
@@ -114,6 +142,105 @@ pub enum WasmInstruction {
     LeUI32,
     LeUI64,
 
+    /// `f32.load`
+    LoadF32 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `f64.load`
+    LoadF64 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i32.load`
+    LoadI32 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i64.load`
+    LoadI64 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i32.load16_s`
+    Load16SI32 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i64.load16_s`
+    Load16SI64 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i32.load16_u`
+    Load16UI32 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i64.load16_u`
+    Load16UI64 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i64.load32_s`
+    Load32SI64 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i64.load32_u`
+    Load32UI64 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i32.load8_s`
+    Load8SI32 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i64.load8_s`
+    Load8SI64 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i32.load8_u`
+    Load8UI32 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+    /// `i64.load8_u`
+    Load8UI64 {
+        /// The alignment exponent of the memory argument.
+        flags: u32,
+        /// The static offset added to the address operand.
+        offset: u32,
+    },
+
     LocalGet128(u32),
     LocalGet32(u32),
     LocalGet64(u32),
@@ -131,7 +258,8 @@ pub enum WasmInstruction {
 
     Loop(u32),
     LoopWithFuncType(u32, u32),
-    LoopWithType(u32, u32),
+    /// `loop` with a single result of the given type, the second operand is the position of its `end`.
+    LoopWithType(WasmValueType, u32),
 
     LtF32,
     LtF64,
@@ -146,6 +274,16 @@ pub enum WasmInstruction {
     MemoryInit(u32, u32),
     MemorySize(u32),
 
+    /// `f32.max`
+    MaxF32,
+    /// `f64.max`
+    MaxF64,
+
+    /// `f32.min`
+    MinF32,
+    /// `f64.min`
+    MinF64,
+
     MulF32,
     MulF64,
     MulI32,
@@ -161,6 +299,11 @@ pub enum WasmInstruction {
     NegF32,
     NegF64,
 
+    /// `f32.nearest`
+    NearestF32,
+    /// `f64.nearest`
+    NearestF64,
+
     OrI32,
     OrI64,
 
@@ -171,7 +314,8 @@ pub enum WasmInstruction {
 
     RefFunc(u32),
     RefIsNull,
-    RefNull(u32),
+    /// `ref.null` of the given reference type.
+    RefNull(WasmValueType),
 
     ReinterpretF32I32,
     ReinterpretF64I64,
@@ -197,6 +341,8 @@ pub enum WasmInstruction {
     Select32,
     Select64,
     SelectRef,
+    /// A typed select with other than one result type, which the validator rejects.
+    SelectWithArity(u32),
 
     ShlI32,
     ShlI64,
@@ -232,6 +378,11 @@ pub enum WasmInstruction {
     TableSet(u32),
     TableSize(u32),
 
+    /// `f32.trunc`
+    TruncF32,
+    /// `f64.trunc`
+    TruncF64,
+
     TruncF32SI32,
     TruncF32SI64,
     TruncF32UI32,
@@ -258,9 +409,12 @@ pub enum WasmInstruction {
     XorI64,
 }
 
-impl From<WasmInstruction> for Instruction {
-    fn from(wasm_inst: WasmInstruction) -> Self {
-        match wasm_inst {
+// instructions the validator rejects have no lowering, they are returned as the error
+impl TryFrom<WasmInstruction> for Instruction {
+    type Error = WasmInstruction;
+
+    fn try_from(wasm_inst: WasmInstruction) -> Result<Self, Self::Error> {
+        Ok(match wasm_inst {
             WasmInstruction::AbsF32 => Instruction::AbsF32,
             WasmInstruction::AbsF64 => Instruction::AbsF64,
             WasmInstruction::AddF32 => Instruction::AddF32,
@@ -271,7 +425,7 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::AndI64 => Instruction::AndI64,
             WasmInstruction::Block(a) => Instruction::Block(a),
             WasmInstruction::BlockWithFuncType(a, b) => Instruction::BlockWithFuncType(a, b),
-            WasmInstruction::BlockWithType(a, b) => Instruction::BlockWithType(a, b),
+            WasmInstruction::BlockWithType(a, b) => Instruction::BlockWithType(a.into(), b),
             WasmInstruction::Br(a) => Instruction::Br(a),
             WasmInstruction::BrIf(a) => Instruction::BrIf(a),
             WasmInstruction::BrLabel(a) => Instruction::BrLabel(a),
@@ -290,7 +444,10 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::CopysignF64 => Instruction::CopysignF64,
             WasmInstruction::CtzI32 => Instruction::CtzI32,
             WasmInstruction::CtzI64 => Instruction::CtzI64,
+            WasmInstruction::DataDrop(a) => Instruction::DataDrop(a),
             WasmInstruction::DemoteF64F32 => Instruction::DemoteF64F32,
+            WasmInstruction::DivF32 => Instruction::DivF32,
+            WasmInstruction::DivF64 => Instruction::DivF64,
             WasmInstruction::DivSI32 => Instruction::DivSI32,
             WasmInstruction::DivUI32 => Instruction::DivUI32,
             WasmInstruction::DivSI64 => Instruction::DivSI64,
@@ -299,6 +456,7 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::Drop => Instruction::Drop32,
             WasmInstruction::Drop64 => Instruction::Drop64,
             WasmInstruction::DropRef => Instruction::DropRef,
+            WasmInstruction::ElemDrop(a) => Instruction::ElemDrop(a),
             WasmInstruction::Else(a) => Instruction::Else(a),
             WasmInstruction::End => Instruction::End,
             WasmInstruction::EndBlockFrame => Instruction::EndBlockFrame,
@@ -313,16 +471,16 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::Extend32SI64 => Instruction::Extend32SI64,
             WasmInstruction::Extend8SI32 => Instruction::Extend8SI32,
             WasmInstruction::Extend8SI64 => Instruction::Extend8SI64,
-            WasmInstruction::ExtendI32SF32 => Instruction::ExtendI32SF32,
-            WasmInstruction::ExtendI32SF64 => Instruction::ExtendI32SF64,
+            WasmInstruction::ConvertI32SF32 => Instruction::ConvertI32SF32,
+            WasmInstruction::ConvertI32SF64 => Instruction::ConvertI32SF64,
             WasmInstruction::ExtendI32SI64 => Instruction::ExtendI32SI64,
             WasmInstruction::ExtendI32UI64 => Instruction::ExtendI32UI64,
-            WasmInstruction::ExtendI32UF32 => Instruction::ExtendI32UF32,
-            WasmInstruction::ExtendI32UF64 => Instruction::ExtendI32UF64,
-            WasmInstruction::ExtendI64SF32 => Instruction::ExtendI64SF32,
-            WasmInstruction::ExtendI64SF64 => Instruction::ExtendI64SF64,
-            WasmInstruction::ExtendI64UF32 => Instruction::ExtendI64UF32,
-            WasmInstruction::ExtendI64UF64 => Instruction::ExtendI64UF64,
+            WasmInstruction::ConvertI32UF32 => Instruction::ConvertI32UF32,
+            WasmInstruction::ConvertI32UF64 => Instruction::ConvertI32UF64,
+            WasmInstruction::ConvertI64SF32 => Instruction::ConvertI64SF32,
+            WasmInstruction::ConvertI64SF64 => Instruction::ConvertI64SF64,
+            WasmInstruction::ConvertI64UF32 => Instruction::ConvertI64UF32,
+            WasmInstruction::ConvertI64UF64 => Instruction::ConvertI64UF64,
             WasmInstruction::FloorF32 => Instruction::FloorF32,
             WasmInstruction::FloorF64 => Instruction::FloorF64,
             WasmInstruction::GeF32 => Instruction::GeF32,
@@ -342,12 +500,29 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::GtSI64 => Instruction::GtSI64,
             WasmInstruction::GtUI32 => Instruction::GtUI32,
             WasmInstruction::GtUI64 => Instruction::GtUI64,
+            WasmInstruction::If(a) => Instruction::If(a),
+            WasmInstruction::IfWithFuncType(a, b) => Instruction::IfWithFuncType(a, b),
+            WasmInstruction::IfWithType(a, b) => Instruction::IfWithType(a.into(), b),
             WasmInstruction::LeF32 => Instruction::LeF32,
             WasmInstruction::LeF64 => Instruction::LeF64,
             WasmInstruction::LeSI32 => Instruction::LeSI32,
             WasmInstruction::LeSI64 => Instruction::LeSI64,
             WasmInstruction::LeUI32 => Instruction::LeUI32,
             WasmInstruction::LeUI64 => Instruction::LeUI64,
+            WasmInstruction::LoadF32 { flags, offset } => Instruction::LoadF32 { flags, offset },
+            WasmInstruction::LoadF64 { flags, offset } => Instruction::LoadF64 { flags, offset },
+            WasmInstruction::LoadI32 { flags, offset } => Instruction::LoadI32 { flags, offset },
+            WasmInstruction::LoadI64 { flags, offset } => Instruction::LoadI64 { flags, offset },
+            WasmInstruction::Load16SI32 { flags, offset } => Instruction::Load16SI32 { flags, offset },
+            WasmInstruction::Load16SI64 { flags, offset } => Instruction::Load16SI64 { flags, offset },
+            WasmInstruction::Load16UI32 { flags, offset } => Instruction::Load16UI32 { flags, offset },
+            WasmInstruction::Load16UI64 { flags, offset } => Instruction::Load16UI64 { flags, offset },
+            WasmInstruction::Load32SI64 { flags, offset } => Instruction::Load32SI64 { flags, offset },
+            WasmInstruction::Load32UI64 { flags, offset } => Instruction::Load32UI64 { flags, offset },
+            WasmInstruction::Load8SI32 { flags, offset } => Instruction::Load8SI32 { flags, offset },
+            WasmInstruction::Load8SI64 { flags, offset } => Instruction::Load8SI64 { flags, offset },
+            WasmInstruction::Load8UI32 { flags, offset } => Instruction::Load8UI32 { flags, offset },
+            WasmInstruction::Load8UI64 { flags, offset } => Instruction::Load8UI64 { flags, offset },
             WasmInstruction::LocalGet128(a) => Instruction::LocalGet128(a),
             WasmInstruction::LocalGet32(a) => Instruction::LocalGet32(a),
             WasmInstruction::LocalGet64(a) => Instruction::LocalGet64(a),
@@ -362,7 +537,7 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::LocalTeeRef(a) => Instruction::LocalTeeRef(a),
             WasmInstruction::Loop(a) => Instruction::Loop(a),
             WasmInstruction::LoopWithFuncType(a, b) => Instruction::LoopWithFuncType(a, b),
-            WasmInstruction::LoopWithType(a, b) => Instruction::LoopWithType(a, b),
+            WasmInstruction::LoopWithType(a, b) => Instruction::LoopWithType(a.into(), b),
             WasmInstruction::LtF32 => Instruction::LtF32,
             WasmInstruction::LtF64 => Instruction::LtF64,
             WasmInstruction::LtSI32 => Instruction::LtSI32,
@@ -374,6 +549,10 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::MemoryGrow(a) => Instruction::MemoryGrow(a),
             WasmInstruction::MemoryInit(a, b) => Instruction::MemoryInit(a, b),
             WasmInstruction::MemorySize(a) => Instruction::MemorySize(a),
+            WasmInstruction::MaxF32 => Instruction::MaxF32,
+            WasmInstruction::MaxF64 => Instruction::MaxF64,
+            WasmInstruction::MinF32 => Instruction::MinF32,
+            WasmInstruction::MinF64 => Instruction::MinF64,
             WasmInstruction::MulF32 => Instruction::MulF32,
             WasmInstruction::MulF64 => Instruction::MulF64,
             WasmInstruction::MulI32 => Instruction::MulI32,
@@ -383,6 +562,8 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::NeF64 => Instruction::NeF64,
             WasmInstruction::NeI32 => Instruction::NeI32,
             WasmInstruction::NeI64 => Instruction::NeI64,
+            WasmInstruction::NearestF32 => Instruction::NearestF32,
+            WasmInstruction::NearestF64 => Instruction::NearestF64,
             WasmInstruction::NegF32 => Instruction::NegF32,
             WasmInstruction::NegF64 => Instruction::NegF64,
             WasmInstruction::OrI32 => Instruction::OrI32,
@@ -392,7 +573,7 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::PromoteF32F64 => Instruction::PromoteF32F64,
             WasmInstruction::RefFunc(a) => Instruction::RefFunc(a),
             WasmInstruction::RefIsNull => Instruction::RefIsNull,
-            WasmInstruction::RefNull(a) => Instruction::RefNull(a),
+            WasmInstruction::RefNull(a) => Instruction::RefNull(a.into()),
             WasmInstruction::ReinterpretF32I32 => Instruction::ReinterpretF32I32,
             WasmInstruction::ReinterpretF64I64 => Instruction::ReinterpretF64I64,
             WasmInstruction::ReinterpretI32F32 => Instruction::ReinterpretI32F32,
@@ -440,6 +621,8 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::TableInit(a, b) => Instruction::TableInit(a, b),
             WasmInstruction::TableSet(a) => Instruction::TableSet(a),
             WasmInstruction::TableSize(a) => Instruction::TableSize(a),
+            WasmInstruction::TruncF32 => Instruction::TruncF32,
+            WasmInstruction::TruncF64 => Instruction::TruncF64,
             WasmInstruction::TruncF32SI32 => Instruction::TruncF32SI32,
             WasmInstruction::TruncF32SI64 => Instruction::TruncF32SI64,
             WasmInstruction::TruncF32UI32 => Instruction::TruncF32UI32,
@@ -459,7 +642,8 @@ impl From<WasmInstruction> for Instruction {
             WasmInstruction::Unreachable => Instruction::Unreachable,
             WasmInstruction::WrapI32I64 => Instruction::WrapI32I64,
             WasmInstruction::XorI32 => Instruction::XorI32,
-            WasmInstruction::XorI64 => Instruction::XorI64
-        }
+            WasmInstruction::XorI64 => Instruction::XorI64,
+            inst @ WasmInstruction::SelectWithArity(_) => return Err(inst),
+        })
     }
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 use alloc::boxed::Box;
 use alloc::string::String;
 
-use hal_core::module::{Value, ValueType};
+use hal_core::module::{ConstExpr, Value, ValueType};
 pub use instruction::WasmInstruction;
 pub(crate) use opcode::Opcode;
 
@@ -36,6 +36,9 @@ pub struct WasmModule {
     /// A boxed slice of  memory types, each defining the limits for the memory.
     pub memories: Box<[WasmMemory]>,
 
+    /// A boxed slice of  globals, each with a type and an initial value.
+    pub globals: Box<[WasmGlobal]>,
+
     /// A boxed slice of  exports, each with a name and description of what is being exported.
     pub exports: Box<[WasmExport]>,
 
@@ -48,9 +51,12 @@ pub struct WasmModule {
     /// A boxed slice of  function bodies, each containing local variable declarations and code.
     pub codes: Box<[WasmFunctionBody]>,
 
-    /// A boxed slice of  data segments, each with a memory index, offset, and data.
+    /// A boxed slice of  data segments, each with a mode and data.
     pub data: Box<[WasmData]>,
 
+    /// The number of data segments, as declared by the data count section.
+    pub data_count: Option<u32>,
+
 }
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
@@ -94,7 +100,7 @@ pub struct WasmImport {
 /// Represents the types of a table, specifying the types of elements and limits on the table size.
 pub struct WasmTable {
     /// The types of elements in the table.
-    pub element_type: u8,
+    pub element_type: WasmValueType,
 
     /// The limits on the table's size.
     pub limits: WasmResizableLimit,
@@ -108,6 +114,28 @@ pub struct WasmMemory {
     pub limits: WasmResizableLimit,
 }
 
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(PartialEq)]
+/// Represents a global variable, specifying its type and initial value.
+pub struct WasmGlobal {
+    /// The type of the global.
+    pub global_type: WasmGlobalType,
+
+    /// The constant expression computing the initial value.
+    pub init: WasmConstExpr,
+}
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq)]
+/// Represents the type of a global, specifying its value type and whether it can be modified.
+pub struct WasmGlobalType {
+    /// The type of the value held by the global.
+    pub value_type: WasmValueType,
+
+    /// Whether the global can be modified by `global.set`.
+    pub mutable: bool,
+}
+
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(PartialEq)]
 /// Represents an export, specifying the name and description of what is being exported.
@@ -121,16 +149,36 @@ pub struct WasmExport {
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(PartialEq)]
-/// Represents an element in the element section, which is used to initialize tables.
+/// Represents an element segment in the element section, which is used to initialize tables.
 pub struct WasmElement {
-    /// The index of the table to initialize.
-    pub table_index: u32,
+    /// Whether the segment is copied into a table at instantiation, on `table.init` or never.
+    pub mode: WasmElementMode,
+
+    /// The type of the references in this segment.
+    pub element_type: WasmValueType,
 
-    /// The offset in the table where the initialization begins.
-    pub offset: Box<[Opcode]>,
+    /// The constant expressions producing the references, function indices are stored as `ref.func`.
+    pub init: Box<[WasmConstExpr]>,
+}
 
-    /// The list of function indices to place in the table.
-    pub init: Box<[u32]>,
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(PartialEq)]
+/// Describes when an element segment gets copied into a table.
+pub enum WasmElementMode {
+    /// The segment is only copied by `table.init`.
+    Passive,
+
+    /// The segment is copied into a table at instantiation.
+    Active {
+        /// The index of the table to initialize.
+        table_index: u32,
+
+        /// The offset in the table where the initialization begins.
+        offset: WasmConstExpr,
+    },
+
+    /// The segment only declares references which can be taken by `ref.func`.
+    Declarative,
 }
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
@@ -148,16 +196,50 @@ pub struct WasmFunctionBody {
 #[derive(PartialEq)]
 /// Represents a data segment in the data section, which initializes a portion of memory.
 pub struct WasmData {
-    /// The index of the memory to initialize.
-    pub memory_index: u32,
-
-    /// The offset in the memory where the data begins.
-    pub offset: u32,
+    /// Whether the segment is copied into memory at instantiation or on `memory.init`.
+    pub mode: WasmDataMode,
 
     /// The raw data to be placed in the memory.
     pub data: Box<[u8]>,
 }
 
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(PartialEq)]
+/// Describes when a data segment gets copied into memory.
+pub enum WasmDataMode {
+    /// The segment is only copied by `memory.init`.
+    Passive,
+
+    /// The segment is copied into memory at instantiation.
+    Active {
+        /// The index of the memory to initialize.
+        memory_index: u32,
+
+        /// The offset in the memory where the data begins.
+        offset: WasmConstExpr,
+    },
+}
+
+#[cfg_attr(any(test, debug_assertions), derive(Debug))]
+#[derive(Clone, PartialEq)]
+/// Represents a constant expression, e.g. the offset of a data segment.
+pub enum WasmConstExpr {
+    /// A constant value.
+    Const(WasmValue),
+
+    /// The value of the global with the given index.
+    GlobalGet(u32),
+
+    /// A reference to the function with the given index.
+    RefFunc(u32),
+
+    /// A null reference of the given reference type.
+    RefNull(WasmValueType),
+
+    /// Any other sequence of instructions, without the final `end`, which the validator rejects.
+    Instructions(Box<[WasmInstruction]>),
+}
+
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
 #[derive(PartialEq)]
 /// Describes the types of an import (function, table, memory, or global).
@@ -170,6 +252,9 @@ pub enum WasmImportDescriptor {
 
     /// Import a memory with the given memory types.
     Memory(WasmMemory),
+
+    /// Import a global with the given global types.
+    Global(WasmGlobalType),
 }
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
@@ -201,17 +286,31 @@ pub struct WasmResizableLimit {
 }
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub enum WasmValueType {
+    /// A 32 bit integer.
     I32,
+    /// A 64 bit integer.
     I64,
+    /// A 32 bit float.
+    F32,
+    /// A 64 bit float.
+    F64,
+    /// A nullable function reference.
+    FuncRef,
+    /// A nullable reference to a host object.
+    ExternRef,
 }
 
 impl From<WasmValueType> for ValueType {
     fn from(value: WasmValueType) -> Self {
         match value {
             WasmValueType::I32 => ValueType::I32,
-            WasmValueType::I64 => ValueType::I64
+            WasmValueType::I64 => ValueType::I64,
+            WasmValueType::F32 => ValueType::F32,
+            WasmValueType::F64 => ValueType::F64,
+            WasmValueType::FuncRef => ValueType::FuncRef,
+            WasmValueType::ExternRef => ValueType::ExternRef,
         }
     }
 }
@@ -220,24 +319,50 @@ impl From<&WasmValueType> for ValueType {
     fn from(value: &WasmValueType) -> Self {
         match value {
             WasmValueType::I32 => ValueType::I32,
-            WasmValueType::I64 => ValueType::I64
+            WasmValueType::I64 => ValueType::I64,
+            WasmValueType::F32 => ValueType::F32,
+            WasmValueType::F64 => ValueType::F64,
+            WasmValueType::FuncRef => ValueType::FuncRef,
+            WasmValueType::ExternRef => ValueType::ExternRef,
         }
     }
 }
 
 
 #[cfg_attr(any(test, debug_assertions), derive(Debug))]
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub enum WasmValue {
+    /// A 32 bit integer.
     I32(i32),
+    /// A 64 bit integer.
     I64(i64),
+    /// A 32 bit float.
+    F32(f32),
+    /// A 64 bit float.
+    F64(f64),
 }
 
 impl From<WasmValue> for Value {
     fn from(value: WasmValue) -> Self {
         match value {
             WasmValue::I32(v) => Value::I32(v),
-            WasmValue::I64(v) => Value::I64(v)
+            WasmValue::I64(v) => Value::I64(v),
+            WasmValue::F32(v) => Value::F32(v),
+            WasmValue::F64(v) => Value::F64(v),
+        }
+    }
+}
+// a sequence of instructions has no lowering, it is returned as the error
+impl TryFrom<WasmConstExpr> for ConstExpr {
+    type Error = WasmConstExpr;
+
+    fn try_from(value: WasmConstExpr) -> Result<Self, Self::Error> {
+        match value {
+            WasmConstExpr::Const(v) => Ok(ConstExpr::Const(v.into())),
+            WasmConstExpr::GlobalGet(idx) => Ok(ConstExpr::GlobalGet(idx)),
+            WasmConstExpr::RefFunc(idx) => Ok(ConstExpr::RefFunc(idx)),
+            WasmConstExpr::RefNull(value_type) => Ok(ConstExpr::RefNull(value_type.into())),
+            expr @ WasmConstExpr::Instructions(_) => Err(expr),
         }
     }
-}
\ No newline at end of file
+}
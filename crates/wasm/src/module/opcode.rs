@@ -1,7 +1,7 @@
 use crate::error::WasmParseError;
 
-#[cfg_attr(any(test, debug_assertions), derive(Debug))]
-#[derive(PartialEq)]
+// parse errors name the opcode, in release builds too
+#[derive(Debug, PartialEq)]
 pub enum Opcode {
     // Control instructions
     Unreachable = 0x00,
@@ -21,6 +21,8 @@ pub enum Opcode {
     Return = 0x0F,
     Call = 0x10,
     CallIndirect = 0x11,
+    ReturnCall = 0x12,
+    ReturnCallIndirect = 0x13,
 
     // Parametric instructions
     Drop = 0x1A,
@@ -34,6 +36,10 @@ pub enum Opcode {
     GlobalGet = 0x23,
     GlobalSet = 0x24,
 
+    // Table instructions
+    TableGet = 0x25,
+    TableSet = 0x26,
+
     // Memory instructions
     LoadI32 = 0x28,
     LoadI64 = 0x29,
@@ -214,6 +220,16 @@ pub enum Opcode {
     RefIsNull = 0xD1,
     RefFunc = 0xD2,
 
+    // Saturating truncation instructions
+    TruncSatSI32F32 = 0xFC_00,
+    TruncSatUI32F32 = 0xFC_01,
+    TruncSatSI32F64 = 0xFC_02,
+    TruncSatUI32F64 = 0xFC_03,
+    TruncSatSI64F32 = 0xFC_04,
+    TruncSatUI64F32 = 0xFC_05,
+    TruncSatSI64F64 = 0xFC_06,
+    TruncSatUI64F64 = 0xFC_07,
+
     // Bulk memory operations
     MemoryInit = 0xFC_08,
     DataDrop = 0xFC_09,
@@ -272,6 +288,8 @@ impl Opcode {
             0x0F => Ok(Opcode::Return),
             0x10 => Ok(Opcode::Call),
             0x11 => Ok(Opcode::CallIndirect),
+            0x12 => Ok(Opcode::ReturnCall),
+            0x13 => Ok(Opcode::ReturnCallIndirect),
             0x1A => Ok(Opcode::Drop),
             0x1B => Ok(Opcode::Select),
             0x1C => Ok(Opcode::SelectT),
@@ -280,6 +298,8 @@ impl Opcode {
             0x22 => Ok(Opcode::LocalTee),
             0x23 => Ok(Opcode::GlobalGet),
             0x24 => Ok(Opcode::GlobalSet),
+            0x25 => Ok(Opcode::TableGet),
+            0x26 => Ok(Opcode::TableSet),
             0x28 => Ok(Opcode::LoadI32),
             0x29 => Ok(Opcode::LoadI64),
             0x2A => Ok(Opcode::LoadF32),
@@ -440,7 +460,23 @@ impl Opcode {
             0xD0 => Ok(Opcode::RefNull),
             0xD1 => Ok(Opcode::RefIsNull),
             0xD2 => Ok(Opcode::RefFunc),
+            _ => Err(WasmParseError::InvalidOpcode(value)),
+        }
+    }
+
+    /// Maps an instruction which is encoded as a prefix byte (`0xFC` or `0xFD`) followed by a
+    /// LEB128 encoded sub opcode.
+    pub(crate) fn from_prefixed(prefix: u8, value: u32) -> Result<Self, WasmParseError> {
+        match prefix {
             0xFC => match value {
+                0x00 => Ok(Opcode::TruncSatSI32F32),
+                0x01 => Ok(Opcode::TruncSatUI32F32),
+                0x02 => Ok(Opcode::TruncSatSI32F64),
+                0x03 => Ok(Opcode::TruncSatUI32F64),
+                0x04 => Ok(Opcode::TruncSatSI64F32),
+                0x05 => Ok(Opcode::TruncSatUI64F32),
+                0x06 => Ok(Opcode::TruncSatSI64F64),
+                0x07 => Ok(Opcode::TruncSatUI64F64),
                 0x08 => Ok(Opcode::MemoryInit),
                 0x09 => Ok(Opcode::DataDrop),
                 0x0A => Ok(Opcode::MemoryCopy),
@@ -451,7 +487,7 @@ impl Opcode {
                 0x0F => Ok(Opcode::TableGrow),
                 0x10 => Ok(Opcode::TableSize),
                 0x11 => Ok(Opcode::TableFill),
-                _ => Err(WasmParseError::InvalidOpcode(value)),
+                _ => Err(WasmParseError::InvalidPrefixedOpcode(prefix, value)),
             },
             0xFD => match value {
                 0x00 => Ok(Opcode::LoadV128),
@@ -476,9 +512,9 @@ impl Opcode {
                 0x1D => Ok(Opcode::ReplaceLaneI64x2),
                 0x1E => Ok(Opcode::ReplaceLaneF32x4),
                 0x1F => Ok(Opcode::ReplaceLaneF64x2),
-                _ => Err(WasmParseError::InvalidOpcode(value)),
+                _ => Err(WasmParseError::InvalidPrefixedOpcode(prefix, value)),
             },
-            _ => Err(WasmParseError::InvalidOpcode(value)),
+            _ => Err(WasmParseError::InvalidPrefixedOpcode(prefix, value)),
         }
     }
 }
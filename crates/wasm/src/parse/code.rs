@@ -1,19 +1,21 @@
 use alloc::boxed::Box;
 use alloc::vec;
+use crate::error::WasmParseError;
+use crate::features::WasmFeatures;
 use crate::Result;
-use crate::module::WasmFunctionBody;
+use crate::module::{WasmFunctionBody, WasmInstruction};
 use crate::parse::instruction::parse_instruction;
 use crate::parse::value::parse_value_type;
 use hal_core::reader::ByteReader;
 
-pub(crate) fn parse_code_section(size: u32, reader: &ByteReader) -> Result<Box<[WasmFunctionBody]>>{
+pub(crate) fn parse_code_section(size: u32, reader: &ByteReader, features: &WasmFeatures) -> Result<Box<[WasmFunctionBody]>>{
     let mut result = vec![];
     let expected_reader_pos = reader.pos() + size as usize;
     let count = reader.read_leb128_u32()?;
 
     for _ in 0..count {
         let size = reader.read_leb128_u32()?;
-        let body = parse_function_body(size, reader)?;
+        let body = parse_function_body(size, reader, features)?;
         result.push(body);
     }
 
@@ -21,22 +23,46 @@ pub(crate) fn parse_code_section(size: u32, reader: &ByteReader) -> Result<Box<[
     Ok(result.into())
 }
 
-fn parse_function_body(size: u32, reader: &ByteReader) -> Result<WasmFunctionBody>{
+fn parse_function_body(size: u32, reader: &ByteReader, features: &WasmFeatures) -> Result<WasmFunctionBody>{
     let expected_reader_pos = reader.pos() + size as usize;
 
     let count = reader.read_leb128_u32()?;
     let mut locals = vec![];
 
+    // the number of locals must fit into a u32, no matter how they are grouped
+    let mut total: u32 = 0;
     for _ in 0..count {
         let type_count = reader.read_leb128_u32()?;
+        total = total.checked_add(type_count).ok_or(WasmParseError::TooManyLocals)?;
         let value_type = parse_value_type(reader)?;
         locals.push((type_count, value_type));
     }
 
     let mut code = vec![];
+    let mut blocks = vec![];
     while reader.pos() < expected_reader_pos {
-        let inst = parse_instruction(reader)?;
-        code.push(inst);
+        let at = code.len();
+        parse_instruction(reader, &mut code, features)?;
+
+        match code[at] {
+            WasmInstruction::Block(_) | WasmInstruction::BlockWithType(..) | WasmInstruction::BlockWithFuncType(..) |
+            WasmInstruction::Loop(_) | WasmInstruction::LoopWithType(..) | WasmInstruction::LoopWithFuncType(..) |
+            WasmInstruction::If(_) | WasmInstruction::IfWithType(..) | WasmInstruction::IfWithFuncType(..) => {
+                blocks.push(at);
+            }
+            WasmInstruction::Else(_) => {
+                if let Some(opened_at) = blocks.pop() {
+                    patch_end(&mut code[opened_at], at as u32);
+                }
+                blocks.push(at);
+            }
+            WasmInstruction::End => {
+                if let Some(opened_at) = blocks.pop() {
+                    patch_end(&mut code[opened_at], at as u32);
+                }
+            }
+            _ => {}
+        }
     }
 
     debug_assert_eq!(reader.pos(), expected_reader_pos);
@@ -44,4 +70,15 @@ fn parse_function_body(size: u32, reader: &ByteReader) -> Result<WasmFunctionBod
         locals: locals.into(),
         code: code.into(),
     })
-}
\ No newline at end of file
+}
+
+/// Points a structured control instruction at the index of its matching `else` or `end`.
+fn patch_end(inst: &mut WasmInstruction, end: u32) {
+    match inst {
+        WasmInstruction::Block(at) | WasmInstruction::Loop(at) | WasmInstruction::If(at) | WasmInstruction::Else(at) => *at = end,
+        WasmInstruction::BlockWithType(_, at) | WasmInstruction::BlockWithFuncType(_, at) |
+        WasmInstruction::LoopWithType(_, at) | WasmInstruction::LoopWithFuncType(_, at) |
+        WasmInstruction::IfWithType(_, at) | WasmInstruction::IfWithFuncType(_, at) => *at = end,
+        _ => {}
+    }
+}
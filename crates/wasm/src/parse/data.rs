@@ -3,35 +3,47 @@ use alloc::vec;
 
 use hal_core::reader::ByteReader;
 
-use crate::module::WasmData;
+use crate::error::WasmParseError::InvalidDataMode;
+use crate::features::{WasmFeature, WasmFeatures};
+use crate::module::{WasmData, WasmDataMode};
+use crate::parse::expr::parse_const_expr;
 use crate::Result;
 
-pub(crate) fn parse_data_section(size: u32, reader: &ByteReader) -> Result<Box<[WasmData]>> {
+pub(crate) fn parse_data_section(size: u32, reader: &ByteReader, features: &WasmFeatures) -> Result<Box<[WasmData]>> {
     let expected_reader_pos = reader.pos() + size as usize;
     let count = reader.read_leb128_u32()?;
 
     let mut result = vec![];
 
     for _ in 0..count {
-        let memory_index = reader.read_leb128_u32()?;
-        let offset = parse_expr(reader)?;
+        let flags = reader.read_leb128_u32()?;
+        if flags != 0 {
+            features.require(WasmFeature::BulkMemory)?;
+        }
+
+        let mode = match flags {
+            0 => WasmDataMode::Active { memory_index: 0, offset: parse_const_expr(reader, features)? },
+            1 => WasmDataMode::Passive,
+            2 => {
+                let memory_index = reader.read_leb128_u32()?;
+                WasmDataMode::Active { memory_index, offset: parse_const_expr(reader, features)? }
+            }
+            flags => return Err(InvalidDataMode(flags)),
+        };
         let size = reader.read_leb128_u32()?;
         let data = reader.read_range(size as usize)?;
 
-        result.push(WasmData {
-            memory_index,
-            offset,
-            data,
-        });
+        result.push(WasmData { mode, data });
     }
 
     debug_assert_eq!(reader.pos(), expected_reader_pos);
     Ok(result.into())
 }
 
-fn parse_expr(reader: &ByteReader) -> Result<u32> {
-    let _ = reader.read_leb128_u32()?;
-    let offset = reader.read_leb128_u32()?;
-    let _ = reader.read_leb128_u32()?;
-    Ok(offset)
+pub(crate) fn parse_data_count_section(size: u32, reader: &ByteReader) -> Result<u32> {
+    let expected_reader_pos = reader.pos() + size as usize;
+    let count = reader.read_leb128_u32()?;
+
+    debug_assert_eq!(reader.pos(), expected_reader_pos);
+    Ok(count)
 }
@@ -0,0 +1,65 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use hal_core::reader::ByteReader;
+
+use crate::error::WasmParseError::{InvalidElementMode, InvalidElementType};
+use crate::features::{WasmFeature, WasmFeatures};
+use crate::module::{WasmConstExpr, WasmElement, WasmElementMode, WasmValueType};
+use crate::parse::expr::parse_const_expr;
+use crate::parse::table::parse_ref_type;
+use crate::Result;
+
+pub(crate) fn parse_element_section(size: u32, reader: &ByteReader, features: &WasmFeatures) -> Result<Box<[WasmElement]>> {
+    let expected_reader_pos = reader.pos() + size as usize;
+    let count = reader.read_leb128_u32()?;
+
+    let mut result = Vec::with_capacity(count as usize);
+
+    for _ in 0..count {
+        // bit 0: passive or declarative, bit 1: explicit table index or declarative, bit 2: expressions instead of function indices
+        let flags = reader.read_leb128_u32()?;
+        if flags > 7 {
+            return Err(InvalidElementMode(flags));
+        }
+        if flags != 0 {
+            features.require(WasmFeature::BulkMemory)?;
+        }
+
+        let mode = match flags & 0b011 {
+            0b000 => WasmElementMode::Active { table_index: 0, offset: parse_const_expr(reader, features)? },
+            0b001 => WasmElementMode::Passive,
+            0b010 => {
+                let table_index = reader.read_leb128_u32()?;
+                WasmElementMode::Active { table_index, offset: parse_const_expr(reader, features)? }
+            }
+            _ => WasmElementMode::Declarative,
+        };
+
+        let uses_expressions = flags & 0b100 != 0;
+        let element_type = match (flags & 0b011, uses_expressions) {
+            // without an explicit type, the segment holds function references
+            (0b000, _) => WasmValueType::FuncRef,
+            (_, true) => parse_ref_type(reader)?,
+            (_, false) => match reader.read_u8()? {
+                0x00 => WasmValueType::FuncRef,
+                kind => return Err(InvalidElementType(kind)),
+            },
+        };
+
+        let count = reader.read_leb128_u32()?;
+        let mut init = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            init.push(if uses_expressions {
+                parse_const_expr(reader, features)?
+            } else {
+                WasmConstExpr::RefFunc(reader.read_leb128_u32()?)
+            });
+        }
+
+        result.push(WasmElement { mode, element_type, init: init.into() });
+    }
+
+    debug_assert_eq!(reader.pos(), expected_reader_pos);
+    Ok(result.into())
+}
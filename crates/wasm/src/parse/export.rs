@@ -19,6 +19,9 @@ pub(crate) fn parse_export_section(size: u32, reader: &ByteReader) -> Result<Box
         let addr = reader.read_leb128_u32()?;
         let desc = match export_kind {
             0x00 => Ok(WasmExportDescriptor::Func(addr)),
+            0x01 => Ok(WasmExportDescriptor::Table(addr)),
+            0x02 => Ok(WasmExportDescriptor::Memory(addr)),
+            0x03 => Ok(WasmExportDescriptor::Global(addr)),
             _ => Err(InvalidExportDescriptor(export_kind)),
         }?;
         result.push(WasmExport { name, desc });
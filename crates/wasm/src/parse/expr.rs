@@ -0,0 +1,109 @@
+use alloc::vec::Vec;
+
+use hal_core::reader::ByteReader;
+
+use crate::features::{WasmFeature, WasmFeatures};
+use crate::module::{WasmConstExpr, WasmInstruction, WasmValue};
+use crate::parse::instruction::parse_instruction;
+use crate::Result;
+
+pub(crate) fn parse_const_expr(reader: &ByteReader, features: &WasmFeatures) -> Result<WasmConstExpr> {
+    // nested blocks are not constant, but their `end` must not end the expression
+    let mut code = Vec::new();
+    let mut depth = 0;
+    loop {
+        parse_instruction(reader, &mut code, features)?;
+        match code.last() {
+            Some(WasmInstruction::End) if depth == 0 => break,
+            Some(WasmInstruction::End) => depth -= 1,
+            Some(WasmInstruction::Block(..) | WasmInstruction::BlockWithType(..) | WasmInstruction::BlockWithFuncType(..) |
+                 WasmInstruction::Loop(..) | WasmInstruction::LoopWithType(..) | WasmInstruction::LoopWithFuncType(..) |
+                 WasmInstruction::If(..) | WasmInstruction::IfWithType(..) | WasmInstruction::IfWithFuncType(..)) => depth += 1,
+            _ => {}
+        }
+    }
+    code.pop();
+
+    match code.as_slice() {
+        [WasmInstruction::ConstI32(value)] => Ok(WasmConstExpr::Const(WasmValue::I32(*value))),
+        [WasmInstruction::ConstI64(value)] => Ok(WasmConstExpr::Const(WasmValue::I64(*value))),
+        [WasmInstruction::ConstF32(value)] => Ok(WasmConstExpr::Const(WasmValue::F32(*value))),
+        [WasmInstruction::ConstF64(value)] => Ok(WasmConstExpr::Const(WasmValue::F64(*value))),
+        [WasmInstruction::GlobalGet(idx)] => Ok(WasmConstExpr::GlobalGet(*idx)),
+        [WasmInstruction::RefFunc(idx)] => Ok(WasmConstExpr::RefFunc(*idx)),
+        [WasmInstruction::RefNull(value_type)] => Ok(WasmConstExpr::RefNull(value_type.clone())),
+        // arithmetic of the extended constant expressions proposal can not be evaluated yet
+        _ if features.extended_const && code.iter().any(is_extended_const) => Err(features.unsupported(WasmFeature::ExtendedConst)),
+        // anything else is left to the validator, which rejects it
+        _ => Ok(WasmConstExpr::Instructions(code.into())),
+    }
+}
+
+fn is_extended_const(instruction: &WasmInstruction) -> bool {
+    matches!(instruction,
+        WasmInstruction::AddI32 | WasmInstruction::SubI32 | WasmInstruction::MulI32 |
+        WasmInstruction::AddI64 | WasmInstruction::SubI64 | WasmInstruction::MulI64)
+}
+
+#[cfg(test)]
+mod tests {
+    use hal_core::reader::ByteReader;
+
+    use crate::error::WasmParseError::{FeatureDisabled, UnsupportedFeature};
+    use crate::features::{WasmFeature, WasmFeatures};
+    use crate::module::{WasmConstExpr, WasmInstruction, WasmValue, WasmValueType};
+    use crate::parse::expr;
+    use crate::Result;
+
+    #[test]
+    fn constant() {
+        let reader = ByteReader::new(&[0x41, 0x80, 0x01, 0x0B]);
+        assert_eq!(parse_const_expr(&reader), Ok(WasmConstExpr::Const(WasmValue::I32(128))));
+        assert!(reader.eof());
+    }
+
+    #[test]
+    fn global_get() {
+        let reader = ByteReader::new(&[0x23, 0x02, 0x0B]);
+        assert_eq!(parse_const_expr(&reader), Ok(WasmConstExpr::GlobalGet(2)));
+    }
+
+    #[test]
+    fn references() {
+        let reader = ByteReader::new(&[0xD2, 0x03, 0x0B, 0xD0, 0x70, 0x0B]);
+        assert_eq!(parse_const_expr(&reader), Ok(WasmConstExpr::RefFunc(3)));
+        assert_eq!(parse_const_expr(&reader), Ok(WasmConstExpr::RefNull(WasmValueType::FuncRef)));
+    }
+
+    #[test]
+    fn not_constant() {
+        let reader = ByteReader::new(&[0x41, 0x01, 0x20, 0x00, 0x0B]);
+        assert_eq!(parse_const_expr(&reader), Ok(WasmConstExpr::Instructions([WasmInstruction::ConstI32(1), WasmInstruction::LocalGet32(0)].into())));
+
+        let reader = ByteReader::new(&[0x0B]);
+        assert_eq!(parse_const_expr(&reader), Ok(WasmConstExpr::Instructions([].into())));
+
+        // the end of the block does not end the expression
+        let reader = ByteReader::new(&[0x02, 0x40, 0x0B, 0x41, 0x01, 0x0B]);
+        assert_eq!(parse_const_expr(&reader), Ok(WasmConstExpr::Instructions([WasmInstruction::Block(0), WasmInstruction::End, WasmInstruction::ConstI32(1)].into())));
+        assert!(reader.eof());
+    }
+
+    #[test]
+    fn disabled_features() {
+        let reader = ByteReader::new(&[0xD2, 0x03, 0x0B]);
+        assert_eq!(expr::parse_const_expr(&reader, &WasmFeatures::mvp()), Err(FeatureDisabled(WasmFeature::ReferenceTypes)));
+
+        let reader = ByteReader::new(&[0x41, 0x01, 0x41, 0x02, 0x6A, 0x0B]);
+        let instructions = [WasmInstruction::ConstI32(1), WasmInstruction::ConstI32(2), WasmInstruction::AddI32];
+        assert_eq!(expr::parse_const_expr(&reader, &WasmFeatures::default()), Ok(WasmConstExpr::Instructions(instructions.into())));
+
+        let features = WasmFeatures { extended_const: true, ..WasmFeatures::default() };
+        let reader = ByteReader::new(&[0x41, 0x01, 0x41, 0x02, 0x6A, 0x0B]);
+        assert_eq!(expr::parse_const_expr(&reader, &features), Err(UnsupportedFeature(WasmFeature::ExtendedConst)));
+    }
+
+    fn parse_const_expr(reader: &ByteReader) -> Result<WasmConstExpr> {
+        expr::parse_const_expr(reader, &WasmFeatures::default())
+    }
+}
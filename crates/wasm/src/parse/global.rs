@@ -0,0 +1,36 @@
+use alloc::boxed::Box;
+use alloc::vec;
+
+use hal_core::reader::ByteReader;
+
+use crate::error::WasmParseError::InvalidMutability;
+use crate::features::WasmFeatures;
+use crate::module::{WasmGlobal, WasmGlobalType};
+use crate::parse::expr::parse_const_expr;
+use crate::parse::value::parse_value_type;
+use crate::Result;
+
+pub(crate) fn parse_global_section(size: u32, reader: &ByteReader, features: &WasmFeatures) -> Result<Box<[WasmGlobal]>> {
+    let expected_reader_pos = reader.pos() + size as usize;
+    let count = reader.read_leb128_u32()?;
+    let mut result = vec![];
+
+    for _ in 0..count {
+        let global_type = parse_global_type(reader)?;
+        let init = parse_const_expr(reader, features)?;
+        result.push(WasmGlobal { global_type, init })
+    }
+
+    debug_assert_eq!(reader.pos(), expected_reader_pos);
+    Ok(result.into())
+}
+
+pub(crate) fn parse_global_type(reader: &ByteReader) -> Result<WasmGlobalType> {
+    let value_type = parse_value_type(reader)?;
+    let mutable = match reader.read_u8()? {
+        0x00 => false,
+        0x01 => true,
+        mutability => return Err(InvalidMutability(mutability)),
+    };
+    Ok(WasmGlobalType { value_type, mutable })
+}
@@ -4,11 +4,15 @@ use alloc::vec;
 use hal_core::reader::ByteReader;
 
 use crate::error::WasmParseError;
-use crate::module::{WasmImport, WasmImportDescriptor};
+use crate::features::WasmFeatures;
+use crate::module::{WasmImport, WasmImportDescriptor, WasmMemory};
+use crate::parse::global::parse_global_type;
+use crate::parse::memory::parse_memory_limits;
 use crate::parse::name::parse_name;
+use crate::parse::table::parse_table_type;
 use crate::Result;
 
-pub(crate) fn parse_import_section(size: u32, reader: &ByteReader) -> Result<Box<[WasmImport]>> {
+pub(crate) fn parse_import_section(size: u32, reader: &ByteReader, features: &WasmFeatures) -> Result<Box<[WasmImport]>> {
     let expected_reader_pos = reader.pos() + size as usize;
     let count = reader.read_leb128_u32()?;
 
@@ -23,6 +27,9 @@ pub(crate) fn parse_import_section(size: u32, reader: &ByteReader) -> Result<Box
                 let addr = reader.read_leb128_u32()?;
                 Ok(WasmImportDescriptor::Func(addr))
             }
+            0x01 => Ok(WasmImportDescriptor::Table(parse_table_type(reader)?)),
+            0x02 => Ok(WasmImportDescriptor::Memory(WasmMemory { limits: parse_memory_limits(reader, features)? })),
+            0x03 => Ok(WasmImportDescriptor::Global(parse_global_type(reader)?)),
             _ => Err(WasmParseError::InvalidImportDescriptor(import_kind)),
         }?;
 
@@ -1,13 +1,23 @@
+use alloc::vec::Vec;
+
 use hal_core::reader::ByteReader;
 
-use crate::module::Opcode;
-use crate::module::WasmInstruction;
+use crate::error::WasmParseError::{FeatureDisabled, InvalidMemoryFlags};
+use crate::features::{WasmFeature, WasmFeatures};
+use crate::module::{Opcode, WasmInstruction, WasmValueType};
+use crate::parse::table::parse_ref_type;
+use crate::parse::value::{BlockType, parse_block_type, parse_value_types};
 use crate::Result;
 
-pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction> {
-    let op = reader.read_u8()?;
-    let op = Opcode::from_u8(op)?;
-    match op {
+pub(crate) fn parse_instruction(reader: &ByteReader, code: &mut Vec<WasmInstruction>, features: &WasmFeatures) -> Result<()> {
+    let op = match reader.read_u8()? {
+        0xFC => Opcode::from_prefixed(0xFC, reader.read_leb128_u32()?)?,
+        // vector and atomic instructions are not decoded yet
+        0xFD => return Err(features.unsupported(WasmFeature::Simd)),
+        0xFE => return Err(features.unsupported(WasmFeature::Threads)),
+        op => Opcode::from_u8(op)?,
+    };
+    let inst: Result<WasmInstruction> = match op {
         Opcode::AddI32 => Ok(WasmInstruction::AddI32),
         Opcode::AddI64 => Ok(WasmInstruction::AddI64),
         Opcode::AddF32 => Ok(WasmInstruction::AddF32),
@@ -27,6 +37,8 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
         Opcode::DivUI32 => Ok(WasmInstruction::DivUI32),
         Opcode::DivSI64 => Ok(WasmInstruction::DivSI64),
         Opcode::DivUI64 => Ok(WasmInstruction::DivUI64),
+        Opcode::DivF32 => Ok(WasmInstruction::DivF32),
+        Opcode::DivF64 => Ok(WasmInstruction::DivF64),
 
         Opcode::ConstI32 => {
             let value = reader.read_leb128_i32()?;
@@ -107,11 +119,17 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
         Opcode::CopysignF32 => Ok(WasmInstruction::CopysignF32),
         Opcode::CopysignF64 => Ok(WasmInstruction::CopysignF64),
 
-        // Opcode::MinF32 => Ok(WasmInstruction::MinF32),
-        // Opcode::MinF64 => Ok(WasmInstruction::MinF64),
-        //
-        // Opcode::MaxF32 => Ok(WasmInstruction::MaxF32),
-        // Opcode::MaxF64 => Ok(WasmInstruction::MaxF64),
+        Opcode::TruncF32 => Ok(WasmInstruction::TruncF32),
+        Opcode::TruncF64 => Ok(WasmInstruction::TruncF64),
+
+        Opcode::NearestF32 => Ok(WasmInstruction::NearestF32),
+        Opcode::NearestF64 => Ok(WasmInstruction::NearestF64),
+
+        Opcode::MinF32 => Ok(WasmInstruction::MinF32),
+        Opcode::MinF64 => Ok(WasmInstruction::MinF64),
+
+        Opcode::MaxF32 => Ok(WasmInstruction::MaxF32),
+        Opcode::MaxF64 => Ok(WasmInstruction::MaxF64),
 
         Opcode::Call => {
             let addr = reader.read_leb128_u32()?;
@@ -122,6 +140,15 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
             let table_index = reader.read_leb128_u32()?;
             Ok(WasmInstruction::CallIndirect(type_index, table_index))
         }
+        Opcode::ReturnCall => {
+            let function_index = reader.read_leb128_u32()?;
+            Ok(WasmInstruction::ReturnCall(function_index))
+        }
+        Opcode::ReturnCallIndirect => {
+            let type_index = reader.read_leb128_u32()?;
+            let table_index = reader.read_leb128_u32()?;
+            Ok(WasmInstruction::ReturnCallIndirect(type_index, table_index))
+        }
 
         Opcode::Br => {
             let label_index = reader.read_leb128_u32()?;
@@ -132,32 +159,55 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
             Ok(WasmInstruction::BrIf(label_index))
         }
         Opcode::BrTable => {
-            let table_index = reader.read_leb128_u32()?;
+            let count = reader.read_leb128_u32()?;
+            let mut labels = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                labels.push(reader.read_leb128_u32()?);
+            }
             let default_index = reader.read_leb128_u32()?;
-            Ok(WasmInstruction::BrTable(table_index, default_index))
-        }
 
-        Opcode::Block => {
-            let block_type = reader.read_leb128_u32()?;
-            Ok(WasmInstruction::Block(block_type))
-        }
-        Opcode::Loop => {
-            let block_type = reader.read_leb128_u32()?;
-            Ok(WasmInstruction::Loop(block_type))
-        }
-        // Opcode::If => {
-        //     let block_type = reader.read_leb128_u32()?;
-        //     Ok(WasmInstruction::If(block_type))
-        // }
-        Opcode::Else => {
-            let offset = reader.read_leb128_u32()?;
-            Ok(WasmInstruction::Else(offset))
-        }
+            code.push(WasmInstruction::BrTable(count, default_index));
+            code.extend(labels.into_iter().map(WasmInstruction::BrLabel));
+            return Ok(());
+        }
+
+        // block, loop and if are followed by their end (or else) index, which is patched in once the
+        // matching end is parsed
+        Opcode::Block => match parse_block_type(reader)? {
+            BlockType::Empty => Ok(WasmInstruction::Block(0)),
+            BlockType::Value(value_type) => Ok(WasmInstruction::BlockWithType(value_type, 0)),
+            BlockType::Func(type_index) => Ok(WasmInstruction::BlockWithFuncType(type_index, 0)),
+        },
+        Opcode::Loop => match parse_block_type(reader)? {
+            BlockType::Empty => Ok(WasmInstruction::Loop(0)),
+            BlockType::Value(value_type) => Ok(WasmInstruction::LoopWithType(value_type, 0)),
+            BlockType::Func(type_index) => Ok(WasmInstruction::LoopWithFuncType(type_index, 0)),
+        },
+        Opcode::If => match parse_block_type(reader)? {
+            BlockType::Empty => Ok(WasmInstruction::If(0)),
+            BlockType::Value(value_type) => Ok(WasmInstruction::IfWithType(value_type, 0)),
+            BlockType::Func(type_index) => Ok(WasmInstruction::IfWithFuncType(type_index, 0)),
+        },
+        Opcode::Else => Ok(WasmInstruction::Else(0)),
 
         Opcode::End => Ok(WasmInstruction::End),
+        Opcode::Nop => Ok(WasmInstruction::Nop),
+        Opcode::Return => Ok(WasmInstruction::Return),
         Opcode::Unreachable => Ok(WasmInstruction::Unreachable),
 
         Opcode::Drop => Ok(WasmInstruction::Drop),
+        Opcode::Select => Ok(WasmInstruction::Select32),
+        Opcode::SelectT => {
+            features.require(WasmFeature::ReferenceTypes)?;
+            let types = reader.read_leb128_u32()?;
+            let value_types = parse_value_types(types, reader)?;
+            match value_types.as_ref() {
+                [WasmValueType::I32 | WasmValueType::F32] => Ok(WasmInstruction::Select32),
+                [WasmValueType::I64 | WasmValueType::F64] => Ok(WasmInstruction::Select64),
+                [WasmValueType::FuncRef | WasmValueType::ExternRef] => Ok(WasmInstruction::SelectRef),
+                _ => Ok(WasmInstruction::SelectWithArity(types)),
+            }
+        }
 
         Opcode::GlobalGet => {
             let global_index = reader.read_leb128_u32()?;
@@ -182,47 +232,101 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
         }
 
         Opcode::LoadI32 => {
-            let flags = reader.read_leb128_u32()?;
-            let offset = reader.read_leb128_u32()?;
-            Ok(WasmInstruction::StoreI32 { flags, offset })
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::LoadI32 { flags, offset })
         }
         Opcode::LoadI64 => {
-            let flags = reader.read_leb128_u32()?;
-            let offset = reader.read_leb128_u32()?;
-            Ok(WasmInstruction::StoreI64 { flags, offset })
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::LoadI64 { flags, offset })
         }
         Opcode::LoadF32 => {
-            let flags = reader.read_leb128_u32()?;
-            let offset = reader.read_leb128_u32()?;
-            Ok(WasmInstruction::StoreF32 { flags, offset })
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::LoadF32 { flags, offset })
         }
         Opcode::LoadF64 => {
-            let flags = reader.read_leb128_u32()?;
-            let offset = reader.read_leb128_u32()?;
-            Ok(WasmInstruction::StoreF64 { flags, offset })
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::LoadF64 { flags, offset })
+        }
+
+        Opcode::Load8SI32 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Load8SI32 { flags, offset })
+        }
+        Opcode::Load8UI32 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Load8UI32 { flags, offset })
+        }
+        Opcode::Load16SI32 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Load16SI32 { flags, offset })
+        }
+        Opcode::Load16UI32 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Load16UI32 { flags, offset })
+        }
+        Opcode::Load8SI64 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Load8SI64 { flags, offset })
+        }
+        Opcode::Load8UI64 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Load8UI64 { flags, offset })
+        }
+        Opcode::Load16SI64 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Load16SI64 { flags, offset })
+        }
+        Opcode::Load16UI64 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Load16UI64 { flags, offset })
+        }
+        Opcode::Load32SI64 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Load32SI64 { flags, offset })
+        }
+        Opcode::Load32UI64 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Load32UI64 { flags, offset })
         }
 
         Opcode::StoreI32 => {
-            let flags = reader.read_leb128_u32()?;
-            let offset = reader.read_leb128_u32()?;
+            let (flags, offset) = parse_memarg(reader)?;
             Ok(WasmInstruction::StoreI32 { flags, offset })
         }
         Opcode::StoreI64 => {
-            let flags = reader.read_leb128_u32()?;
-            let offset = reader.read_leb128_u32()?;
+            let (flags, offset) = parse_memarg(reader)?;
             Ok(WasmInstruction::StoreI64 { flags, offset })
         }
         Opcode::StoreF32 => {
-            let flags = reader.read_leb128_u32()?;
-            let offset = reader.read_leb128_u32()?;
+            let (flags, offset) = parse_memarg(reader)?;
             Ok(WasmInstruction::StoreF32 { flags, offset })
         }
         Opcode::StoreF64 => {
-            let flags = reader.read_leb128_u32()?;
-            let offset = reader.read_leb128_u32()?;
+            let (flags, offset) = parse_memarg(reader)?;
             Ok(WasmInstruction::StoreF64 { flags, offset })
         }
 
+        Opcode::Store8I32 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Store8I32 { flags, offset })
+        }
+        Opcode::Store16I32 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Store16I32 { flags, offset })
+        }
+        Opcode::Store8I64 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Store8I64 { flags, offset })
+        }
+        Opcode::Store16I64 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Store16I64 { flags, offset })
+        }
+        Opcode::Store32I64 => {
+            let (flags, offset) = parse_memarg(reader)?;
+            Ok(WasmInstruction::Store32I64 { flags, offset })
+        }
+
         Opcode::MemorySize => {
             let memory_index = reader.read_leb128_u32()?;
             Ok(WasmInstruction::MemorySize(memory_index))
@@ -236,24 +340,28 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
             let memory_index = reader.read_leb128_u32()?;
             Ok(WasmInstruction::MemoryInit(segment_index, memory_index))
         }
+        Opcode::DataDrop => {
+            let segment_index = reader.read_leb128_u32()?;
+            Ok(WasmInstruction::DataDrop(segment_index))
+        }
         Opcode::MemoryCopy => {
-            let source_index = reader.read_leb128_u32()?;
             let destination_index = reader.read_leb128_u32()?;
-            Ok(WasmInstruction::MemoryCopy(source_index, destination_index))
+            let source_index = reader.read_leb128_u32()?;
+            Ok(WasmInstruction::MemoryCopy(destination_index, source_index))
         }
         Opcode::MemoryFill => {
             let memory_index = reader.read_leb128_u32()?;
             Ok(WasmInstruction::MemoryFill(memory_index))
         }
 
-        // Opcode::TableGet => {
-        //     let table_index = reader.read_leb128_u32()?;
-        //     Ok(WasmInstruction::TableGet(table_index))
-        // }
-        // Opcode::TableSet => {
-        //     let table_index = reader.read_leb128_u32()?;
-        //     Ok(WasmInstruction::TableSet(table_index))
-        // }
+        Opcode::TableGet => {
+            let table_index = reader.read_leb128_u32()?;
+            Ok(WasmInstruction::TableGet(table_index))
+        }
+        Opcode::TableSet => {
+            let table_index = reader.read_leb128_u32()?;
+            Ok(WasmInstruction::TableSet(table_index))
+        }
         Opcode::TableGrow => {
             let table_index = reader.read_leb128_u32()?;
             Ok(WasmInstruction::TableGrow(table_index))
@@ -263,8 +371,9 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
             Ok(WasmInstruction::TableSize(table_index))
         }
         Opcode::TableCopy => {
-            let from_index = reader.read_leb128_u32()?;
+            // like memory.copy, the destination comes first
             let to_index = reader.read_leb128_u32()?;
+            let from_index = reader.read_leb128_u32()?;
             Ok(WasmInstruction::TableCopy { from: from_index, to: to_index })
         }
         Opcode::TableInit => {
@@ -272,6 +381,10 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
             let table_index = reader.read_leb128_u32()?;
             Ok(WasmInstruction::TableInit(element_index, table_index))
         }
+        Opcode::ElemDrop => {
+            let segment_index = reader.read_leb128_u32()?;
+            Ok(WasmInstruction::ElemDrop(segment_index))
+        }
         Opcode::TableFill => {
             let table_index = reader.read_leb128_u32()?;
             Ok(WasmInstruction::TableFill(table_index))
@@ -283,31 +396,50 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
         }
         Opcode::RefIsNull => Ok(WasmInstruction::RefIsNull),
         Opcode::RefNull => {
-            let ref_type = reader.read_leb128_u32()?;
+            let ref_type = parse_ref_type(reader)?;
             Ok(WasmInstruction::RefNull(ref_type))
         }
 
-        // Opcode::TruncF32SI32 => Ok(WasmInstruction::TruncF32SI32),
-        // Opcode::TruncF32SI64 => Ok(WasmInstruction::TruncF32SI64),
-        // Opcode::TruncF32UI32 => Ok(WasmInstruction::TruncF32UI32),
-        // Opcode::TruncF32UI64 => Ok(WasmInstruction::TruncF32UI64),
-        // Opcode::TruncF64SI32 => Ok(WasmInstruction::TruncF64SI32),
-        // Opcode::TruncF64SI64 => Ok(WasmInstruction::TruncF64SI64),
-        // Opcode::TruncF64UI32 => Ok(WasmInstruction::TruncF64UI32),
-        // Opcode::TruncF64UI64 => Ok(WasmInstruction::TruncF64UI64),
+        Opcode::TruncSI32F32 => Ok(WasmInstruction::TruncF32SI32),
+        Opcode::TruncUI32F32 => Ok(WasmInstruction::TruncF32UI32),
+        Opcode::TruncSI32F64 => Ok(WasmInstruction::TruncF64SI32),
+        Opcode::TruncUI32F64 => Ok(WasmInstruction::TruncF64UI32),
+        Opcode::TruncSI64F32 => Ok(WasmInstruction::TruncF32SI64),
+        Opcode::TruncUI64F32 => Ok(WasmInstruction::TruncF32UI64),
+        Opcode::TruncSI64F64 => Ok(WasmInstruction::TruncF64SI64),
+        Opcode::TruncUI64F64 => Ok(WasmInstruction::TruncF64UI64),
+
+        Opcode::TruncSatSI32F32 => Ok(WasmInstruction::TruncSatF32SI32),
+        Opcode::TruncSatUI32F32 => Ok(WasmInstruction::TruncSatF32UI32),
+        Opcode::TruncSatSI32F64 => Ok(WasmInstruction::TruncSatF64SI32),
+        Opcode::TruncSatUI32F64 => Ok(WasmInstruction::TruncSatF64UI32),
+        Opcode::TruncSatSI64F32 => Ok(WasmInstruction::TruncSatF32SI64),
+        Opcode::TruncSatUI64F32 => Ok(WasmInstruction::TruncSatF32UI64),
+        Opcode::TruncSatSI64F64 => Ok(WasmInstruction::TruncSatF64SI64),
+        Opcode::TruncSatUI64F64 => Ok(WasmInstruction::TruncSatF64UI64),
+
+        Opcode::ConvertSI32F32 => Ok(WasmInstruction::ConvertI32SF32),
+        Opcode::ConvertUI32F32 => Ok(WasmInstruction::ConvertI32UF32),
+        Opcode::ConvertSI64F32 => Ok(WasmInstruction::ConvertI64SF32),
+        Opcode::ConvertUI64F32 => Ok(WasmInstruction::ConvertI64UF32),
+        Opcode::ConvertSI32F64 => Ok(WasmInstruction::ConvertI32SF64),
+        Opcode::ConvertUI32F64 => Ok(WasmInstruction::ConvertI32UF64),
+        Opcode::ConvertSI64F64 => Ok(WasmInstruction::ConvertI64SF64),
+        Opcode::ConvertUI64F64 => Ok(WasmInstruction::ConvertI64UF64),
 
         Opcode::PromoteF32F64 => Ok(WasmInstruction::PromoteF32F64),
-        // Opcode::DemoteF64F32 => Ok(WasmInstruction::DemoteF64F32),
+        Opcode::DemoteF32F64 => Ok(WasmInstruction::DemoteF64F32),
 
-        Opcode::ReinterpretF32I32 => Ok(WasmInstruction::ReinterpretF32I32),
-        Opcode::ReinterpretF64I64 => Ok(WasmInstruction::ReinterpretF64I64),
-        Opcode::ReinterpretI32F32 => Ok(WasmInstruction::ReinterpretI32F32),
-        Opcode::ReinterpretI64F64 => Ok(WasmInstruction::ReinterpretI64F64),
+        // the opcode is named after the result type first, the instruction after the operand type first
+        Opcode::ReinterpretI32F32 => Ok(WasmInstruction::ReinterpretF32I32),
+        Opcode::ReinterpretI64F64 => Ok(WasmInstruction::ReinterpretF64I64),
+        Opcode::ReinterpretF32I32 => Ok(WasmInstruction::ReinterpretI32F32),
+        Opcode::ReinterpretF64I64 => Ok(WasmInstruction::ReinterpretI64F64),
 
         Opcode::WrapI32I64 => Ok(WasmInstruction::WrapI32I64),
 
-        // Opcode::ExtendI32SI64 => Ok(WasmInstruction::ExtendI32SI64),
-        // Opcode::ExtendI32UI64 => Ok(WasmInstruction::ExtendI32UI64),
+        Opcode::ExtendSI64I32 => Ok(WasmInstruction::ExtendI32SI64),
+        Opcode::ExtendUI64I32 => Ok(WasmInstruction::ExtendI32UI64),
         Opcode::Extend16SI32 => Ok(WasmInstruction::Extend16SI32),
         Opcode::Extend16SI64 => Ok(WasmInstruction::Extend16SI64),
         Opcode::Extend32SI64 => Ok(WasmInstruction::Extend32SI64),
@@ -315,30 +447,7 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
         Opcode::Extend8SI64 => Ok(WasmInstruction::Extend8SI64),
 
         // _ => Err(WasmParseError::UnsupportedOpcode(op))
-        Opcode::Nop => todo!(),
-        Opcode::If => todo!(),
-        Opcode::Try => todo!(),
-        Opcode::Catch => todo!(),
-        Opcode::Throw => todo!(),
-        Opcode::Rethrow => todo!(),
-        Opcode::Return => todo!(),
-        Opcode::Select => todo!(),
-        Opcode::SelectT => todo!(),
-        Opcode::Load8SI32 => todo!(),
-        Opcode::Load8UI32 => todo!(),
-        Opcode::Load16SI32 => todo!(),
-        Opcode::Load16UI32 => todo!(),
-        Opcode::Load8SI64 => todo!(),
-        Opcode::Load8UI64 => todo!(),
-        Opcode::Load16SI64 => todo!(),
-        Opcode::Load16UI64 => todo!(),
-        Opcode::Load32SI64 => todo!(),
-        Opcode::Load32UI64 => todo!(),
-        Opcode::Store8I32 => todo!(),
-        Opcode::Store16I32 => todo!(),
-        Opcode::Store8I64 => todo!(),
-        Opcode::Store16I64 => todo!(),
-        Opcode::Store32I64 => todo!(),
+        Opcode::Try | Opcode::Catch | Opcode::Throw | Opcode::Rethrow => Err(features.unsupported(WasmFeature::Exceptions)),
         Opcode::LtSI32 => Ok(WasmInstruction::LtSI32),
         Opcode::LtUI32 => Ok(WasmInstruction::LtUI32),
         Opcode::GtSI32 => Ok(WasmInstruction::GtSI32),
@@ -369,37 +478,6 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
         Opcode::RemUI64 => Ok(WasmInstruction::RemUI64),
         Opcode::XorI32 => Ok(WasmInstruction::XorI32),
         Opcode::XorI64 => Ok(WasmInstruction::XorI64),
-        Opcode::TruncF32 => todo!(),
-        Opcode::NearestF32 => todo!(),
-        Opcode::DivF32 => todo!(),
-        Opcode::MinF32 => todo!(),
-        Opcode::MaxF32 => todo!(),
-        Opcode::TruncF64 => todo!(),
-        Opcode::NearestF64 => todo!(),
-        Opcode::DivF64 => todo!(),
-        Opcode::MinF64 => todo!(),
-        Opcode::MaxF64 => todo!(),
-        Opcode::TruncSI32F32 => todo!(),
-        Opcode::TruncUI32F32 => todo!(),
-        Opcode::TruncSI32F64 => todo!(),
-        Opcode::TruncUI32F64 => todo!(),
-        Opcode::ExtendSI64I32 => todo!(),
-        Opcode::ExtendUI64I32 => todo!(),
-        Opcode::TruncSI64F32 => todo!(),
-        Opcode::TruncUI64F32 => todo!(),
-        Opcode::TruncSI64F64 => todo!(),
-        Opcode::TruncUI64F64 => todo!(),
-        Opcode::ConvertSI32F32 => todo!(),
-        Opcode::ConvertUI32F32 => todo!(),
-        Opcode::ConvertSI64F32 => todo!(),
-        Opcode::ConvertUI64F32 => todo!(),
-        Opcode::DemoteF32F64 => todo!(),
-        Opcode::ConvertSI32F64 => todo!(),
-        Opcode::ConvertUI32F64 => todo!(),
-        Opcode::ConvertSI64F64 => todo!(),
-        Opcode::ConvertUI64F64 => todo!(),
-        Opcode::DataDrop => todo!(),
-        Opcode::ElemDrop => todo!(),
         Opcode::LoadV128 => todo!(),
         Opcode::StoreV128 => todo!(),
         Opcode::SplatI8x16 => todo!(),
@@ -422,7 +500,22 @@ pub(crate) fn parse_instruction(reader: &ByteReader) -> Result<WasmInstruction>
         Opcode::ReplaceLaneI64x2 => todo!(),
         Opcode::ReplaceLaneF32x4 => todo!(),
         Opcode::ReplaceLaneF64x2 => todo!(),
+    };
+
+    let inst = inst?;
+    features.check(&inst).map_err(FeatureDisabled)?;
+    code.push(inst);
+    Ok(())
+}
+
+// the alignment exponent is below 32, bit 6 selecting a memory belongs to multi-memory which is not decoded yet
+fn parse_memarg(reader: &ByteReader) -> Result<(u32, u32)> {
+    let flags = reader.read_leb128_u32()?;
+    if flags >= 32 {
+        return Err(InvalidMemoryFlags(flags));
     }
+    let offset = reader.read_leb128_u32()?;
+    Ok((flags, offset))
 }
 
 #[cfg(test)]
@@ -449,4 +542,24 @@ mod tests {
                 ]),
             }]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_narrow_load() {
+        let wasm = hal_wat::WatParser::parse_str("(module (func (drop (i64.load16_s offset=8 (i32.const 4)))))").unwrap();
+        let result = WasmParser::parse(&wasm).unwrap();
+        assert_eq!(result.codes.as_ref(), [
+            WasmFunctionBody {
+                locals: Box::default(),
+                code: Box::new([
+                    WasmInstruction::ConstI32(4),
+                    WasmInstruction::Load16SI64 {
+                        flags: 1,
+                        offset: 8,
+                    },
+                    WasmInstruction::Drop,
+                    WasmInstruction::End,
+                ]),
+            }]);
+    }
+}
+
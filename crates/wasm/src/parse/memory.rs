@@ -3,16 +3,17 @@ use alloc::vec;
 
 use hal_core::reader::ByteReader;
 
+use crate::features::{WasmFeature, WasmFeatures};
 use crate::module::{WasmMemory, WasmResizableLimit};
 use crate::Result;
 
-pub(crate) fn parse_memory_section(size: u32, reader: &ByteReader) -> Result<Box<[WasmMemory]>> {
+pub(crate) fn parse_memory_section(size: u32, reader: &ByteReader, features: &WasmFeatures) -> Result<Box<[WasmMemory]>> {
     let expected_reader_pos = reader.pos() + size as usize;
     let count = reader.read_leb128_u32()?;
     let mut result = vec![];
 
     for _ in 0..count {
-        let limits = parse_limits(reader)?;
+        let limits = parse_memory_limits(reader, features)?;
         result.push(WasmMemory { limits })
     }
 
@@ -20,8 +21,24 @@ pub(crate) fn parse_memory_section(size: u32, reader: &ByteReader) -> Result<Box
     Ok(result.into())
 }
 
-fn parse_limits(reader: &ByteReader) -> Result<WasmResizableLimit> {
+pub(crate) fn parse_limits(reader: &ByteReader) -> Result<WasmResizableLimit> {
     let flags = reader.read_leb128_u32()?;
+    parse_bounds(flags, reader)
+}
+
+pub(crate) fn parse_memory_limits(reader: &ByteReader, features: &WasmFeatures) -> Result<WasmResizableLimit> {
+    // bit 0: maximum present, bit 1: shared memory, bit 2: 64-bit addresses
+    let flags = reader.read_leb128_u32()?;
+    if flags & 0b100 != 0 {
+        return Err(features.unsupported(WasmFeature::Memory64));
+    }
+    if flags & 0b010 != 0 {
+        return Err(features.unsupported(WasmFeature::Threads));
+    }
+    parse_bounds(flags, reader)
+}
+
+fn parse_bounds(flags: u32, reader: &ByteReader) -> Result<WasmResizableLimit> {
     let min = reader.read_leb128_u32()?;
 
     let max = if flags == 0 {
@@ -36,7 +53,8 @@ fn parse_limits(reader: &ByteReader) -> Result<WasmResizableLimit> {
 
 #[cfg(test)]
 mod tests {
-    use crate::module::{WasmMemory, WasmResizableLimit};
+    use crate::features::{WasmFeature, WasmFeatures};
+use crate::module::{WasmMemory, WasmResizableLimit};
     use crate::parse::WasmParser;
 
     #[test]
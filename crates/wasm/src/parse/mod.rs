@@ -5,39 +5,54 @@ use WasmParseError::InvalidSectionCode;
 
 use crate::error::WasmParseError;
 use crate::error::WasmParseError::{InvalidMagicNumber, UnsupportedVersion};
+use crate::features::{WasmFeature, WasmFeatures};
 use crate::module::WasmModule;
 use crate::parse::code::parse_code_section;
 use crate::parse::custom::parse_custom_section;
-use crate::parse::data::parse_data_section;
+use crate::parse::data::{parse_data_count_section, parse_data_section};
+use crate::parse::element::parse_element_section;
 use crate::parse::export::parse_export_section;
 use crate::parse::function::parse_functions_section;
+use crate::parse::global::parse_global_section;
 use crate::parse::import::parse_import_section;
 use crate::parse::memory::parse_memory_section;
 use crate::parse::r#type::parse_types_section;
+use crate::parse::start::parse_start_section;
+use crate::parse::table::parse_table_section;
 use crate::Result;
 
 mod code;
 mod custom;
 mod data;
+mod element;
 mod export;
+mod expr;
 mod function;
+mod global;
 mod import;
 mod instruction;
 mod memory;
 mod name;
 mod r#type;
+mod start;
+mod table;
 mod value;
 
 
-pub enum SectionCode {
+pub(crate) enum SectionCode {
     Custom = 0x00,
     Type = 0x01,
     Import = 0x02,
     Function = 0x03,
+    Table = 0x04,
     Memory = 0x05,
+    Global = 0x06,
     Export = 0x07,
+    Start = 0x08,
+    Element = 0x09,
     Code = 0x0a,
     Data = 0x0b,
+    DataCount = 0x0c,
 }
 
 impl SectionCode {
@@ -47,10 +62,15 @@ impl SectionCode {
             0x01 => Ok(SectionCode::Type),
             0x02 => Ok(SectionCode::Import),
             0x03 => Ok(SectionCode::Function),
+            0x04 => Ok(SectionCode::Table),
             0x05 => Ok(SectionCode::Memory),
+            0x06 => Ok(SectionCode::Global),
             0x07 => Ok(SectionCode::Export),
+            0x08 => Ok(SectionCode::Start),
+            0x09 => Ok(SectionCode::Element),
             0x0a => Ok(SectionCode::Code),
             0x0b => Ok(SectionCode::Data),
+            0x0c => Ok(SectionCode::DataCount),
             _ => Err(InvalidSectionCode(value)),
         }
     }
@@ -73,6 +93,12 @@ impl WasmParser {
     /// if any part of the decoding process fails (e.g., due to an unexpected end of file or
     /// invalid data).
     pub fn parse(input: &[u8]) -> Result<WasmModule> {
+        Self::parse_with_features(input, &WasmFeatures::default())
+    }
+
+    /// Decodes the WASM module from the byte stream like [`WasmParser::parse`], rejecting
+    /// instructions and encodings of proposals which are disabled in `features`.
+    pub fn parse_with_features(input: &[u8], features: &WasmFeatures) -> Result<WasmModule> {
         let reader = ByteReader::new(input);
         let magic = Self::parse_magic(&reader)?;
         let version = Self::parse_version(&reader)?;
@@ -86,11 +112,13 @@ impl WasmParser {
             functions: Box::default(),
             tables: Box::default(),
             memories: Box::default(),
+            globals: Box::default(),
             exports: Box::default(),
             start_function: None,
             elements: Box::default(),
             codes: Box::default(),
             data: Box::default(),
+            data_count: None,
         };
 
         while !reader.eof() {
@@ -103,22 +131,38 @@ impl WasmParser {
                     result.types = parse_types_section(size, &reader)?
                 }
                 SectionCode::Import => {
-                    result.imports = parse_import_section(size, &reader)?
+                    result.imports = parse_import_section(size, &reader, features)?
                 }
                 SectionCode::Function => {
                     result.functions = parse_functions_section(size, &reader)?
                 }
+                SectionCode::Table => {
+                    result.tables = parse_table_section(size, &reader)?
+                }
                 SectionCode::Memory => {
-                    result.memories = parse_memory_section(size, &reader)?
+                    result.memories = parse_memory_section(size, &reader, features)?
+                }
+                SectionCode::Global => {
+                    result.globals = parse_global_section(size, &reader, features)?
                 }
                 SectionCode::Export => {
                     result.exports = parse_export_section(size, &reader)?
                 }
+                SectionCode::Start => {
+                    result.start_function = Some(parse_start_section(size, &reader)?)
+                }
+                SectionCode::Element => {
+                    result.elements = parse_element_section(size, &reader, features)?
+                }
                 SectionCode::Code => {
-                    result.codes = parse_code_section(size, &reader)?
+                    result.codes = parse_code_section(size, &reader, features)?
                 }
                 SectionCode::Data => {
-                    result.data = parse_data_section(size, &reader)?
+                    result.data = parse_data_section(size, &reader, features)?
+                }
+                SectionCode::DataCount => {
+                    features.require(WasmFeature::BulkMemory)?;
+                    result.data_count = Some(parse_data_count_section(size, &reader)?)
                 }
             }
         }
@@ -2,10 +2,13 @@ use alloc::boxed::Box;
 
 use hal_core::reader::ByteReader;
 
+use crate::error::WasmParseError::InvalidUtf8String;
 use crate::Result;
 
+// names stay bytes, but are checked to be UTF-8 so they can be converted to strings without failing
 pub(crate) fn parse_name<'a>(reader: &'a ByteReader) -> Result<Box<[u8]>> {
     let size = reader.read_leb128_u32()?;
     let name = reader.read_range(size as usize)?;
+    core::str::from_utf8(&name).map_err(|_| InvalidUtf8String)?;
     Ok(name)
 }
\ No newline at end of file
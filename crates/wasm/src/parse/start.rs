@@ -0,0 +1,11 @@
+use hal_core::reader::ByteReader;
+
+use crate::Result;
+
+pub(crate) fn parse_start_section(size: u32, reader: &ByteReader) -> Result<u32> {
+    let expected_reader_pos = reader.pos() + size as usize;
+    let function_index = reader.read_leb128_u32()?;
+
+    debug_assert_eq!(reader.pos(), expected_reader_pos);
+    Ok(function_index)
+}
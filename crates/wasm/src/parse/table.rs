@@ -0,0 +1,36 @@
+use alloc::boxed::Box;
+use alloc::vec;
+
+use hal_core::reader::ByteReader;
+
+use crate::error::WasmParseError::InvalidElementType;
+use crate::module::{WasmTable, WasmValueType};
+use crate::parse::memory::parse_limits;
+use crate::Result;
+
+pub(crate) fn parse_table_section(size: u32, reader: &ByteReader) -> Result<Box<[WasmTable]>> {
+    let expected_reader_pos = reader.pos() + size as usize;
+    let count = reader.read_leb128_u32()?;
+    let mut result = vec![];
+
+    for _ in 0..count {
+        result.push(parse_table_type(reader)?);
+    }
+
+    debug_assert_eq!(reader.pos(), expected_reader_pos);
+    Ok(result.into())
+}
+
+pub(crate) fn parse_table_type(reader: &ByteReader) -> Result<WasmTable> {
+    let element_type = parse_ref_type(reader)?;
+    let limits = parse_limits(reader)?;
+    Ok(WasmTable { element_type, limits })
+}
+
+pub(crate) fn parse_ref_type(reader: &ByteReader) -> Result<WasmValueType> {
+    match reader.read_u8()? {
+        0x70 => Ok(WasmValueType::FuncRef),
+        0x6F => Ok(WasmValueType::ExternRef),
+        ref_type => Err(InvalidElementType(ref_type)),
+    }
+}
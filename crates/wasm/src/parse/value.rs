@@ -3,10 +3,38 @@ use alloc::vec;
 
 use hal_core::reader::ByteReader;
 
-use crate::error::WasmParseError::InvalidValueType;
+use crate::error::WasmParseError::{InvalidBlockType, InvalidValueType, UnexpectedEndOfFile};
 use crate::module::WasmValueType;
 use crate::Result;
 
+/// The type of a structured control instruction (`block`, `loop` or `if`).
+pub(crate) enum BlockType {
+    /// No parameters and no results.
+    Empty,
+    /// No parameters and a single result.
+    Value(WasmValueType),
+    /// Parameters and results as described by a function type.
+    Func(u32),
+}
+
+pub(crate) fn parse_block_type(reader: &ByteReader) -> Result<BlockType> {
+    let value = *reader.peek_range(1)?.first().ok_or(UnexpectedEndOfFile)?;
+    if value == 0x40 {
+        reader.read_u8()?;
+        return Ok(BlockType::Empty);
+    }
+
+    if let Ok(value_type) = value_type_from_u8(value) {
+        reader.read_u8()?;
+        return Ok(BlockType::Value(value_type));
+    }
+
+    let type_index = reader.read_leb128_i64()?;
+    u32::try_from(type_index)
+        .map(BlockType::Func)
+        .map_err(|_| InvalidBlockType(type_index))
+}
+
 pub(crate) fn parse_value_type(reader: &ByteReader) -> Result<WasmValueType> {
     let value_type = reader.read_u8()?;
     Ok(value_type_from_u8(value_type)?)
@@ -24,6 +52,10 @@ fn value_type_from_u8(value: u8) -> Result<WasmValueType> {
     match value {
         0x7F => Ok(WasmValueType::I32),
         0x7E => Ok(WasmValueType::I64),
+        0x7D => Ok(WasmValueType::F32),
+        0x7C => Ok(WasmValueType::F64),
+        0x70 => Ok(WasmValueType::FuncRef),
+        0x6F => Ok(WasmValueType::ExternRef),
         _ => Err(InvalidValueType(value)),
     }
 }
@@ -7,7 +7,7 @@ use hal_wasm;
 mod tests {
     use alloc::boxed::Box;
 
-    use hal_wasm::{WasmData, WasmExport, WasmExportDescriptor, WasmFunc, WasmFunctionBody, WasmImport, WasmImportDescriptor, WasmInstruction, WasmMemory, WasmParser, WasmResizableLimit, WasmValueType};
+    use hal_wasm::{WasmConstExpr, WasmData, WasmDataMode, WasmElement, WasmElementMode, WasmExport, WasmExportDescriptor, WasmFeature, WasmFeatures, WasmFunc, WasmGlobal, WasmGlobalType, WasmFunctionBody, WasmImport, WasmImportDescriptor, WasmInstruction, WasmMemory, WasmParseError, WasmParser, WasmResizableLimit, WasmTable, WasmValue, WasmValueType};
 
     #[test]
     fn parse_empty_module() {
@@ -310,18 +310,329 @@ mod tests {
         assert_eq!(result.codes.as_ref(), []);
         assert_eq!(result.data.as_ref(), [
             WasmData {
-                memory_index: 0,
-                offset: 0,
+                mode: WasmDataMode::Active { memory_index: 0, offset: WasmConstExpr::Const(WasmValue::I32(0)) },
                 data: Box::from("hello".as_bytes()),
             },
             WasmData {
-                memory_index: 0,
-                offset: 5,
+                mode: WasmDataMode::Active { memory_index: 0, offset: WasmConstExpr::Const(WasmValue::I32(5)) },
                 data: Box::from("world".as_bytes()),
             },
         ]);
     }
 
+    #[test]
+    fn parse_data_modes() {
+        let wasm = wasm(r#"
+        (module
+          (memory 1)
+          (data "passive")
+          (data (memory 0) (offset (i32.const 8)) "explicit")
+          (func (memory.init 0 (i32.const 0) (i32.const 0) (i32.const 0)) (data.drop 0))
+        )
+        "#);
+        let result = WasmParser::parse(&wasm).unwrap();
+        assert_eq!(result.data_count, Some(2));
+        assert_eq!(result.data.as_ref(), [
+            WasmData {
+                mode: WasmDataMode::Passive,
+                data: Box::from("passive".as_bytes()),
+            },
+            WasmData {
+                mode: WasmDataMode::Active { memory_index: 0, offset: WasmConstExpr::Const(WasmValue::I32(8)) },
+                data: Box::from("explicit".as_bytes()),
+            },
+        ]);
+        assert_eq!(result.codes[0].code.as_ref(), [
+            WasmInstruction::ConstI32(0),
+            WasmInstruction::ConstI32(0),
+            WasmInstruction::ConstI32(0),
+            WasmInstruction::MemoryInit(0, 0),
+            WasmInstruction::DataDrop(0),
+            WasmInstruction::End,
+        ]);
+    }
+
+    #[test]
+    fn parse_globals() {
+        let wasm = wasm(r#"
+        (module
+          (global i32 (i32.const 42))
+          (global (mut f64) (f64.const 1.5))
+          (global i32 (global.get 0))
+        )
+        "#);
+        let result = WasmParser::parse(&wasm).unwrap();
+        assert_eq!(result.globals.as_ref(), [
+            WasmGlobal {
+                global_type: WasmGlobalType { value_type: WasmValueType::I32, mutable: false },
+                init: WasmConstExpr::Const(WasmValue::I32(42)),
+            },
+            WasmGlobal {
+                global_type: WasmGlobalType { value_type: WasmValueType::F64, mutable: true },
+                init: WasmConstExpr::Const(WasmValue::F64(1.5)),
+            },
+            WasmGlobal {
+                global_type: WasmGlobalType { value_type: WasmValueType::I32, mutable: false },
+                init: WasmConstExpr::GlobalGet(0),
+            },
+        ]);
+    }
+
+    #[test]
+    fn parse_tables_and_elements() {
+        let wasm = wasm(r#"
+        (module
+          (table $t 2 10 funcref)
+          (table $u 1 externref)
+          (func $f)
+          (elem (i32.const 1) $f)
+          (elem func $f $f)
+          (elem declare func $f)
+          (elem (table $t) (offset (i32.const 0)) funcref (ref.null func) (ref.func $f))
+          (elem externref (ref.null extern))
+        )
+        "#);
+        let result = WasmParser::parse(&wasm).unwrap();
+        assert_eq!(result.tables.as_ref(), [
+            WasmTable { element_type: WasmValueType::FuncRef, limits: WasmResizableLimit { min: 2, max: Some(10) } },
+            WasmTable { element_type: WasmValueType::ExternRef, limits: WasmResizableLimit { min: 1, max: None } },
+        ]);
+        assert_eq!(result.elements.as_ref(), [
+            WasmElement {
+                mode: WasmElementMode::Active { table_index: 0, offset: WasmConstExpr::Const(WasmValue::I32(1)) },
+                element_type: WasmValueType::FuncRef,
+                init: Box::new([WasmConstExpr::RefFunc(0)]),
+            },
+            WasmElement {
+                mode: WasmElementMode::Passive,
+                element_type: WasmValueType::FuncRef,
+                init: Box::new([WasmConstExpr::RefFunc(0), WasmConstExpr::RefFunc(0)]),
+            },
+            WasmElement {
+                mode: WasmElementMode::Declarative,
+                element_type: WasmValueType::FuncRef,
+                init: Box::new([WasmConstExpr::RefFunc(0)]),
+            },
+            WasmElement {
+                mode: WasmElementMode::Active { table_index: 0, offset: WasmConstExpr::Const(WasmValue::I32(0)) },
+                element_type: WasmValueType::FuncRef,
+                init: Box::new([WasmConstExpr::RefNull(WasmValueType::FuncRef), WasmConstExpr::RefFunc(0)]),
+            },
+            WasmElement {
+                mode: WasmElementMode::Passive,
+                element_type: WasmValueType::ExternRef,
+                init: Box::new([WasmConstExpr::RefNull(WasmValueType::ExternRef)]),
+            },
+        ]);
+    }
+
+    #[test]
+    fn parse_table_instructions() {
+        let wasm = wasm(r#"
+        (module
+          (table $a 1 funcref)
+          (table $b 1 funcref)
+          (elem $e func)
+          (func
+            (table.set $b (i32.const 0) (table.get $a (i32.const 0)))
+            (table.copy $b $a (i32.const 0) (i32.const 0) (i32.const 1))
+            (table.init $b $e (i32.const 0) (i32.const 0) (i32.const 0))
+            (elem.drop $e)
+          )
+        )
+        "#);
+        let result = WasmParser::parse(&wasm).unwrap();
+        assert_eq!(result.codes[0].code.as_ref(), [
+            WasmInstruction::ConstI32(0),
+            WasmInstruction::ConstI32(0),
+            WasmInstruction::TableGet(0),
+            WasmInstruction::TableSet(1),
+            WasmInstruction::ConstI32(0),
+            WasmInstruction::ConstI32(0),
+            WasmInstruction::ConstI32(1),
+            WasmInstruction::TableCopy { from: 0, to: 1 },
+            WasmInstruction::ConstI32(0),
+            WasmInstruction::ConstI32(0),
+            WasmInstruction::ConstI32(0),
+            WasmInstruction::TableInit(0, 1),
+            WasmInstruction::ElemDrop(0),
+            WasmInstruction::End,
+        ]);
+    }
+
+    #[test]
+    fn parse_import_kinds() {
+        let wasm = wasm(r#"
+        (module
+          (import "env" "table" (table 1 2 funcref))
+          (import "env" "memory" (memory 1))
+          (import "env" "__stack_pointer" (global (mut i32)))
+          (import "env" "limit" (global i64))
+        )
+        "#);
+        let result = WasmParser::parse(&wasm).unwrap();
+        let descriptors = result.imports.iter().map(|import| &import.desc).collect::<Vec<_>>();
+        assert_eq!(descriptors, [
+            &WasmImportDescriptor::Table(WasmTable {
+                element_type: WasmValueType::FuncRef,
+                limits: WasmResizableLimit { min: 1, max: Some(2) },
+            }),
+            &WasmImportDescriptor::Memory(WasmMemory { limits: WasmResizableLimit { min: 1, max: None } }),
+            &WasmImportDescriptor::Global(WasmGlobalType { value_type: WasmValueType::I32, mutable: true }),
+            &WasmImportDescriptor::Global(WasmGlobalType { value_type: WasmValueType::I64, mutable: false }),
+        ]);
+    }
+
+    #[test]
+    fn parse_reference_instructions() {
+        let wasm = wasm(r#"
+        (module
+          (func $f (param externref) (result funcref)
+            (drop (ref.is_null (local.get 0)))
+            (drop (select (result externref) (ref.null extern) (local.get 0) (i32.const 1)))
+            (drop (select (result i64) (i64.const 1) (i64.const 2) (i32.const 1)))
+            (ref.func $f)
+          )
+        )
+        "#);
+        let result = WasmParser::parse(&wasm).unwrap();
+        assert_eq!(result.codes[0].code.as_ref(), [
+            WasmInstruction::LocalGet32(0),
+            WasmInstruction::RefIsNull,
+            WasmInstruction::Drop,
+            WasmInstruction::RefNull(WasmValueType::ExternRef),
+            WasmInstruction::LocalGet32(0),
+            WasmInstruction::ConstI32(1),
+            WasmInstruction::SelectRef,
+            WasmInstruction::Drop,
+            WasmInstruction::ConstI64(1),
+            WasmInstruction::ConstI64(2),
+            WasmInstruction::ConstI32(1),
+            WasmInstruction::Select64,
+            WasmInstruction::Drop,
+            WasmInstruction::RefFunc(0),
+            WasmInstruction::End,
+        ]);
+    }
+
+    #[test]
+    fn parse_control_flow() {
+        let wasm = wasm(r#"
+        (module
+          (func (param i32) (result i32)
+            (block (result i32)
+              (loop
+                (br_table 0 1 1 (local.get 0))
+              )
+              (i32.const 1)
+            )
+            (if (result i32) (local.get 0)
+              (then (i32.const 2))
+              (else (i32.const 3))
+            )
+            (drop)
+          )
+        )
+        "#);
+        let result = WasmParser::parse(&wasm).unwrap();
+        assert_eq!(result.codes.as_ref(), [WasmFunctionBody {
+            locals: Box::default(),
+            code: Box::new([
+                WasmInstruction::BlockWithType(WasmValueType::I32, 8),
+                WasmInstruction::Loop(6),
+                WasmInstruction::LocalGet32(0),
+                WasmInstruction::BrTable(2, 1),
+                WasmInstruction::BrLabel(0),
+                WasmInstruction::BrLabel(1),
+                WasmInstruction::End,
+                WasmInstruction::ConstI32(1),
+                WasmInstruction::End,
+                WasmInstruction::LocalGet32(0),
+                WasmInstruction::IfWithType(WasmValueType::I32, 12),
+                WasmInstruction::ConstI32(2),
+                WasmInstruction::Else(14),
+                WasmInstruction::ConstI32(3),
+                WasmInstruction::End,
+                WasmInstruction::Drop,
+                WasmInstruction::End,
+            ]),
+        }]);
+    }
+
+    #[test]
+    fn parse_start_function() {
+        let wasm = wasm("(module (func) (func $main) (start $main))");
+        let result = WasmParser::parse(&wasm).unwrap();
+        assert_eq!(result.start_function, Some(1));
+    }
+
+    #[test]
+    fn too_many_locals() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00,
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+            0x03, 0x02, 0x01, 0x00,
+            // two groups of locals, 0xFFFFFFFF i32 and 2 i64
+            0x0A, 0x0C, 0x01, 0x0A, 0x02, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0x7F, 0x02, 0x7E, 0x0B,
+        ];
+        assert_eq!(WasmParser::parse(&wasm).err(), Some(WasmParseError::TooManyLocals));
+    }
+
+    #[test]
+    fn invalid_utf8_name() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00,
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+            // a function import from the module "\xFF"
+            0x02, 0x07, 0x01, 0x01, 0xFF, 0x01, 0x66, 0x00, 0x00,
+        ];
+        assert_eq!(WasmParser::parse(&wasm).err(), Some(WasmParseError::InvalidUtf8String));
+    }
+
+    #[test]
+    fn invalid_memory_flags() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00,
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+            0x03, 0x02, 0x01, 0x00,
+            0x05, 0x03, 0x01, 0x00, 0x01,
+            // i32.load with an alignment exponent of 32
+            0x0A, 0x0A, 0x01, 0x08, 0x00, 0x41, 0x00, 0x28, 0x20, 0x00, 0x1A, 0x0B,
+        ];
+        assert_eq!(WasmParser::parse(&wasm).err(), Some(WasmParseError::InvalidMemoryFlags(32)));
+    }
+
+    #[test]
+    fn disabled_features() {
+        let mvp = |wat| WasmParser::parse_with_features(&wasm(wat), &WasmFeatures::mvp()).err();
+
+        assert_eq!(mvp("(module (memory 1) (func (memory.fill (i32.const 0) (i32.const 0) (i32.const 0))))"), Some(WasmParseError::FeatureDisabled(WasmFeature::BulkMemory)));
+        assert_eq!(mvp("(module (func (drop (ref.null func))))"), Some(WasmParseError::FeatureDisabled(WasmFeature::ReferenceTypes)));
+        assert_eq!(mvp("(module (func (i32.const 1) (block (param i32) (drop))))"), Some(WasmParseError::FeatureDisabled(WasmFeature::MultiValue)));
+        assert_eq!(mvp("(module (memory 1) (data (i32.const 0) \"a\") (func (data.drop 0)))"), Some(WasmParseError::FeatureDisabled(WasmFeature::BulkMemory)));
+        assert_eq!(mvp("(module (memory 1 2 shared))"), Some(WasmParseError::FeatureDisabled(WasmFeature::Threads)));
+    }
+
+    #[test]
+    fn default_features() {
+        let parse = |wat| WasmParser::parse(&wasm(wat)).err();
+
+        assert_eq!(parse("(module (func (return_call 0)))"), Some(WasmParseError::FeatureDisabled(WasmFeature::TailCall)));
+        assert_eq!(parse("(module (func (drop (v128.const i32x4 0 0 0 0))))"), Some(WasmParseError::FeatureDisabled(WasmFeature::Simd)));
+        assert_eq!(parse("(module (memory 1) (memory 1))"), None);
+    }
+
+    #[test]
+    fn enabled_features() {
+        let features = WasmFeatures { tail_call: true, simd: true, ..WasmFeatures::default() };
+
+        let result = WasmParser::parse_with_features(&wasm("(module (func (return_call 0)))"), &features).unwrap();
+        assert_eq!(result.codes[0].code.as_ref(), [WasmInstruction::ReturnCall(0), WasmInstruction::End]);
+
+        let result = WasmParser::parse_with_features(&wasm("(module (func (drop (v128.const i32x4 0 0 0 0))))"), &features);
+        assert_eq!(result.err(), Some(WasmParseError::UnsupportedFeature(WasmFeature::Simd)));
+    }
+
     fn wasm(content: &str) -> Box<[u8]> {
         hal_wat::WatParser::parse_str(content).unwrap()
     }